@@ -0,0 +1,2556 @@
+use crate::cache;
+use crate::data_types::count_regression::{CountModel, CountRegression};
+use crate::data_types::data_array::{CategoricalDataArray, ContinuousDataArray};
+use crate::data_types::linear_model::LinearModel;
+use crate::data_types::multiple_regression::MultipleRegression;
+use crate::data_types::multiple_response::MultipleResponse;
+use crate::data_types::ordinal_regression::OrdinalRegression;
+use crate::data_types::outliers::{column_outlier_rows, OutlierMethod};
+use crate::data_types::statistics::{
+    Correlation, IndependentGroupsT, KruskalWallis, MannWhitneyU, PairedSamplesT, SingleSampleT,
+    WilcoxonSignedRank, ZTest, ANOVA,
+};
+use crate::functions::csv::{
+    import_csv_data, import_csv_sample, CSVData, CSVStream, Delimiter, JoinKind, MissingPolicy,
+};
+use crate::functions::distributions::{
+    chi_square_quantile, critical_values, f_quantile, standard_normal_quantile, t_quantile, Tails,
+};
+use crate::functions::filter::Filter;
+use crate::functions::formula::{Design, Formula};
+use crate::functions::graph::{
+    boxplot, default_bins, graph, histogram, image_graph, residual_plots, Graph, PlotBackend,
+};
+use crate::functions::json::{import_json_data, is_json_file, InputFormat};
+use crate::functions::recode::{
+    collapse_sparse_levels, recode_column, set_reference_level, Recode,
+};
+use crate::functions::sampling::clock_seed;
+use crate::functions::transform::{transform_column, Transform};
+use crate::logging::{self, default_title_width, TitleStyle};
+use crate::overview::{print_columns, Overview, OverviewFormat};
+use crate::provenance::{sha256_hex, Provenance};
+use crate::report::{write_report, ReportFormat};
+use crate::reporter::{
+    capture_output, report, report_message, report_warning, JsonReporter, LogReporter, Reporter,
+    SilentReporter,
+};
+use crate::scan::Scan;
+use crate::stream::Stream;
+use crate::timing::{time_export, PhaseTimer, EXPORT_PHASE};
+use anyhow::{anyhow, Error, Result};
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Separates chained statistics on a `configure` command line.
+const CHAIN_SEPARATOR: &str = "+";
+/// A column name this many edits from a header (or more, for long names) is suggested as
+/// a typo of it.
+const HEADER_TYPO_DISTANCE: usize = 2;
+
+#[derive(Parser, Debug)]
+#[command(name = "stisty", version, about = "A statistics CLI", long_about = None)]
+pub struct Cli {
+    /// Where statistic output goes
+    #[arg(long, value_enum, default_value_t = ReporterKind::Log, global = true)]
+    pub reporter: ReporterKind,
+
+    /// Banner width in characters [default: 70, or narrower to fit the terminal]
+    #[arg(long, global = true)]
+    pub title_width: Option<usize>,
+
+    /// Character banners are drawn with
+    #[arg(long, default_value_t = '=', global = true)]
+    pub title_fill: char,
+
+    /// Print titles without decorative banners
+    #[arg(long, default_value_t = false, global = true)]
+    pub no_banners: bool,
+
+    /// How charts are drawn
+    #[arg(long, value_enum, default_value_t = PlotBackend::Html, global = true)]
+    pub plot_backend: PlotBackend,
+
+    /// Omit to launch the interactive menu
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReporterKind {
+    /// Timestamped log lines on stderr and in the log file
+    Log,
+    /// One JSON object per line on stdout
+    Json,
+    /// No statistic output
+    Silent,
+}
+
+/// The words after a `+` in a `configure` command line: one more statistic.
+#[derive(Parser, Debug)]
+#[command(name = "+", no_binary_name = true)]
+struct ChainedStatistic {
+    #[command(subcommand)]
+    statistic: Statistic,
+}
+
+impl Cli {
+    /// Parses the command line, reading each segment after a standalone `+` as a further
+    /// statistic for `configure` to run.
+    pub fn parse_chained() -> Cli {
+        let arguments = std::env::args_os().collect::<Vec<OsString>>();
+        let mut segments = arguments.split(|argument| argument == CHAIN_SEPARATOR);
+        let mut cli = Cli::parse_from(segments.next().unwrap_or_default());
+        let chained = segments
+            .map(|segment| ChainedStatistic::parse_from(segment).statistic)
+            .collect::<Vec<Statistic>>();
+        match &mut cli.command {
+            Some(Commands::Configure { then, .. }) => *then = chained,
+            _ if !chained.is_empty() => Cli::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "only configure can chain statistics with '+'",
+                )
+                .exit(),
+            _ => {}
+        }
+        cli
+    }
+
+    pub fn title_style(&self) -> TitleStyle {
+        TitleStyle {
+            width: self.title_width.unwrap_or_else(default_title_width),
+            fill: self.title_fill,
+            enabled: !self.no_banners,
+        }
+    }
+}
+
+impl ReporterKind {
+    pub fn reporter(self) -> Box<dyn Reporter> {
+        match self {
+            ReporterKind::Log => Box::new(LogReporter),
+            ReporterKind::Json => Box::new(JsonReporter),
+            ReporterKind::Silent => Box::new(SilentReporter),
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Commands {
+    /// Configure a statistic to run against a CSV file. Chain more statistics after the
+    /// first with a standalone `+` (e.g. `anova ... + kruskal-wallis ...`) to run them all
+    /// on the same loaded data
+    #[command(short_flag = 'C', long_flag = "configure")]
+    Configure {
+        /// Path to the CSV file (or a .json/.jsonl file, or an Arrow IPC .arrow/.feather/.ipc
+        /// file with the `arrow` feature)
+        #[arg(long)]
+        csv: PathBuf,
+
+        #[command(flatten)]
+        options: Box<DataOptions>,
+
+        /// Recompute the statistic instead of replaying a cached result
+        #[arg(long, default_value_t = false)]
+        no_cache: bool,
+
+        /// Report the duration of each phase and peak memory use
+        #[arg(long, default_value_t = false)]
+        timing: bool,
+
+        /// Also write the results, with descriptives and histograms of the continuous
+        /// columns, to a Markdown (.md) or HTML (.html) report. Always recomputes
+        #[arg(long)]
+        report: Option<PathBuf>,
+
+        #[command(subcommand)]
+        statistic: Box<Statistic>,
+
+        /// The statistics chained after the first with `+`
+        #[arg(skip)]
+        then: Vec<Statistic>,
+    },
+    /// Answer a few questions about the design to get a recommended statistic, then
+    /// optionally run it
+    Suggest {
+        /// CSV file to run the suggested statistic against (prompted for if omitted)
+        #[arg(long)]
+        csv: Option<PathBuf>,
+    },
+    /// Run the statistics listed in a TOML analysis spec (e.g. stisty.toml), each as
+    /// `configure` would
+    Run {
+        /// Path to the spec; relative paths inside it are read from its directory
+        config: PathBuf,
+    },
+    /// Summarize every column of a file: types, missing cells, descriptives,
+    /// frequencies, and correlations
+    Overview {
+        /// Path to the CSV file (or a .json/.jsonl file, or an Arrow IPC .arrow/.feather/.ipc
+        /// file with the `arrow` feature)
+        #[arg(long)]
+        csv: PathBuf,
+
+        /// Treat the first row as data rather than headers
+        #[arg(long, default_value_t = false)]
+        no_headers: bool,
+
+        /// Field separator of the file; detected from its first rows when omitted
+        #[arg(long, value_enum)]
+        delimiter: Option<Delimiter>,
+
+        /// Read the file as this format; chosen by its extension when omitted
+        #[arg(long, value_enum)]
+        format: Option<InputFormat>,
+
+        /// Also write the overview, with a heat map of the missing cells, to an HTML
+        /// (.html) or JSON (.json) file
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Rank every pair of columns by how strongly they're associated (Pearson r, eta, or
+    /// Cramér's V, by the columns' types), for exploring a file
+    Scan {
+        /// Path to the CSV file (or a .json/.jsonl file, or an Arrow IPC .arrow/.feather/.ipc
+        /// file with the `arrow` feature)
+        #[arg(long)]
+        csv: PathBuf,
+
+        /// Treat the first row as data rather than headers
+        #[arg(long, default_value_t = false)]
+        no_headers: bool,
+
+        /// Field separator of the file; detected from its first rows when omitted
+        #[arg(long, value_enum)]
+        delimiter: Option<Delimiter>,
+
+        /// Read the file as this format; chosen by its extension when omitted
+        #[arg(long, value_enum)]
+        format: Option<InputFormat>,
+
+        /// How many of the strongest pairs to list
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+
+        /// Leave a column, such as a participant ID, out of the scan; repeatable
+        #[arg(long)]
+        exclude_column: Vec<usize>,
+    },
+    /// Descriptives of continuous columns, and optionally a single sample t or z test of
+    /// each, read in one pass a row at a time, for CSV files too large to import
+    Stream {
+        /// Path to the CSV file
+        #[arg(long)]
+        csv: PathBuf,
+
+        /// Treat the first row as data rather than headers
+        #[arg(long, default_value_t = false)]
+        no_headers: bool,
+
+        /// Field separator of the file; detected from its first rows when omitted
+        #[arg(long, value_enum)]
+        delimiter: Option<Delimiter>,
+
+        /// 0-based continuous column to summarize; repeatable
+        #[arg(long, required_unless_present = "column_name")]
+        column: Vec<usize>,
+
+        /// Header of a continuous column to summarize; repeatable
+        #[arg(long)]
+        column_name: Vec<String>,
+
+        /// Test each column's mean against this population mean
+        #[arg(long, allow_negative_numbers = true)]
+        mu: Option<f64>,
+
+        /// Known population standard deviation, making the test a z test
+        #[arg(long, requires = "mu")]
+        sigma: Option<f64>,
+    },
+    /// Print critical values for a distribution at a significance level
+    Critical {
+        /// Significance level
+        #[arg(long, default_value_t = 0.05)]
+        alpha: f64,
+
+        #[arg(long, value_enum, default_value_t = Tails::Two)]
+        tails: Tails,
+
+        #[command(subcommand)]
+        distribution: Distribution,
+    },
+}
+
+/// How the data are read and prepared before a statistic runs. Part of the cache key
+/// and recorded in provenance.
+#[derive(Args, Debug, Clone, Default)]
+pub struct DataOptions {
+    /// Treat the first row as data rather than headers
+    #[arg(long, default_value_t = false)]
+    pub no_headers: bool,
+
+    /// Field separator of the file; detected from its first rows when omitted
+    #[arg(long, value_enum)]
+    pub delimiter: Option<Delimiter>,
+
+    /// Read the file as this format; chosen by its extension when omitted
+    #[arg(long, value_enum)]
+    pub format: Option<InputFormat>,
+
+    /// Join the rows of another file (CSV or JSON) onto the data by a key column
+    #[arg(long, requires = "join_on")]
+    pub join: Option<PathBuf>,
+
+    /// Header of the key column, found in both files, that `--join` matches rows on
+    #[arg(long, requires = "join")]
+    pub join_on: Option<String>,
+
+    /// Keep only the rows with a match in the joined file (inner), or every row (left)
+    #[arg(long, value_enum, default_value_t = JoinKind::Inner)]
+    pub join_type: JoinKind,
+
+    /// Keep only the rows an expression holds for, e.g. "GPA > 3 && School == 'CSU'"
+    /// (after any join, and before a sample is drawn)
+    #[arg(long)]
+    pub filter: Option<String>,
+
+    /// Reshape wide to long: stack these columns (0-based indices, inclusive ranges such
+    /// as 3..6, or headers, separated by commas) into a pair of columns, one naming the
+    /// column each value came from, repeating the other columns on every row
+    #[arg(long, conflicts_with = "pivot")]
+    pub melt: Option<String>,
+
+    /// Headers of the two columns --melt creates [default: condition value]
+    #[arg(long, num_args = 2, value_names = ["KEY", "VALUE"], requires = "melt")]
+    pub melt_into: Vec<String>,
+
+    /// Reshape long to wide: one row per value of this ID column, with a column for each
+    /// value of --pivot-key
+    #[arg(long, requires_all = ["pivot_key", "pivot_value"])]
+    pub pivot: Option<String>,
+
+    /// Column whose values become the new columns of --pivot
+    #[arg(long, requires = "pivot")]
+    pub pivot_key: Option<String>,
+
+    /// Column whose cells fill the new columns of --pivot
+    #[arg(long, requires = "pivot")]
+    pub pivot_value: Option<String>,
+
+    /// Merge levels of the categorical columns before the statistic runs, written
+    /// FROM,FROM=>TO (e.g. --recode "Yes,Y,yes=>Yes"); repeat to apply several in order.
+    /// An empty TO makes the levels missing
+    #[arg(long)]
+    pub recode: Vec<String>,
+
+    /// Merge the levels of the categorical columns held by fewer than this many rows
+    /// into one level, "Other" (after any --recode)
+    #[arg(long)]
+    pub collapse_below: Option<usize>,
+
+    /// Level of the categorical columns the others are compared to: the one left out of
+    /// the dummy coding of a --formula predictor [default: the first level, sorted]
+    #[arg(long)]
+    pub reference: Option<String>,
+
+    /// 0-based categorical columns for --recode, --collapse-below, and --reference
+    /// (repeatable); all of the statistic's categorical columns when omitted
+    #[arg(long)]
+    pub recode_column: Vec<usize>,
+
+    /// How to handle missing cells (empty, NA, N/A, NaN, or null) in the columns the
+    /// statistic reads
+    #[arg(long, value_enum, default_value_t = MissingPolicy::Error)]
+    pub missing: MissingPolicy,
+
+    /// Transform the continuous columns before the statistic runs; repeat to apply
+    /// several in order (e.g. --transform log --transform center)
+    #[arg(long, value_enum)]
+    pub transform: Vec<Transform>,
+
+    /// 0-based continuous columns to transform (repeatable); all of the statistic's
+    /// continuous columns when omitted
+    #[arg(long, requires = "transform")]
+    pub transform_column: Vec<usize>,
+
+    /// Leave out rows holding an outlier (by this method) in any continuous column the
+    /// statistic reads
+    #[arg(long, value_enum)]
+    pub exclude_outliers: Option<OutlierMethod>,
+
+    /// Report the rows holding an outlier (by this method) in each continuous column the
+    /// statistic reads, without leaving them out
+    #[arg(long, value_enum, conflicts_with = "exclude_outliers")]
+    pub flag_outliers: Option<OutlierMethod>,
+
+    /// 0-based categorical column to split the rows by; the statistic runs once per level,
+    /// followed by a table comparing the results
+    #[arg(long)]
+    pub group_by: Option<usize>,
+
+    /// Analyze a uniform random sample of this many rows, drawn while reading, for a
+    /// quick (approximate) look at a file too big for a full analysis
+    #[arg(long)]
+    pub sample: Option<usize>,
+
+    /// Seed for --sample, to draw the same rows again; taken from the clock when omitted
+    #[arg(long, requires = "sample")]
+    pub seed: Option<u64>,
+}
+
+/// The title and hypothesis a result is printed under. Both default to ones built from
+/// the analyzed columns' headers.
+#[derive(Args, Debug, Clone, Default)]
+pub struct Labels {
+    /// Title of the result
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// Hypothesis printed under the title
+    #[arg(long)]
+    pub description: Option<String>,
+}
+
+impl Labels {
+    fn name(&self, default: String) -> String {
+        self.name.clone().unwrap_or(default)
+    }
+
+    fn description(&self, default: String) -> String {
+        self.description.clone().unwrap_or(default)
+    }
+}
+
+#[derive(Subcommand, Debug, Clone, Copy)]
+pub enum Distribution {
+    /// Standard normal (z)
+    Normal,
+    /// Student's t
+    T {
+        #[arg(long)]
+        df: f64,
+    },
+    /// F
+    F {
+        #[arg(long)]
+        df_1: f64,
+        #[arg(long)]
+        df_2: f64,
+    },
+    /// Chi-square
+    ChiSquare {
+        #[arg(long)]
+        df: f64,
+    },
+}
+
+impl Distribution {
+    pub fn quantile(&self, p: f64) -> Result<f64, Error> {
+        match *self {
+            Distribution::Normal => standard_normal_quantile(p),
+            Distribution::T { df } => t_quantile(p, df),
+            Distribution::F { df_1, df_2 } => f_quantile(p, df_1, df_2),
+            Distribution::ChiSquare { df } => chi_square_quantile(p, df),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match *self {
+            Distribution::Normal => String::from("Standard normal (z)"),
+            Distribution::T { df } => format!("t (df = {})", df),
+            Distribution::F { df_1, df_2 } => format!("F (df = {}, {})", df_1, df_2),
+            Distribution::ChiSquare { df } => format!("Chi-square (df = {})", df),
+        }
+    }
+}
+
+/// Column arguments are 0-based indices into the CSV. Each also has a `-name` form
+/// (`--column-name`, `--predictor-column-names`, `--by-name`, ...) taking the header
+/// instead, matched regardless of case, spaces, and punctuation.
+#[derive(Subcommand, Debug, Clone)]
+pub enum Statistic {
+    /// Single sample t test of a continuous column against mu
+    #[command(long_flag = "single-sample-t")]
+    SingleSampleT {
+        #[arg(long, required_unless_present = "column_name")]
+        column: Option<usize>,
+        #[arg(long, conflicts_with = "column")]
+        column_name: Option<String>,
+        #[arg(long)]
+        mu: f64,
+        /// Write the rows with each value's deviation from the mean and z-score appended
+        /// to this CSV file
+        #[arg(long)]
+        export_derived: Option<PathBuf>,
+        #[command(flatten)]
+        labels: Labels,
+    },
+    /// Z test of a continuous column's mean against mu with a known population sigma
+    #[command(long_flag = "z-test")]
+    ZTest {
+        #[arg(long, required_unless_present = "column_name")]
+        column: Option<usize>,
+        #[arg(long, conflicts_with = "column")]
+        column_name: Option<String>,
+        #[arg(long)]
+        mu: f64,
+        #[arg(long)]
+        sigma: f64,
+        #[command(flatten)]
+        labels: Labels,
+    },
+    /// Paired samples t test of two continuous columns
+    #[command(long_flag = "paired-samples-t")]
+    PairedSamplesT {
+        #[arg(long, required_unless_present = "column_x_name")]
+        column_x: Option<usize>,
+        #[arg(long, conflicts_with = "column_x")]
+        column_x_name: Option<String>,
+        #[arg(long, required_unless_present = "column_y_name")]
+        column_y: Option<usize>,
+        #[arg(long, conflicts_with = "column_y")]
+        column_y_name: Option<String>,
+        /// Write the rows with each pair's difference (y - x) and its deviation from the
+        /// mean difference appended to this CSV file
+        #[arg(long)]
+        export_derived: Option<PathBuf>,
+        #[command(flatten)]
+        labels: Labels,
+    },
+    /// Independent groups t test of a continuous column split by a two-level categorical column
+    #[command(long_flag = "independent-groups-t")]
+    IndependentGroupsT {
+        #[arg(long, required_unless_present = "categorical_column_name")]
+        categorical_column: Option<usize>,
+        #[arg(long, conflicts_with = "categorical_column")]
+        categorical_column_name: Option<String>,
+        #[arg(long, required_unless_present = "continuous_column_name")]
+        continuous_column: Option<usize>,
+        #[arg(long, conflicts_with = "continuous_column")]
+        continuous_column_name: Option<String>,
+        /// Write the rows with each value's group mean and deviation from it appended to
+        /// this CSV file
+        #[arg(long)]
+        export_derived: Option<PathBuf>,
+        #[command(flatten)]
+        labels: Labels,
+    },
+    /// One way ANOVA of a continuous column split by a categorical column of three or more levels
+    #[command(long_flag = "anova")]
+    Anova {
+        #[arg(long, required_unless_present = "categorical_column_name")]
+        categorical_column: Option<usize>,
+        #[arg(long, conflicts_with = "categorical_column")]
+        categorical_column_name: Option<String>,
+        #[arg(long, required_unless_present = "continuous_column_name")]
+        continuous_column: Option<usize>,
+        #[arg(long, conflicts_with = "continuous_column")]
+        continuous_column_name: Option<String>,
+        #[command(flatten)]
+        labels: Labels,
+    },
+    /// Mann-Whitney U test of a continuous column split by a two-level categorical column
+    #[command(long_flag = "mann-whitney-u")]
+    MannWhitneyU {
+        #[arg(long, required_unless_present = "categorical_column_name")]
+        categorical_column: Option<usize>,
+        #[arg(long, conflicts_with = "categorical_column")]
+        categorical_column_name: Option<String>,
+        #[arg(long, required_unless_present = "continuous_column_name")]
+        continuous_column: Option<usize>,
+        #[arg(long, conflicts_with = "continuous_column")]
+        continuous_column_name: Option<String>,
+        #[command(flatten)]
+        labels: Labels,
+    },
+    /// Kruskal-Wallis H test of a continuous column split by a categorical column
+    #[command(long_flag = "kruskal-wallis")]
+    KruskalWallis {
+        #[arg(long, required_unless_present = "categorical_column_name")]
+        categorical_column: Option<usize>,
+        #[arg(long, conflicts_with = "categorical_column")]
+        categorical_column_name: Option<String>,
+        #[arg(long, required_unless_present = "continuous_column_name")]
+        continuous_column: Option<usize>,
+        #[arg(long, conflicts_with = "continuous_column")]
+        continuous_column_name: Option<String>,
+        #[command(flatten)]
+        labels: Labels,
+    },
+    /// Pearson, Spearman, and Kendall correlations between two continuous columns
+    #[command(long_flag = "correlation")]
+    Correlation {
+        #[arg(long, required_unless_present = "column_x_name")]
+        column_x: Option<usize>,
+        #[arg(long, conflicts_with = "column_x")]
+        column_x_name: Option<String>,
+        #[arg(long, required_unless_present = "column_y_name")]
+        column_y: Option<usize>,
+        #[arg(long, conflicts_with = "column_y")]
+        column_y_name: Option<String>,
+        /// Column identifying which rows come from the same subject (or other cluster),
+        /// for standard errors that don't assume the rows are independent
+        #[arg(long)]
+        cluster: Option<usize>,
+        #[arg(long, conflicts_with = "cluster")]
+        cluster_name: Option<String>,
+        #[command(flatten)]
+        labels: Labels,
+    },
+    /// Wilcoxon signed-rank test of two paired continuous columns
+    #[command(long_flag = "wilcoxon-signed-rank")]
+    WilcoxonSignedRank {
+        #[arg(long, required_unless_present = "column_x_name")]
+        column_x: Option<usize>,
+        #[arg(long, conflicts_with = "column_x")]
+        column_x_name: Option<String>,
+        #[arg(long, required_unless_present = "column_y_name")]
+        column_y: Option<usize>,
+        #[arg(long, conflicts_with = "column_y")]
+        column_y_name: Option<String>,
+        #[command(flatten)]
+        labels: Labels,
+    },
+    /// Ordinary least squares regression of one dependent column on one or more predictors
+    #[command(long_flag = "multi-regression")]
+    MultiRegression {
+        #[arg(long, required_unless_present_any = ["dependent_column_name", "formula"])]
+        dependent_column: Option<usize>,
+        #[arg(long, conflicts_with = "dependent_column")]
+        dependent_column_name: Option<String>,
+        #[arg(long, num_args = 1.., required_unless_present_any = ["predictor_column_names", "formula"])]
+        predictor_columns: Vec<usize>,
+        #[arg(long, num_args = 1..)]
+        predictor_column_names: Vec<String>,
+        /// Regress on the columns of an R-style formula instead, e.g. "y ~ x1 + x2 + x1:x2 +
+        /// x1^2", with a predictor for each interaction, power, and dummy coded level
+        #[arg(
+            long,
+            conflicts_with_all = ["dependent_column", "dependent_column_name", "predictor_columns", "predictor_column_names"]
+        )]
+        formula: Option<String>,
+        /// Column identifying which rows come from the same subject (or other cluster),
+        /// for standard errors that don't assume the rows are independent
+        #[arg(long)]
+        cluster: Option<usize>,
+        #[arg(long, conflicts_with = "cluster")]
+        cluster_name: Option<String>,
+        /// Write the rows with the fitted values, residuals, standardized residuals,
+        /// leverages, and Cook's distances appended to this CSV file
+        #[arg(long)]
+        export_derived: Option<PathBuf>,
+        /// Write residual-vs-fitted, normal Q-Q, and scale-location plots (HTML) to this
+        /// directory, or print them with --plot-backend terminal
+        #[arg(long)]
+        plots: Option<PathBuf>,
+        /// Write the residual plots as .svg or .png files named after this one, e.g.
+        /// plots.png gives plots-GPA-normal-q-q.png
+        #[arg(long, conflicts_with = "plots")]
+        chart_out: Option<PathBuf>,
+        #[command(flatten)]
+        labels: Labels,
+    },
+    /// Frequencies and Cochran's Q for a "select all that apply" column whose cells list
+    /// options separated by a delimiter, optionally tested against a grouping column
+    #[command(long_flag = "multiple-response")]
+    MultipleResponse {
+        #[arg(long, required_unless_present = "column_name")]
+        column: Option<usize>,
+        #[arg(long, conflicts_with = "column")]
+        column_name: Option<String>,
+        /// Separator between the options in a cell
+        #[arg(long, default_value = ";")]
+        delimiter: String,
+        /// Categorical column to test each option against (chi-square of independence)
+        #[arg(long)]
+        by: Option<usize>,
+        #[arg(long, conflicts_with = "by")]
+        by_name: Option<String>,
+        #[command(flatten)]
+        labels: Labels,
+    },
+    /// Linear model from an R-style formula over column headers, e.g. "GPA ~ School * Netflix".
+    /// A single categorical predictor of three or more levels runs a one way ANOVA
+    #[command(long_flag = "formula")]
+    Formula {
+        /// Response ~ terms; `a:b` is an interaction, `a*b` is `a + b + a:b`, `a^2` is a
+        /// square, and factor(a) treats a numeric column as categorical
+        formula: String,
+        #[command(flatten)]
+        labels: Labels,
+    },
+    /// Proportional odds (cumulative logit) regression of an ordinal response, such as a
+    /// Likert item, from an R-style formula, with a Brant test of proportional odds
+    #[command(long_flag = "ordinal-regression")]
+    OrdinalRegression {
+        /// Response ~ terms, written as for --formula
+        formula: String,
+        /// The response's levels from lowest to highest, separated by commas; numeric
+        /// responses default to numeric order
+        #[arg(long)]
+        levels: Option<String>,
+        #[command(flatten)]
+        labels: Labels,
+    },
+    /// Poisson or negative binomial regression of a count response, such as visits or
+    /// errors, from an R-style formula, with a test for overdispersion
+    #[command(long_flag = "count-regression")]
+    CountRegression {
+        /// Response ~ terms, written as for --formula
+        formula: String,
+        #[arg(long, value_enum, default_value_t = CountModel::Auto)]
+        model: CountModel,
+        #[command(flatten)]
+        labels: Labels,
+    },
+    /// Histogram of a continuous column, to inspect its distribution before running a
+    /// test
+    #[command(long_flag = "histogram")]
+    Histogram {
+        #[arg(long, required_unless_present = "column_name")]
+        column: Option<usize>,
+        #[arg(long, conflicts_with = "column")]
+        column_name: Option<String>,
+        /// Number of equal-width bins; Sturges' rule, ceil(log2 n) + 1, when omitted
+        #[arg(long)]
+        bins: Option<usize>,
+        /// Directory to write the HTML chart to
+        #[arg(long, default_value = "graphics")]
+        output: PathBuf,
+        /// Write the chart to this .svg or .png file instead
+        #[arg(long)]
+        chart_out: Option<PathBuf>,
+    },
+    /// Boxplot of a continuous column, with one box per level of a categorical column
+    /// when one is given
+    #[command(long_flag = "boxplot")]
+    Boxplot {
+        #[arg(long, required_unless_present = "continuous_column_name")]
+        continuous_column: Option<usize>,
+        #[arg(long, conflicts_with = "continuous_column")]
+        continuous_column_name: Option<String>,
+        #[arg(long)]
+        categorical_column: Option<usize>,
+        #[arg(long, conflicts_with = "categorical_column")]
+        categorical_column_name: Option<String>,
+        /// Directory to write the HTML chart to
+        #[arg(long, default_value = "graphics")]
+        output: PathBuf,
+        /// Write the chart to this .svg or .png file instead
+        #[arg(long)]
+        chart_out: Option<PathBuf>,
+    },
+    /// List each column's index, header, inferred type, level and missing counts, and a
+    /// few of its values, to pick the columns for a statistic
+    #[command(long_flag = "inspect")]
+    Inspect {
+        /// How many distinct values to show per column
+        #[arg(long, default_value_t = 3)]
+        samples: usize,
+    },
+}
+
+impl Statistic {
+    /// Where `--export-derived` writes, for the statistics that offer it.
+    pub fn export_derived(&self) -> Option<&Path> {
+        match self {
+            Statistic::SingleSampleT { export_derived, .. }
+            | Statistic::PairedSamplesT { export_derived, .. }
+            | Statistic::IndependentGroupsT { export_derived, .. }
+            | Statistic::MultiRegression { export_derived, .. } => export_derived.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Whether running the statistic writes a file (derived columns or a chart), which
+    /// replaying a cached result wouldn't.
+    pub fn writes_files(&self) -> bool {
+        match self {
+            Statistic::Histogram { .. } | Statistic::Boxplot { .. } => true,
+            Statistic::MultiRegression {
+                plots, chart_out, ..
+            } => plots.is_some() || chart_out.is_some() || self.export_derived().is_some(),
+            _ => self.export_derived().is_some(),
+        }
+    }
+
+    /// The `--name` and `--description` of the statistics that print a titled result.
+    pub fn labels_mut(&mut self) -> Option<&mut Labels> {
+        match self {
+            Statistic::SingleSampleT { labels, .. }
+            | Statistic::ZTest { labels, .. }
+            | Statistic::PairedSamplesT { labels, .. }
+            | Statistic::IndependentGroupsT { labels, .. }
+            | Statistic::Anova { labels, .. }
+            | Statistic::MannWhitneyU { labels, .. }
+            | Statistic::KruskalWallis { labels, .. }
+            | Statistic::Correlation { labels, .. }
+            | Statistic::WilcoxonSignedRank { labels, .. }
+            | Statistic::MultiRegression { labels, .. }
+            | Statistic::MultipleResponse { labels, .. }
+            | Statistic::Formula { labels, .. }
+            | Statistic::OrdinalRegression { labels, .. }
+            | Statistic::CountRegression { labels, .. } => Some(labels),
+            Statistic::Histogram { .. } | Statistic::Boxplot { .. } | Statistic::Inspect { .. } => {
+                None
+            }
+        }
+    }
+
+    /// 0-based indices of the columns this statistic reads as continuous data. Columns
+    /// given by name count once `resolve_column_names` has run.
+    pub fn continuous_columns(&self, csv_data: &CSVData) -> Vec<usize> {
+        match *self {
+            Statistic::SingleSampleT { column, .. }
+            | Statistic::ZTest { column, .. }
+            | Statistic::Histogram { column, .. } => column.into_iter().collect(),
+            Statistic::PairedSamplesT {
+                column_x, column_y, ..
+            }
+            | Statistic::Correlation {
+                column_x, column_y, ..
+            }
+            | Statistic::WilcoxonSignedRank {
+                column_x, column_y, ..
+            } => column_x.into_iter().chain(column_y).collect(),
+            Statistic::IndependentGroupsT {
+                continuous_column, ..
+            }
+            | Statistic::Anova {
+                continuous_column, ..
+            }
+            | Statistic::MannWhitneyU {
+                continuous_column, ..
+            }
+            | Statistic::KruskalWallis {
+                continuous_column, ..
+            }
+            | Statistic::Boxplot {
+                continuous_column, ..
+            } => continuous_column.into_iter().collect(),
+            Statistic::MultiRegression {
+                formula: Some(ref formula),
+                ..
+            } => Formula::parse(formula)
+                .map(|formula| formula.columns(csv_data).0)
+                .unwrap_or_default(),
+            Statistic::MultiRegression {
+                dependent_column,
+                ref predictor_columns,
+                ..
+            } => dependent_column
+                .into_iter()
+                .chain(predictor_columns.iter().copied())
+                .collect(),
+            Statistic::MultipleResponse { .. } | Statistic::Inspect { .. } => vec![],
+            Statistic::Formula { ref formula, .. }
+            | Statistic::CountRegression { ref formula, .. } => Formula::parse(formula)
+                .map(|formula| formula.columns(csv_data).0)
+                .unwrap_or_default(),
+            // the ordinal response is read as categorical
+            Statistic::OrdinalRegression { ref formula, .. } => Formula::parse(formula)
+                .map(|formula| {
+                    let response = formula.response_column(csv_data).ok();
+                    formula
+                        .columns(csv_data)
+                        .0
+                        .into_iter()
+                        .filter(|column| Some(*column) != response)
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+
+    /// 0-based indices of the columns this statistic reads as categorical data.
+    pub fn categorical_columns(&self, csv_data: &CSVData) -> Vec<usize> {
+        match *self {
+            Statistic::IndependentGroupsT {
+                categorical_column, ..
+            }
+            | Statistic::Anova {
+                categorical_column, ..
+            }
+            | Statistic::MannWhitneyU {
+                categorical_column, ..
+            }
+            | Statistic::KruskalWallis {
+                categorical_column, ..
+            }
+            | Statistic::Boxplot {
+                categorical_column, ..
+            } => categorical_column.into_iter().collect(),
+            Statistic::MultipleResponse { column, by, .. } => {
+                column.into_iter().chain(by).collect()
+            }
+            Statistic::MultiRegression {
+                formula: Some(ref formula),
+                cluster,
+                ..
+            } => Formula::parse(formula)
+                .map(|formula| formula.columns(csv_data).1)
+                .unwrap_or_default()
+                .into_iter()
+                .chain(cluster)
+                .collect(),
+            Statistic::Correlation { cluster, .. } | Statistic::MultiRegression { cluster, .. } => {
+                cluster.into_iter().collect()
+            }
+            Statistic::Formula { ref formula, .. }
+            | Statistic::CountRegression { ref formula, .. } => Formula::parse(formula)
+                .map(|formula| formula.columns(csv_data).1)
+                .unwrap_or_default(),
+            Statistic::OrdinalRegression { ref formula, .. } => Formula::parse(formula)
+                .map(|formula| {
+                    let mut categorical = formula.columns(csv_data).1;
+                    if let Ok(response) = formula.response_column(csv_data) {
+                        categorical.retain(|column| *column != response);
+                        categorical.insert(0, response);
+                    }
+                    categorical
+                })
+                .unwrap_or_default(),
+            _ => vec![],
+        }
+    }
+
+    /// Replaces every column given by header with its index.
+    pub fn resolve_column_names(&mut self, csv_data: &CSVData) -> Result<(), Error> {
+        match self {
+            Statistic::SingleSampleT {
+                column,
+                column_name,
+                ..
+            }
+            | Statistic::ZTest {
+                column,
+                column_name,
+                ..
+            }
+            | Statistic::Histogram {
+                column,
+                column_name,
+                ..
+            } => resolve_column(csv_data, column, column_name),
+            Statistic::PairedSamplesT {
+                column_x,
+                column_x_name,
+                column_y,
+                column_y_name,
+                ..
+            }
+            | Statistic::WilcoxonSignedRank {
+                column_x,
+                column_x_name,
+                column_y,
+                column_y_name,
+                ..
+            } => {
+                resolve_column(csv_data, column_x, column_x_name)?;
+                resolve_column(csv_data, column_y, column_y_name)
+            }
+            Statistic::IndependentGroupsT {
+                categorical_column,
+                categorical_column_name,
+                continuous_column,
+                continuous_column_name,
+                ..
+            }
+            | Statistic::Anova {
+                categorical_column,
+                categorical_column_name,
+                continuous_column,
+                continuous_column_name,
+                ..
+            }
+            | Statistic::MannWhitneyU {
+                categorical_column,
+                categorical_column_name,
+                continuous_column,
+                continuous_column_name,
+                ..
+            }
+            | Statistic::KruskalWallis {
+                categorical_column,
+                categorical_column_name,
+                continuous_column,
+                continuous_column_name,
+                ..
+            }
+            | Statistic::Boxplot {
+                categorical_column,
+                categorical_column_name,
+                continuous_column,
+                continuous_column_name,
+                ..
+            } => {
+                resolve_column(csv_data, categorical_column, categorical_column_name)?;
+                resolve_column(csv_data, continuous_column, continuous_column_name)
+            }
+            Statistic::Correlation {
+                column_x,
+                column_x_name,
+                column_y,
+                column_y_name,
+                cluster,
+                cluster_name,
+                ..
+            } => {
+                resolve_column(csv_data, column_x, column_x_name)?;
+                resolve_column(csv_data, column_y, column_y_name)?;
+                resolve_column(csv_data, cluster, cluster_name)
+            }
+            Statistic::MultiRegression {
+                dependent_column,
+                dependent_column_name,
+                predictor_columns,
+                predictor_column_names,
+                cluster,
+                cluster_name,
+                ..
+            } => {
+                resolve_column(csv_data, dependent_column, dependent_column_name)?;
+                for name in predictor_column_names.drain(..) {
+                    predictor_columns.push(find_header(csv_data, &name)?);
+                }
+                resolve_column(csv_data, cluster, cluster_name)
+            }
+            Statistic::MultipleResponse {
+                column,
+                column_name,
+                by,
+                by_name,
+                ..
+            } => {
+                resolve_column(csv_data, column, column_name)?;
+                resolve_column(csv_data, by, by_name)
+            }
+            Statistic::Formula { .. }
+            | Statistic::OrdinalRegression { .. }
+            | Statistic::CountRegression { .. }
+            | Statistic::Inspect { .. } => Ok(()),
+        }
+    }
+}
+
+fn resolve_column(
+    csv_data: &CSVData,
+    column: &mut Option<usize>,
+    name: &mut Option<String>,
+) -> Result<(), Error> {
+    if let Some(name) = name.take() {
+        *column = Some(find_header(csv_data, &name)?);
+    }
+    Ok(())
+}
+
+/// The index of the header `name` names: an exact match, else the one header equal to it
+/// ignoring case, spaces, and punctuation. Otherwise lists the headers, with the closest
+/// ones first when `name` looks like a typo of them.
+fn find_header(csv_data: &CSVData, name: &str) -> Result<usize, Error> {
+    if let Some(index) = csv_data.headers.iter().position(|header| header == name) {
+        return Ok(index);
+    }
+    let normalized = normalize_header(name);
+    let matches = csv_data
+        .headers
+        .iter()
+        .enumerate()
+        .filter(|(_, header)| normalize_header(header) == normalized)
+        .map(|(index, _)| index)
+        .collect::<Vec<usize>>();
+    if let [index] = matches[..] {
+        return Ok(index);
+    }
+
+    let listed = |columns: &[usize]| {
+        columns
+            .iter()
+            .map(|column| format!("'{}' ({})", csv_data.headers[*column], column))
+            .collect::<Vec<String>>()
+            .join(", ")
+    };
+    let headers = listed(&(0..csv_data.headers.len()).collect::<Vec<usize>>());
+    if !matches.is_empty() {
+        return Err(anyhow!(
+            "Column name '{}' matches more than one header ({}); give the exact header or \
+             the index",
+            name,
+            listed(&matches)
+        ));
+    }
+    let distances = csv_data
+        .headers
+        .iter()
+        .map(|header| edit_distance(&normalize_header(header), &normalized))
+        .collect::<Vec<usize>>();
+    let closest = distances.iter().copied().min().unwrap_or_default();
+    let suggestions = (0..csv_data.headers.len())
+        .filter(|column| {
+            distances[*column] == closest
+                && closest <= HEADER_TYPO_DISTANCE.max(normalized.len() / 4)
+        })
+        .collect::<Vec<usize>>();
+    Err(match suggestions.is_empty() {
+        true => anyhow!(
+            "'{}' is not a column header; the headers are {}",
+            name,
+            headers
+        ),
+        false => anyhow!(
+            "'{}' is not a column header; did you mean {}? The headers are {}",
+            name,
+            listed(&suggestions),
+            headers
+        ),
+    })
+}
+
+fn normalize_header(header: &str) -> String {
+    header
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Levenshtein distance between two strings, by characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<char>>();
+    let mut previous = (0..=b.len()).collect::<Vec<usize>>();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+pub fn handle_args(command: Commands) -> Result<(), Error> {
+    match command {
+        Commands::Configure {
+            csv,
+            mut options,
+            no_cache,
+            timing,
+            report,
+            statistic,
+            then,
+        } => {
+            let mut statistics = std::iter::once(*statistic)
+                .chain(then)
+                .collect::<Vec<Statistic>>();
+            let exports = statistics
+                .iter()
+                .any(|statistic| statistic.export_derived().is_some());
+            let writes_files = statistics.iter().any(Statistic::writes_files);
+            let mut timer = PhaseTimer::new(timing);
+            if let Some(report) = &report {
+                ReportFormat::from_path(report)?;
+            }
+            if options.group_by.is_some() && exports {
+                return Err(anyhow!(
+                    "--export-derived writes a single file and can't be combined with --group-by"
+                ));
+            }
+            if options.sample.is_some() && options.seed.is_none() {
+                options.seed = Some(clock_seed());
+            }
+            let file_hash = timer.time("hash", || Ok(sha256_hex(fs::read(&csv)?)))?;
+            // a joined file's contents change the result as much as the main file's
+            let data_hash = match &options.join {
+                Some(join) => format!("{}|{}", file_hash, sha256_hex(fs::read(join)?)),
+                None => file_hash.clone(),
+            };
+            let key = cache::cache_key(&data_hash, &options, &statistics);
+            // reports, exports, and charts need the prepared data, which a cached result
+            // doesn't keep
+            if !no_cache && report.is_none() && !writes_files {
+                if let Some(entry) = timer.time("cache lookup", || Ok(cache::load(&key)))? {
+                    report!(
+                        "Replaying result cached at {} (pass --no-cache to recompute)",
+                        entry.provenance.timestamp
+                    );
+                    entry
+                        .lines
+                        .iter()
+                        .for_each(|line| report_message(line.level, &line.message));
+                    timer.report();
+                    return Ok(());
+                }
+            }
+
+            let (mut csv_data, total_rows) =
+                timer.time("import", || import_data(&csv, &options))?;
+            for statistic in statistics.iter_mut() {
+                statistic.resolve_column_names(&csv_data)?;
+            }
+            if !options.recode.is_empty()
+                || options.collapse_below.is_some()
+                || options.reference.is_some()
+            {
+                csv_data = timer.time("recode", || {
+                    let columns = categorical_columns(&statistics, &csv_data)
+                        .into_iter()
+                        .chain(options.group_by)
+                        .collect::<Vec<usize>>();
+                    recode_columns(csv_data, &columns, &options)
+                })?;
+            }
+            csv_data = timer.time("missing values", || {
+                // the analysis policy drops each statistic's own incomplete rows as it runs
+                if options.missing == MissingPolicy::Analysis {
+                    return Ok(csv_data);
+                }
+                let continuous_columns = continuous_columns(&statistics, &csv_data);
+                let categorical_columns = categorical_columns(&statistics, &csv_data)
+                    .into_iter()
+                    .chain(options.group_by)
+                    .collect::<Vec<usize>>();
+                csv_data.apply_missing_policy(
+                    options.missing,
+                    &continuous_columns,
+                    &categorical_columns,
+                )
+            })?;
+            if !options.transform.is_empty() {
+                csv_data = timer.time("transform", || {
+                    let columns = continuous_columns(&statistics, &csv_data);
+                    transform_columns(csv_data, &columns, &options)
+                })?;
+            }
+            if let Some(method) = options.exclude_outliers {
+                csv_data = timer.time("outliers", || {
+                    let columns = continuous_columns(&statistics, &csv_data);
+                    exclude_outlier_rows(csv_data, &columns, method)
+                })?;
+            }
+            if let Some(method) = options.flag_outliers {
+                timer.time("outliers", || {
+                    let columns = continuous_columns(&statistics, &csv_data);
+                    flag_outlier_rows(&csv_data, &columns, method)
+                })?;
+            }
+            let ((), lines) = timer.time("computation", || {
+                capture_output(|| {
+                    if let (Some(seed), Some(total_rows)) = (options.seed, total_rows) {
+                        report_warning!(
+                            "Approximate results from a random sample of {} of {} rows (seed {})",
+                            csv_data.column_count,
+                            total_rows,
+                            seed
+                        );
+                    }
+                    for statistic in statistics.iter() {
+                        let complete_data;
+                        let csv_data = if options.missing == MissingPolicy::Analysis {
+                            complete_data = complete_cases(&csv_data, statistic, options.group_by)?;
+                            &complete_data
+                        } else {
+                            &csv_data
+                        };
+                        match options.group_by {
+                            Some(group_column) => run_grouped(csv_data, statistic, group_column)?,
+                            None => {
+                                run_statistic(csv_data, statistic)?;
+                            }
+                        }
+                    }
+                    Ok(())
+                })
+            })?;
+            let provenance = Provenance::new(&csv, &file_hash, &options, &csv_data, &statistics);
+            if let Some(report) = &report {
+                timer.time(EXPORT_PHASE, || {
+                    write_report(
+                        report,
+                        &provenance,
+                        &csv_data,
+                        &continuous_columns(&statistics, &csv_data),
+                        &lines,
+                    )?;
+                    report!("Wrote report to {}", report.display());
+                    Ok(())
+                })?;
+            }
+            if !no_cache {
+                timer.time("cache store", || {
+                    cache::store(&key, provenance, lines);
+                    Ok(())
+                })?;
+            }
+            timer.report();
+            Ok(())
+        }
+        Commands::Suggest { csv } => crate::menu::run_suggest(csv),
+        Commands::Run { config } => crate::spec::run_spec(&config),
+        Commands::Overview {
+            csv,
+            no_headers,
+            delimiter,
+            format,
+            output,
+        } => {
+            if let Some(output) = &output {
+                OverviewFormat::from_path(output)?;
+            }
+            let file_hash = sha256_hex(fs::read(&csv)?);
+            let options = DataOptions {
+                no_headers,
+                delimiter,
+                format,
+                ..Default::default()
+            };
+            let (csv_data, _) = import_data(&csv, &options)?;
+            let overview = Overview::new(&csv, &file_hash, &csv_data)?;
+            overview.print();
+            if let Some(output) = &output {
+                overview.write(output)?;
+                report!("Wrote overview to {}", output.display());
+            }
+            Ok(())
+        }
+        Commands::Scan {
+            csv,
+            no_headers,
+            delimiter,
+            format,
+            top,
+            exclude_column,
+        } => {
+            let options = DataOptions {
+                no_headers,
+                delimiter,
+                format,
+                ..Default::default()
+            };
+            let (csv_data, _) = import_data(&csv, &options)?;
+            Scan::new(&csv, &csv_data, &exclude_column)?.print(top);
+            Ok(())
+        }
+        Commands::Stream {
+            csv,
+            no_headers,
+            delimiter,
+            mut column,
+            column_name,
+            mu,
+            sigma,
+        } => {
+            let mut stream = CSVStream::open(
+                &csv,
+                Some(!no_headers),
+                delimiter.map(|delimiter| delimiter.byte()),
+            )?;
+            let mut headers = CSVData::default();
+            headers.headers = stream.headers.clone();
+            for name in column_name.iter() {
+                column.push(find_header(&headers, name)?);
+            }
+            let streamed = Stream::new(&csv, &mut stream, &column)?;
+            streamed.print()?;
+            if let Some(mu) = mu {
+                streamed.print_tests(mu, sigma)?;
+            }
+            Ok(())
+        }
+        Commands::Critical {
+            alpha,
+            tails,
+            distribution,
+        } => {
+            let (lower, upper) = critical_values(|p| distribution.quantile(p), alpha, tails)?;
+            report!("{}", logging::format_title("Critical Values"));
+            report!("Distribution: {}", distribution.label());
+            report!("alpha: {}", alpha);
+            report!("Tails: {:?}", tails);
+            if let Some(lower) = lower {
+                report!("Lower critical value: {}", lower);
+            }
+            if let Some(upper) = upper {
+                report!("Upper critical value: {}", upper);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// The headline numbers of a result as (label, value), in the order they are printed.
+pub type Summary = Vec<(String, f64)>;
+
+/// Builds the data arrays a statistic needs from `csv_data`, runs it, and prints the result.
+/// Shared by the CLI and the interactive menu.
+pub fn run_statistic(csv_data: &CSVData, statistic: &Statistic) -> Result<Summary, Error> {
+    Ok(match *statistic {
+        Statistic::SingleSampleT {
+            column,
+            mu,
+            ref export_derived,
+            ref labels,
+            ..
+        } => {
+            let data_array = continuous_data_array(csv_data, resolved(column)?)?;
+            let result = SingleSampleT::new(
+                labels.name(format!("{} Single Sample t", data_array.name)),
+                labels.description(format!(
+                    "The mean of '{}' differs from {}.",
+                    data_array.name, mu
+                )),
+                &data_array,
+                mu,
+            )?;
+            let summary = vec![("t".to_string(), result.t), ("p".to_string(), result.p)];
+            result.print()?;
+            if let Some(path) = export_derived {
+                export_derived_columns(
+                    csv_data,
+                    path,
+                    vec![
+                        (
+                            format!("{} deviation", data_array.name),
+                            data_array.deviations.clone(),
+                        ),
+                        (
+                            format!("{} z", data_array.name),
+                            data_array.z_scores.clone(),
+                        ),
+                    ],
+                )?;
+            }
+            summary
+        }
+        Statistic::ZTest {
+            column,
+            mu,
+            sigma,
+            ref labels,
+            ..
+        } => {
+            let data_array = continuous_data_array(csv_data, resolved(column)?)?;
+            let result = ZTest::new(
+                labels.name(format!("{} Z Test", data_array.name)),
+                labels.description(format!(
+                    "The mean of '{}' differs from {}.",
+                    data_array.name, mu
+                )),
+                &data_array,
+                mu,
+                sigma,
+            )?;
+            let summary = vec![("z".to_string(), result.z), ("p".to_string(), result.p)];
+            result.print()?;
+            summary
+        }
+        Statistic::PairedSamplesT {
+            column_x,
+            column_y,
+            ref export_derived,
+            ref labels,
+            ..
+        } => {
+            let data_x = continuous_data_array(csv_data, resolved(column_x)?)?;
+            let data_y = continuous_data_array(csv_data, resolved(column_y)?)?;
+            let result = PairedSamplesT::new(
+                labels.name(format!("{} vs {}", data_x.name, data_y.name)),
+                labels.description(format!(
+                    "There is a difference between paired '{}' and '{}'.",
+                    data_x.name, data_y.name
+                )),
+                &data_x,
+                &data_y,
+            )?;
+            let summary = vec![("t".to_string(), result.t), ("p".to_string(), result.p)];
+            result.print()?;
+            if let Some(path) = export_derived {
+                let differences = data_x
+                    .data_array
+                    .data
+                    .iter()
+                    .zip(data_y.data_array.data.iter())
+                    .map(|(x, y)| y.1 - x.1)
+                    .collect::<Vec<f64>>();
+                let mean_difference = differences.iter().sum::<f64>() / differences.len() as f64;
+                let deviations = differences
+                    .iter()
+                    .map(|difference| difference - mean_difference)
+                    .collect();
+                export_derived_columns(
+                    csv_data,
+                    path,
+                    vec![
+                        (String::from("difference"), differences),
+                        (String::from("difference deviation"), deviations),
+                    ],
+                )?;
+            }
+            summary
+        }
+        Statistic::IndependentGroupsT {
+            categorical_column,
+            continuous_column,
+            ref export_derived,
+            ref labels,
+            ..
+        } => {
+            let categorical_column = resolved(categorical_column)?;
+            let categorical_vec = csv_data.get_column_cached::<String>(categorical_column)?;
+            let categorical_data =
+                categorical_data_array(csv_data, &categorical_vec, categorical_column)?;
+            let continuous_data = continuous_data_array(csv_data, resolved(continuous_column)?)?;
+            let result = IndependentGroupsT::new(
+                labels.name(format!(
+                    "{} vs {}",
+                    categorical_data.name, continuous_data.name
+                )),
+                labels.description(format!(
+                    "The means of '{}' differ between the levels of '{}'.",
+                    continuous_data.name, categorical_data.name
+                )),
+                &categorical_data,
+                &continuous_data,
+            )?;
+            let summary = vec![("t".to_string(), result.t), ("p".to_string(), result.p)];
+            result.print()?;
+            if let Some(path) = export_derived {
+                let mut group_means = vec![0.0; continuous_data.n];
+                for rows in categorical_data.levels.values() {
+                    let level_mean = rows
+                        .iter()
+                        .map(|row| continuous_data.data_array.data[*row].1)
+                        .sum::<f64>()
+                        / rows.len() as f64;
+                    rows.iter().for_each(|row| group_means[*row] = level_mean);
+                }
+                let deviations = continuous_data
+                    .data_array
+                    .data
+                    .iter()
+                    .zip(group_means.iter())
+                    .map(|(x, group_mean)| x.1 - group_mean)
+                    .collect();
+                export_derived_columns(
+                    csv_data,
+                    path,
+                    vec![
+                        (format!("{} group mean", continuous_data.name), group_means),
+                        (
+                            format!("{} deviation from group mean", continuous_data.name),
+                            deviations,
+                        ),
+                    ],
+                )?;
+            }
+            summary
+        }
+        Statistic::Anova {
+            categorical_column,
+            continuous_column,
+            ref labels,
+            ..
+        } => {
+            let categorical_column = resolved(categorical_column)?;
+            let categorical_vec = csv_data.get_column_cached::<String>(categorical_column)?;
+            let categorical_data =
+                categorical_data_array(csv_data, &categorical_vec, categorical_column)?;
+            let continuous_data = continuous_data_array(csv_data, resolved(continuous_column)?)?;
+            let result = ANOVA::new(
+                labels.name(format!(
+                    "{} vs {}",
+                    categorical_data.name, continuous_data.name
+                )),
+                labels.description(format!(
+                    "There is a difference in the means of '{}' between the levels of '{}'.",
+                    continuous_data.name, categorical_data.name
+                )),
+                &categorical_data,
+                &continuous_data,
+            )?;
+            let summary = vec![("F".to_string(), result.f), ("p".to_string(), result.p)];
+            result.print()?;
+            summary
+        }
+        Statistic::MannWhitneyU {
+            categorical_column,
+            continuous_column,
+            ref labels,
+            ..
+        } => {
+            let categorical_column = resolved(categorical_column)?;
+            let categorical_vec = csv_data.get_column_cached::<String>(categorical_column)?;
+            let categorical_data =
+                categorical_data_array(csv_data, &categorical_vec, categorical_column)?;
+            let continuous_data = continuous_data_array(csv_data, resolved(continuous_column)?)?;
+            let result = MannWhitneyU::new(
+                labels.name(format!(
+                    "{} vs {}",
+                    categorical_data.name, continuous_data.name
+                )),
+                labels.description(format!(
+                    "The distribution of '{}' differs between the levels of '{}'.",
+                    continuous_data.name, categorical_data.name
+                )),
+                &categorical_data,
+                &continuous_data,
+            )?;
+            let summary = vec![("U".to_string(), result.u), ("p".to_string(), result.p)];
+            result.print()?;
+            summary
+        }
+        Statistic::KruskalWallis {
+            categorical_column,
+            continuous_column,
+            ref labels,
+            ..
+        } => {
+            let categorical_column = resolved(categorical_column)?;
+            let categorical_vec = csv_data.get_column_cached::<String>(categorical_column)?;
+            let categorical_data =
+                categorical_data_array(csv_data, &categorical_vec, categorical_column)?;
+            let continuous_data = continuous_data_array(csv_data, resolved(continuous_column)?)?;
+            let result = KruskalWallis::new(
+                labels.name(format!(
+                    "{} vs {}",
+                    categorical_data.name, continuous_data.name
+                )),
+                labels.description(format!(
+                    "The distribution of '{}' differs between the levels of '{}'.",
+                    continuous_data.name, categorical_data.name
+                )),
+                &categorical_data,
+                &continuous_data,
+            )?;
+            let summary = vec![("H".to_string(), result.h), ("p".to_string(), result.p)];
+            result.print()?;
+            summary
+        }
+        Statistic::Correlation {
+            column_x,
+            column_y,
+            cluster,
+            ref labels,
+            ..
+        } => {
+            let data_x = continuous_data_array(csv_data, resolved(column_x)?)?;
+            let data_y = continuous_data_array(csv_data, resolved(column_y)?)?;
+            let mut result = Correlation::new(
+                labels.name(format!("{} vs {}", data_x.name, data_y.name)),
+                labels.description(format!(
+                    "'{}' and '{}' are correlated.",
+                    data_x.name, data_y.name
+                )),
+                &data_x,
+                &data_y,
+            )?;
+            let cluster_vec;
+            if let Some(cluster) = cluster {
+                cluster_vec = csv_data.get_column_cached::<String>(cluster)?;
+                result = result.with_clusters(&categorical_data_array(
+                    csv_data,
+                    &cluster_vec,
+                    cluster,
+                )?)?;
+            }
+            let summary = vec![
+                ("r".to_string(), result.pearson_r),
+                ("p".to_string(), result.pearson_p),
+            ];
+            result.print()?;
+            summary
+        }
+        Statistic::WilcoxonSignedRank {
+            column_x,
+            column_y,
+            ref labels,
+            ..
+        } => {
+            let data_x = continuous_data_array(csv_data, resolved(column_x)?)?;
+            let data_y = continuous_data_array(csv_data, resolved(column_y)?)?;
+            let result = WilcoxonSignedRank::new(
+                labels.name(format!("{} vs {}", data_x.name, data_y.name)),
+                labels.description(format!(
+                    "There is a difference between paired '{}' and '{}'.",
+                    data_x.name, data_y.name
+                )),
+                &data_x,
+                &data_y,
+            )?;
+            let summary = vec![("W".to_string(), result.w), ("p".to_string(), result.p)];
+            result.print()?;
+            summary
+        }
+        Statistic::MultiRegression {
+            dependent_column,
+            ref predictor_columns,
+            ref formula,
+            cluster,
+            ref export_derived,
+            ref plots,
+            ref chart_out,
+            ref labels,
+            ..
+        } => {
+            // a formula's design columns are the predictors, over the rows it keeps
+            let design = formula
+                .as_deref()
+                .map(|formula| Formula::parse(formula)?.design(csv_data))
+                .transpose()?;
+            let complete_rows;
+            let csv_data = match &design {
+                Some(design) => {
+                    complete_rows = csv_data.without_rows(&design.dropped_rows);
+                    &complete_rows
+                }
+                None => csv_data,
+            };
+            let (y_data, x_data) = match design {
+                Some(design) => design_data_arrays(csv_data, design)?,
+                None => (
+                    continuous_data_array(csv_data, resolved(dependent_column)?)?,
+                    predictor_columns
+                        .iter()
+                        .map(|column| continuous_data_array(csv_data, *column))
+                        .collect::<Result<Vec<Arc<ContinuousDataArray>>, Error>>()?,
+                ),
+            };
+            let predictor_names = x_data
+                .iter()
+                .map(|x| x.name.clone())
+                .collect::<Vec<String>>()
+                .join(" + ");
+            let mut result = MultipleRegression::new(
+                labels.name(format!("{} ~ {}", y_data.name, predictor_names)),
+                labels.description(format!(
+                    "'{}' can be predicted from {}.",
+                    y_data.name, predictor_names
+                )),
+                &y_data,
+                x_data.iter().map(|x| &**x).collect(),
+            )?;
+            let cluster_vec;
+            if let Some(cluster) = cluster {
+                cluster_vec = csv_data.get_column_cached::<String>(cluster)?;
+                result = result.with_clusters(&categorical_data_array(
+                    csv_data,
+                    &cluster_vec,
+                    cluster,
+                )?)?;
+            }
+            let summary = vec![
+                ("F".to_string(), result.f),
+                ("p".to_string(), result.p),
+                ("R^2".to_string(), result.r_squared),
+            ];
+            if let Some(path) = export_derived {
+                export_derived_columns(
+                    csv_data,
+                    path,
+                    vec![
+                        (
+                            format!("{} fitted", y_data.name),
+                            result.fitted_values().to_vec(),
+                        ),
+                        (
+                            format!("{} residual", y_data.name),
+                            result.residuals().to_vec(),
+                        ),
+                        (
+                            format!("{} standardized residual", y_data.name),
+                            result.standardized_residuals(),
+                        ),
+                        (
+                            format!("{} leverage", y_data.name),
+                            result.leverages().to_vec(),
+                        ),
+                        (
+                            format!("{} Cook's distance", y_data.name),
+                            result.cooks_distances().to_vec(),
+                        ),
+                    ],
+                )?;
+            }
+            // drawn after the model's output, which a terminal chart would otherwise precede
+            let graph = match (plots, chart_out) {
+                (_, Some(path)) => Some(image_graph(path, true)?),
+                (Some(directory), None) => Some(graph(directory)),
+                (None, None) => None,
+            };
+            let residuals = graph.as_ref().map(|_| {
+                (
+                    result.fitted_values().to_vec(),
+                    result.residuals().to_vec(),
+                    result.studentized_residuals(),
+                )
+            });
+            result.print()?;
+            if let (Some(graph), Some((fitted, residuals, studentized))) = (graph, residuals) {
+                let paths = time_export(|| {
+                    residual_plots(&y_data.name, &*graph, &fitted, &residuals, &studentized)
+                })?;
+                if !paths.is_empty() {
+                    report!(
+                        "Wrote residual plots to {}",
+                        paths
+                            .iter()
+                            .map(|path| path.display().to_string())
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    );
+                }
+            }
+            summary
+        }
+        Statistic::MultipleResponse {
+            column,
+            ref delimiter,
+            by,
+            ref labels,
+            ..
+        } => {
+            let column = resolved(column)?;
+            let values = csv_data.get_column_cached::<String>(column)?;
+            let group_values = by
+                .map(|by| csv_data.get_column_cached::<String>(by))
+                .transpose()?;
+            let groups = match (by, group_values.as_ref()) {
+                (Some(by), Some(group_values)) => {
+                    Some(categorical_data_array(csv_data, group_values, by)?)
+                }
+                _ => None,
+            };
+            let name = header_name(csv_data, column);
+            let result = MultipleResponse::new(
+                labels.name(format!("{} Multiple Response", name)),
+                labels.description(match &groups {
+                    Some(groups) => format!(
+                        "The options chosen in '{}' differ in popularity or between the levels of '{}'.",
+                        name, groups.name
+                    ),
+                    None => format!("The options chosen in '{}' differ in popularity.", name),
+                }),
+                &values,
+                delimiter,
+                groups.as_ref(),
+            )?;
+            let summary = vec![
+                ("Q".to_string(), result.cochran_q),
+                ("p".to_string(), result.p),
+            ];
+            result.print()?;
+            summary
+        }
+        Statistic::Formula {
+            ref formula,
+            ref labels,
+        } => {
+            let formula = Formula::parse(formula)?;
+            match formula.one_way_factor(csv_data)? {
+                Some((categorical_column, levels)) if levels >= 3 => {
+                    let csv_data = csv_data.without_rows(&formula.missing_rows(csv_data)?);
+                    let continuous_column = formula.response_column(&csv_data)?;
+                    run_statistic(
+                        &csv_data,
+                        &Statistic::Anova {
+                            categorical_column: Some(categorical_column),
+                            categorical_column_name: None,
+                            continuous_column: Some(continuous_column),
+                            continuous_column_name: None,
+                            labels: labels.clone(),
+                        },
+                    )?
+                }
+                _ => {
+                    let design = formula.design(csv_data)?;
+                    let term_names = design
+                        .terms
+                        .iter()
+                        .map(|term| term.name.clone())
+                        .collect::<Vec<String>>()
+                        .join(" + ");
+                    let result = LinearModel::new(
+                        labels.name(formula.expanded()),
+                        labels.description(format!(
+                            "'{}' can be predicted from {}.",
+                            design.response_name, term_names
+                        )),
+                        design,
+                    )?;
+                    let summary = result
+                        .terms
+                        .iter()
+                        .flat_map(|term| {
+                            [
+                                (format!("F({})", term.name), term.f),
+                                (format!("p({})", term.name), term.p),
+                            ]
+                        })
+                        .collect();
+                    result.print()?;
+                    summary
+                }
+            }
+        }
+        Statistic::OrdinalRegression {
+            ref formula,
+            ref levels,
+            ref labels,
+        } => {
+            let formula = Formula::parse(formula)?;
+            let order = levels
+                .iter()
+                .flat_map(|levels| levels.split(','))
+                .map(|level| level.trim().to_string())
+                .filter(|level| !level.is_empty())
+                .collect::<Vec<String>>();
+            let (design, levels) = formula.ordinal_design(csv_data, &order)?;
+            let term_names = design
+                .terms
+                .iter()
+                .map(|term| term.name.clone())
+                .collect::<Vec<String>>()
+                .join(" + ");
+            let result = OrdinalRegression::new(
+                labels.name(formula.expanded()),
+                labels.description(format!(
+                    "Higher levels of '{}' can be predicted from {}.",
+                    design.response_name, term_names
+                )),
+                design,
+                levels,
+            )?;
+            let summary = vec![
+                ("chi^2".to_string(), result.chi_square),
+                ("p".to_string(), result.p),
+            ];
+            result.print()?;
+            summary
+        }
+        Statistic::CountRegression {
+            ref formula,
+            model,
+            ref labels,
+        } => {
+            let formula = Formula::parse(formula)?;
+            let design = formula.design(csv_data)?;
+            let term_names = design
+                .terms
+                .iter()
+                .map(|term| term.name.clone())
+                .collect::<Vec<String>>()
+                .join(" + ");
+            let result = CountRegression::new(
+                labels.name(formula.expanded()),
+                labels.description(format!(
+                    "The count '{}' can be predicted from {}.",
+                    design.response_name, term_names
+                )),
+                design,
+                model,
+            )?;
+            let summary = vec![
+                ("chi^2".to_string(), result.chi_square),
+                ("p".to_string(), result.p),
+                ("dispersion".to_string(), result.dispersion),
+            ];
+            result.print()?;
+            summary
+        }
+        Statistic::Histogram {
+            column,
+            bins,
+            ref output,
+            ref chart_out,
+            ..
+        } => {
+            let data = continuous_data_array(csv_data, resolved(column)?)?;
+            let bins = bins.unwrap_or_else(|| default_bins(data.n));
+            let graph = chart_graph(output, chart_out.as_deref())?;
+            if let Some(path) = time_export(|| histogram(&data, bins, &*graph))? {
+                report!(
+                    "Wrote a histogram of '{}' ({} bins) to {}",
+                    data.name,
+                    bins,
+                    path.display()
+                );
+            }
+            vec![]
+        }
+        Statistic::Boxplot {
+            continuous_column,
+            categorical_column,
+            ref output,
+            ref chart_out,
+            ..
+        } => {
+            let continuous_data = continuous_data_array(csv_data, resolved(continuous_column)?)?;
+            let graph = chart_graph(output, chart_out.as_deref())?;
+            match categorical_column {
+                Some(categorical_column) => {
+                    let categorical_vec =
+                        csv_data.get_column_cached::<String>(categorical_column)?;
+                    let categorical_data =
+                        categorical_data_array(csv_data, &categorical_vec, categorical_column)?;
+                    if let Some(path) =
+                        time_export(|| boxplot(&continuous_data, Some(&categorical_data), &*graph))?
+                    {
+                        report!(
+                            "Wrote a boxplot of '{}' by '{}' ({} levels) to {}",
+                            continuous_data.name,
+                            categorical_data.name,
+                            categorical_data.levels.len(),
+                            path.display()
+                        );
+                    }
+                }
+                None => {
+                    if let Some(path) = time_export(|| boxplot(&continuous_data, None, &*graph))? {
+                        report!(
+                            "Wrote a boxplot of '{}' to {}",
+                            continuous_data.name,
+                            path.display()
+                        );
+                    }
+                }
+            }
+            vec![]
+        }
+        Statistic::Inspect { samples } => {
+            print_columns(csv_data, samples)?;
+            vec![]
+        }
+    })
+}
+
+/// What a single chart is drawn with: an image file at `chart_out` when given, otherwise
+/// the selected backend, whose HTML goes in `directory`.
+fn chart_graph(directory: &Path, chart_out: Option<&Path>) -> Result<Box<dyn Graph>, Error> {
+    match chart_out {
+        Some(path) => image_graph(path, false),
+        None => Ok(graph(directory)),
+    }
+}
+
+/// A column argument after `resolve_column_names`, which leaves every one set.
+fn resolved(column: Option<usize>) -> Result<usize, Error> {
+    column.ok_or_else(|| anyhow!("A column was given by name but not resolved to an index"))
+}
+
+/// Runs `statistic` on the rows of each level of `group_column` in turn, then prints a
+/// table of each level's headline numbers. A level the statistic fails on is reported
+/// and left out of the table.
+fn run_grouped(
+    csv_data: &CSVData,
+    statistic: &Statistic,
+    group_column: usize,
+) -> Result<(), Error> {
+    let group_values = csv_data.get_column_cached::<String>(group_column)?;
+    let groups = categorical_data_array(csv_data, &group_values, group_column)?;
+
+    let mut results: Vec<(String, usize, Summary)> = vec![];
+    for (level, rows) in groups.levels.iter() {
+        report!(
+            "{}",
+            logging::format_title(&format!("{} = {}", groups.name, level))
+        );
+        match run_statistic(&csv_data.with_rows(rows), statistic) {
+            Ok(summary) => results.push((level.to_string(), rows.len(), summary)),
+            Err(error) => report_warning!(
+                "Skipped {} = {} ({} rows): {}",
+                groups.name,
+                level,
+                rows.len(),
+                error
+            ),
+        }
+    }
+    if results.is_empty() {
+        return Err(anyhow!(
+            "The statistic could not be run for any level of '{}'",
+            groups.name
+        ));
+    }
+
+    // columns in order of first appearance, so levels missing a term still line up
+    let mut labels: Vec<&String> = vec![];
+    for (label, _) in results.iter().flat_map(|(_, _, summary)| summary.iter()) {
+        if !labels.contains(&label) {
+            labels.push(label);
+        }
+    }
+    let level_width = results
+        .iter()
+        .map(|(level, _, _)| level.chars().count())
+        .chain(std::iter::once(groups.name.chars().count()))
+        .max()
+        .unwrap_or_default();
+    let value_width = labels
+        .iter()
+        .map(|label| label.chars().count())
+        .chain(std::iter::once(10))
+        .max()
+        .unwrap_or_default();
+
+    report!(
+        "{}",
+        logging::format_title(&format!("Results by {}", groups.name))
+    );
+    report!(
+        "{:<level_width$}  {:>5}{}",
+        groups.name,
+        "n",
+        labels
+            .iter()
+            .map(|label| format!("  {:>value_width$}", label))
+            .collect::<String>()
+    );
+    for (level, n, summary) in results.iter() {
+        report!(
+            "{:<level_width$}  {:>5}{}",
+            level,
+            n,
+            labels
+                .iter()
+                .map(|label| {
+                    let value = summary
+                        .iter()
+                        .find(|(name, _)| name == *label)
+                        .map_or(String::from("n/a"), |(_, value)| format!("{:.4}", value));
+                    format!("  {:>value_width$}", value)
+                })
+                .collect::<String>()
+        );
+    }
+
+    Ok(())
+}
+
+fn export_derived_columns(
+    csv_data: &CSVData,
+    path: &Path,
+    columns: Vec<(String, Vec<f64>)>,
+) -> Result<(), Error> {
+    time_export(|| csv_data.export_with_columns(path, &columns))?;
+    report!(
+        "Wrote {} to {}",
+        columns
+            .iter()
+            .map(|(name, _)| format!("'{}'", name))
+            .collect::<Vec<String>>()
+            .join(", "),
+        path.display()
+    );
+    Ok(())
+}
+
+/// Reads the data at `path`, joins the `--join` file onto it, keeps the rows `--filter`
+/// holds for, and reshapes it by `--melt` or `--pivot`. With `--sample`, also returns the
+/// number of rows the sample was drawn from.
+pub fn import_data(path: &Path, options: &DataOptions) -> Result<(CSVData, Option<usize>), Error> {
+    if options.filter.is_none() && options.melt.is_none() && options.pivot.is_none() {
+        return join_data(path, options);
+    }
+
+    // filtering and reshaping see every row, so the sample is drawn from their result
+    let whole = DataOptions {
+        sample: None,
+        ..options.clone()
+    };
+    let (mut csv_data, _) = join_data(path, &whole)?;
+    if let Some(filter) = &options.filter {
+        let rows = Filter::parse(filter)?.matching_rows(&csv_data)?;
+        report!(
+            "Filtered to {} of {} rows",
+            rows.len(),
+            csv_data.column_count
+        );
+        csv_data = csv_data.with_rows(&rows);
+    }
+    csv_data = reshape_data(csv_data, options)?;
+    Ok(match (options.sample, options.seed) {
+        (Some(0), _) => return Err(anyhow!("--sample needs at least one row")),
+        (Some(size), Some(seed)) => {
+            let (csv_data, total_rows) = csv_data.sample_rows(size, seed)?;
+            (csv_data, Some(total_rows))
+        }
+        _ => (csv_data, None),
+    })
+}
+
+/// The names `--melt-into` gives the stacked columns when it is omitted.
+const MELT_INTO: [&str; 2] = ["condition", "value"];
+
+/// Melts or pivots the data as the options ask, reporting the new shape.
+fn reshape_data(csv_data: CSVData, options: &DataOptions) -> Result<CSVData, Error> {
+    let reshaped = if let Some(melt) = &options.melt {
+        let columns = melt_columns(&csv_data, melt)?;
+        let (key_name, value_name) = match &options.melt_into[..] {
+            [key_name, value_name] => (key_name.as_str(), value_name.as_str()),
+            _ => (MELT_INTO[0], MELT_INTO[1]),
+        };
+        csv_data.melt(&columns, key_name, value_name)?
+    } else if let (Some(id), Some(key), Some(value)) =
+        (&options.pivot, &options.pivot_key, &options.pivot_value)
+    {
+        csv_data.pivot(
+            find_header(&csv_data, id)?,
+            find_header(&csv_data, key)?,
+            find_header(&csv_data, value)?,
+        )?
+    } else {
+        return Ok(csv_data);
+    };
+    report!(
+        "Reshaped {} rows x {} columns to {} rows x {} columns: {}",
+        csv_data.column_count,
+        csv_data.row_length,
+        reshaped.column_count,
+        reshaped.row_length,
+        reshaped
+            .headers
+            .iter()
+            .enumerate()
+            .map(|(index, header)| format!("{} {}", index, header))
+            .collect::<Vec<String>>()
+            .join(", ")
+    );
+    Ok(reshaped)
+}
+
+/// The columns a `--melt` list names: comma-separated 0-based indices, inclusive ranges
+/// (`3..6`), or headers.
+fn melt_columns(csv_data: &CSVData, melt: &str) -> Result<Vec<usize>, Error> {
+    let mut columns = vec![];
+    for item in melt.split(',').map(str::trim) {
+        if let Ok(column) = item.parse::<usize>() {
+            columns.push(column);
+        } else if let Some((first, last)) = item.split_once("..") {
+            let (Ok(first), Ok(last)) =
+                (first.trim().parse::<usize>(), last.trim().parse::<usize>())
+            else {
+                return Err(anyhow!("'{}' is not a range of columns such as 3..6", item));
+            };
+            columns.extend(first..=last);
+        } else {
+            columns.push(find_header(csv_data, item)?);
+        }
+    }
+    Ok(distinct(columns.into_iter()))
+}
+
+/// Reads the data at `path` and joins the `--join` file onto it.
+fn join_data(path: &Path, options: &DataOptions) -> Result<(CSVData, Option<usize>), Error> {
+    let (csv_data, total_rows) = read_data(path, options)?;
+    let (Some(join), Some(join_on)) = (&options.join, &options.join_on) else {
+        return Ok((csv_data, total_rows));
+    };
+
+    // the joined file is read whole, with its own delimiter and format
+    let join_options = DataOptions {
+        no_headers: options.no_headers,
+        ..Default::default()
+    };
+    let (other, _) = read_data(join, &join_options)?;
+    let label = join
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| join.display().to_string());
+    let joined = csv_data.join(
+        &other,
+        find_header(&csv_data, join_on)?,
+        find_header(&other, join_on)?,
+        options.join_type,
+        &label,
+    )?;
+    report!(
+        "Joined '{}' on '{}': {} of {} rows",
+        join.display(),
+        join_on,
+        joined.column_count,
+        csv_data.column_count
+    );
+    Ok((joined, total_rows))
+}
+
+/// Reads JSON (or JSON lines) by `--format` or extension, Arrow IPC files when built with
+/// the `arrow` feature, and CSV otherwise.
+fn read_data(path: &Path, options: &DataOptions) -> Result<(CSVData, Option<usize>), Error> {
+    let sample = match (options.sample, options.seed) {
+        (Some(0), _) => return Err(anyhow!("--sample needs at least one row")),
+        (Some(size), Some(seed)) => Some((size, seed)),
+        _ => None,
+    };
+    let delimiter = options.delimiter.map(|delimiter| delimiter.byte());
+
+    // files read whole are sampled after reading
+    let sampled = |csv_data: CSVData| -> Result<(CSVData, Option<usize>), Error> {
+        Ok(match sample {
+            Some((size, seed)) => {
+                let (csv_data, total_rows) = csv_data.sample_rows(size, seed)?;
+                (csv_data, Some(total_rows))
+            }
+            None => (csv_data, None),
+        })
+    };
+
+    let json = match options.format {
+        Some(format) => format == InputFormat::Json,
+        None => is_json_file(path),
+    };
+    if json {
+        return sampled(import_json_data(path)?);
+    }
+    #[cfg(feature = "arrow")]
+    if options.format.is_none() && crate::functions::arrow::is_arrow_file(path) {
+        return sampled(crate::functions::arrow::import_arrow_data(path)?);
+    }
+    Ok(match sample {
+        Some((size, seed)) => {
+            let (csv_data, total_rows) =
+                import_csv_sample(path, Some(!options.no_headers), delimiter, size, seed)?;
+            (csv_data, Some(total_rows))
+        }
+        None => (
+            import_csv_data(path, Some(!options.no_headers), delimiter)?,
+            None,
+        ),
+    })
+}
+
+/// Every column any of `statistics` reads as continuous, in order of first use.
+pub fn continuous_columns(statistics: &[Statistic], csv_data: &CSVData) -> Vec<usize> {
+    distinct(
+        statistics
+            .iter()
+            .flat_map(|statistic| statistic.continuous_columns(csv_data)),
+    )
+}
+
+/// Every column any of `statistics` reads as categorical, in order of first use.
+pub fn categorical_columns(statistics: &[Statistic], csv_data: &CSVData) -> Vec<usize> {
+    distinct(
+        statistics
+            .iter()
+            .flat_map(|statistic| statistic.categorical_columns(csv_data)),
+    )
+}
+
+fn distinct(columns: impl Iterator<Item = usize>) -> Vec<usize> {
+    let mut distinct = vec![];
+    for column in columns {
+        if !distinct.contains(&column) {
+            distinct.push(column);
+        }
+    }
+    distinct
+}
+
+/// Applies each of `options.transform` in order to the selected continuous columns.
+fn transform_columns(
+    mut csv_data: CSVData,
+    continuous_columns: &[usize],
+    options: &DataOptions,
+) -> Result<CSVData, Error> {
+    let columns = if options.transform_column.is_empty() {
+        continuous_columns.to_vec()
+    } else {
+        if let Some(column) = options
+            .transform_column
+            .iter()
+            .find(|column| !continuous_columns.contains(column))
+        {
+            return Err(anyhow!(
+                "--transform-column {} is not a continuous column of the statistics",
+                column
+            ));
+        }
+        options.transform_column.clone()
+    };
+    for transform in options.transform.iter() {
+        for column in columns.iter() {
+            transform_column(&mut csv_data, *column, *transform)?;
+        }
+    }
+    Ok(csv_data)
+}
+
+/// Applies each of `options.recode` in order, then `--collapse-below` and `--reference`,
+/// to the selected categorical columns.
+fn recode_columns(
+    mut csv_data: CSVData,
+    categorical_columns: &[usize],
+    options: &DataOptions,
+) -> Result<CSVData, Error> {
+    let columns = if options.recode_column.is_empty() {
+        categorical_columns.to_vec()
+    } else {
+        if let Some(column) = options
+            .recode_column
+            .iter()
+            .find(|column| !categorical_columns.contains(column))
+        {
+            return Err(anyhow!(
+                "--recode-column {} is not a categorical column of the statistics",
+                column
+            ));
+        }
+        options.recode_column.clone()
+    };
+    let recodes = options
+        .recode
+        .iter()
+        .map(|recode| Recode::parse(recode))
+        .collect::<Result<Vec<Recode>, Error>>()?;
+    for recode in recodes.iter() {
+        for column in columns.iter() {
+            recode_column(&mut csv_data, *column, recode)?;
+        }
+    }
+    if let Some(min_count) = options.collapse_below {
+        for column in columns.iter() {
+            collapse_sparse_levels(&mut csv_data, *column, min_count)?;
+        }
+    }
+    if let Some(reference) = &options.reference {
+        let mut found = false;
+        for column in columns.iter() {
+            found |= set_reference_level(&mut csv_data, *column, reference)?;
+        }
+        if !found {
+            return Err(anyhow!(
+                "--reference '{}' is not a level of the statistic's categorical columns",
+                reference
+            ));
+        }
+    }
+    Ok(csv_data)
+}
+
+/// `csv_data` without the rows missing a value in any column `statistic` reads (or in
+/// the --group-by column), for `--missing analysis`.
+fn complete_cases(
+    csv_data: &CSVData,
+    statistic: &Statistic,
+    group_by: Option<usize>,
+) -> Result<CSVData, Error> {
+    let continuous_columns = statistic.continuous_columns(csv_data);
+    let categorical_columns = statistic
+        .categorical_columns(csv_data)
+        .into_iter()
+        .chain(group_by)
+        .collect::<Vec<usize>>();
+    csv_data.clone().apply_missing_policy(
+        MissingPolicy::Analysis,
+        &continuous_columns,
+        &categorical_columns,
+    )
+}
+
+/// Drops every row flagged by `method` in any of the given continuous columns,
+/// reporting the rows found in each column.
+fn exclude_outlier_rows(
+    csv_data: CSVData,
+    continuous_columns: &[usize],
+    method: OutlierMethod,
+) -> Result<CSVData, Error> {
+    let mut excluded_rows = vec![];
+    for column in continuous_columns.iter().copied() {
+        let rows = column_outlier_rows(&csv_data, column, method)?;
+        if !rows.is_empty() {
+            report!(
+                "Excluding {} outlier row(s) ({}) in '{}': {:?}",
+                rows.len(),
+                method.label(),
+                header_name(&csv_data, column),
+                rows
+            );
+            excluded_rows.extend(rows);
+        }
+    }
+    Ok(if excluded_rows.is_empty() {
+        csv_data
+    } else {
+        csv_data.without_rows(&excluded_rows)
+    })
+}
+
+/// Reports the rows flagged by `method` in each of the given continuous columns,
+/// leaving them in the data.
+fn flag_outlier_rows(
+    csv_data: &CSVData,
+    continuous_columns: &[usize],
+    method: OutlierMethod,
+) -> Result<(), Error> {
+    for column in continuous_columns.iter().copied() {
+        let rows = column_outlier_rows(csv_data, column, method)?;
+        if !rows.is_empty() {
+            report!(
+                "'{}' has {} outlier(s) ({}) at row(s) {:?}",
+                header_name(csv_data, column),
+                rows.len(),
+                method.label(),
+                rows
+            );
+        }
+    }
+    Ok(())
+}
+
+fn header_name(csv_data: &CSVData, column: usize) -> String {
+    csv_data
+        .headers
+        .get(column)
+        .cloned()
+        .unwrap_or_else(|| format!("Column {}", column))
+}
+
+/// The column's data array, built once per column and shared by every analysis of the
+/// same data (such as several run from the menu).
+fn continuous_data_array(
+    csv_data: &CSVData,
+    column: usize,
+) -> Result<Arc<ContinuousDataArray>, Error> {
+    let population = Some(false);
+    csv_data.get_derived_cached(column, &format!("{:?}", population), || {
+        let data = csv_data.get_column_cached::<f64>(column)?;
+        ContinuousDataArray::new(header_name(csv_data, column), &data, column, population)
+    })
+}
+
+fn categorical_data_array<'a>(
+    csv_data: &CSVData,
+    data: &'a Vec<String>,
+    column: usize,
+) -> Result<CategoricalDataArray<'a>, Error> {
+    CategoricalDataArray::new(header_name(csv_data, column), data, column, Some(false))
+}
+
+/// The response and one predictor per design column, named as the formula writes them.
+/// A design column isn't a column of the data, so it takes its position in the design
+/// as its index.
+fn design_data_arrays(
+    csv_data: &CSVData,
+    design: Design,
+) -> Result<(Arc<ContinuousDataArray>, Vec<Arc<ContinuousDataArray>>), Error> {
+    let y_data = ContinuousDataArray::new(
+        design.response_name.clone(),
+        &design.response,
+        find_header(csv_data, &design.response_name)?,
+        Some(false),
+    )?;
+    let x_data = design
+        .columns
+        .into_iter()
+        .enumerate()
+        .map(|(index, column)| {
+            ContinuousDataArray::new(column.name, &column.values, index, Some(false)).map(Arc::new)
+        })
+        .collect::<Result<Vec<Arc<ContinuousDataArray>>, Error>>()?;
+    Ok((Arc::new(y_data), x_data))
+}
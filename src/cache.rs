@@ -0,0 +1,69 @@
+const CACHE_DIR_NAME: &str = "cache";
+
+use crate::arg_handler::{DataOptions, Statistic};
+use crate::config::config_dir;
+use crate::functions::graph::plot_backend;
+use crate::logging::title_style;
+use crate::provenance::{sha256_hex, Provenance};
+use crate::reporter::{report_warning, ReportedLine};
+use anyhow::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// A previously printed statistic, stored as JSON under `<config dir>/cache/<key>.json`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CacheEntry {
+    pub key: String,
+    pub provenance: Provenance,
+    pub lines: Vec<ReportedLine>,
+}
+
+/// SHA-256 over the stisty version, the import options, the statistics with their
+/// arguments, the plot backend they draw with, the banner style they print with, and
+/// the hash of the CSV file. Any change to one of them is a miss.
+pub fn cache_key(file_hash: &str, options: &DataOptions, statistics: &[Statistic]) -> String {
+    sha256_hex(format!(
+        "{}|{:?}|{:?}|{:?}|{:?}|{}",
+        env!("CARGO_PKG_VERSION"),
+        options,
+        statistics,
+        plot_backend(),
+        title_style(),
+        file_hash
+    ))
+}
+
+/// The cached entry for `key`, if one exists and was written by this version of stisty.
+pub fn load(key: &str) -> Option<CacheEntry> {
+    let contents = fs::read_to_string(entry_path(key).ok()?).ok()?;
+    let entry = serde_json::from_str::<CacheEntry>(&contents).ok()?;
+    (entry.key == key && entry.provenance.stisty_version == env!("CARGO_PKG_VERSION"))
+        .then_some(entry)
+}
+
+/// Writes printed output under `key`. Failing to persist is logged, not fatal.
+pub fn store(key: &str, provenance: Provenance, lines: Vec<ReportedLine>) {
+    let entry = CacheEntry {
+        key: key.to_string(),
+        provenance,
+        lines,
+    };
+    if let Err(error) = try_store(&entry) {
+        report_warning!("Could not write result cache: {}", error);
+    }
+}
+
+fn try_store(entry: &CacheEntry) -> Result<(), Error> {
+    fs::write(
+        entry_path(&entry.key)?,
+        serde_json::to_string_pretty(entry)?,
+    )?;
+    Ok(())
+}
+
+fn entry_path(key: &str) -> Result<PathBuf, Error> {
+    let dir = config_dir()?.join(CACHE_DIR_NAME);
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join(format!("{}.json", key)))
+}
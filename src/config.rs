@@ -0,0 +1,55 @@
+const APP_DIR_NAME: &str = "stisty";
+const RECENT_FILES_NAME: &str = "recent_files";
+const RECENT_FILES_LIMIT: usize = 8;
+
+use crate::reporter::report_warning;
+use anyhow::{anyhow, Error, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Per-user configuration directory (e.g. `~/.config/stisty` on Linux), created on first use.
+pub fn config_dir() -> Result<PathBuf, Error> {
+    let dir = dirs::config_dir()
+        .ok_or_else(|| anyhow!("Could not determine a configuration directory"))?
+        .join(APP_DIR_NAME);
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Most recently used file paths, newest first. A missing or unreadable history is empty.
+pub fn recent_files() -> Vec<String> {
+    let history_path = match config_dir() {
+        Ok(dir) => dir.join(RECENT_FILES_NAME),
+        Err(_) => return vec![],
+    };
+    fs::read_to_string(history_path)
+        .map(|contents| {
+            contents
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Moves `path` to the front of the recent files history, keeping at most
+/// `RECENT_FILES_LIMIT` entries. Failing to persist is logged, not fatal.
+pub fn push_recent_file(path: &Path) {
+    if let Err(error) = try_push_recent_file(path) {
+        report_warning!("Could not update recent files history: {}", error);
+    }
+}
+
+fn try_push_recent_file(path: &Path) -> Result<(), Error> {
+    let path = fs::canonicalize(path)?.to_string_lossy().to_string();
+    let mut history = recent_files();
+    history.retain(|entry| *entry != path);
+    history.insert(0, path);
+    history.truncate(RECENT_FILES_LIMIT);
+    fs::write(
+        config_dir()?.join(RECENT_FILES_NAME),
+        history.join("\n") + "\n",
+    )?;
+    Ok(())
+}
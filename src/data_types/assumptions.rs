@@ -0,0 +1,273 @@
+use crate::data_types::data_array::{CategoricalDataArray, ContinuousDataArray};
+use crate::functions::distributions::f_p;
+use crate::functions::stats_math::{kurtosis, median, shapiro_wilk, skewness};
+use crate::logging;
+use crate::reporter::{report, report_warning};
+use anyhow::{anyhow, Error, Result};
+
+/// Shapiro-Wilk p-values below this are reported as a departure from normality.
+pub const NORMALITY_ALPHA: f64 = 0.05;
+
+/// Levene and Brown-Forsythe p-values below this are reported as unequal variances.
+pub const HOMOGENEITY_ALPHA: f64 = 0.05;
+
+/// Rule-of-thumb limits (West, Finch, & Curran, 1995) used when Shapiro-Wilk can't be run.
+const SKEWNESS_LIMIT: f64 = 2.0;
+const KURTOSIS_LIMIT: f64 = 7.0;
+
+#[derive(Debug, Clone)]
+pub struct Normality {
+    pub name: String,
+    pub n: usize,
+    pub skewness: Option<f64>,
+    pub kurtosis: Option<f64>, // excess kurtosis, 0 for a normal distribution
+    pub shapiro_wilk_w: Option<f64>,
+    pub shapiro_wilk_p: Option<f64>,
+}
+
+impl Normality {
+    /// Each measure is `None` when the data are too few (or too constant) to compute it.
+    pub fn new(name: String, data: &[f64]) -> Result<Normality, Error> {
+        let (shapiro_wilk_w, shapiro_wilk_p) = match shapiro_wilk(data) {
+            Ok((w, p)) => (Some(w), Some(p)),
+            Err(_) => (None, None),
+        };
+        Ok(Normality {
+            name,
+            n: data.len(),
+            skewness: skewness(data).ok().filter(|x| x.is_finite()),
+            kurtosis: kurtosis(data).ok().filter(|x| x.is_finite()),
+            shapiro_wilk_w,
+            shapiro_wilk_p,
+        })
+    }
+
+    pub fn from_data_array(data: &ContinuousDataArray) -> Result<Normality, Error> {
+        Normality::new(
+            data.name.clone(),
+            &data
+                .data_array
+                .data
+                .iter()
+                .map(|x| x.1)
+                .collect::<Vec<f64>>(),
+        )
+    }
+
+    /// Shapiro-Wilk rejects normality, or, when it can't be run, skewness or
+    /// kurtosis fall outside the usual limits.
+    pub fn is_questionable(&self) -> bool {
+        match self.shapiro_wilk_p {
+            Some(p) => p < NORMALITY_ALPHA,
+            None => {
+                self.skewness.is_some_and(|x| x.abs() > SKEWNESS_LIMIT)
+                    || self.kurtosis.is_some_and(|x| x.abs() > KURTOSIS_LIMIT)
+            }
+        }
+    }
+
+    /// Logs a warning naming the data and the failed check, if normality is questionable.
+    pub fn warn_if_questionable(&self) {
+        if !self.is_questionable() {
+            return;
+        }
+        match (self.shapiro_wilk_w, self.shapiro_wilk_p) {
+            (Some(w), Some(p)) => report_warning!(
+                "'{}' may not be normally distributed (n = {}, Shapiro-Wilk W = {:.4}, p = {:.4}); \
+                 consider a nonparametric test",
+                self.name, self.n, w, p
+            ),
+            _ => report_warning!(
+                "'{}' may not be normally distributed (n = {}, skewness = {}, kurtosis = {})",
+                self.name,
+                self.n,
+                display_option(self.skewness),
+                display_option(self.kurtosis)
+            ),
+        }
+    }
+}
+
+/// A compact summary, printed ahead of a group comparison, of each group's n, skewness,
+/// excess kurtosis, and Shapiro-Wilk p, followed by the Levene and Brown-Forsythe p.
+/// Questionable rows are marked with `*`.
+pub fn print_assumptions_table(
+    normality: &[Normality],
+    homogeneity: Option<&HomogeneityOfVariance>,
+) {
+    let name_width = normality
+        .iter()
+        .map(|check| check.name.chars().count())
+        .chain(std::iter::once("Group".len()))
+        .max()
+        .unwrap_or_default();
+    let format_cell =
+        |value: Option<f64>| value.map_or(String::from("n/a"), |x| format!("{:.3}", x));
+    let marker = |questionable: bool| if questionable { " *" } else { "" };
+
+    report!("{}", logging::format_title("Assumptions"));
+    report!(
+        "{:<name_width$}  {:>5}  {:>8}  {:>8}  {:>8}",
+        "Group",
+        "n",
+        "Skew",
+        "Kurtosis",
+        "S-W p"
+    );
+    for check in normality {
+        report!(
+            "{:<name_width$}  {:>5}  {:>8}  {:>8}  {:>8}{}",
+            check.name,
+            check.n,
+            format_cell(check.skewness),
+            format_cell(check.kurtosis),
+            format_cell(check.shapiro_wilk_p),
+            marker(check.is_questionable())
+        );
+    }
+    if let Some(homogeneity) = homogeneity {
+        report!(
+            "Levene p: {:.3}, Brown-Forsythe p: {:.3}{}",
+            homogeneity.levene_p,
+            homogeneity.brown_forsythe_p,
+            marker(homogeneity.is_questionable())
+        );
+    }
+    if normality.iter().any(|check| check.is_questionable())
+        || homogeneity.is_some_and(|homogeneity| homogeneity.is_questionable())
+    {
+        report!(
+            "* questionable (normality at alpha = {}, equal variances at alpha = {})",
+            NORMALITY_ALPHA,
+            HOMOGENEITY_ALPHA
+        );
+    }
+}
+
+fn display_option(value: Option<f64>) -> String {
+    value.map_or(String::from("n/a"), |x| x.to_string())
+}
+
+/// Levene's test (deviations from each level's mean) and the Brown-Forsythe variant
+/// (deviations from each level's median) for equal variances across the levels of a
+/// categorical variable. Both are a one way ANOVA on the absolute deviations.
+#[derive(Debug, Clone)]
+pub struct HomogeneityOfVariance {
+    pub name: String,
+    pub df_1: usize, // k - 1
+    pub df_2: usize, // N - k
+    pub levene_f: f64,
+    pub levene_p: f64,
+    pub brown_forsythe_f: f64,
+    pub brown_forsythe_p: f64,
+}
+
+impl HomogeneityOfVariance {
+    pub fn new(
+        name: String,
+        categorical_data: &CategoricalDataArray,
+        continuous_data: &ContinuousDataArray,
+    ) -> Result<HomogeneityOfVariance, Error> {
+        let groups = categorical_data
+            .levels
+            .keys()
+            .map(|level_name| {
+                Ok(categorical_data
+                    .get_level_data(level_name, continuous_data)?
+                    .into_iter()
+                    .copied()
+                    .collect::<Vec<f64>>())
+            })
+            .collect::<Result<Vec<Vec<f64>>, Error>>()?;
+
+        let n = groups.iter().map(|group| group.len()).sum::<usize>();
+        if groups.len() < 2 || n <= groups.len() {
+            return Err(anyhow!(
+                "Testing homogeneity of variance requires at least two levels and more observations than levels"
+            ));
+        }
+        let df_1 = groups.len() - 1;
+        let df_2 = n - groups.len();
+
+        let mean_centers = groups
+            .iter()
+            .map(|group| group.iter().sum::<f64>() / group.len() as f64)
+            .collect::<Vec<f64>>();
+        let median_centers = groups
+            .iter()
+            .map(|group| median(group))
+            .collect::<Result<Vec<f64>, Error>>()?;
+
+        let levene_f = absolute_deviation_f(&groups, &mean_centers, df_1, df_2);
+        let brown_forsythe_f = absolute_deviation_f(&groups, &median_centers, df_1, df_2);
+
+        Ok(HomogeneityOfVariance {
+            name,
+            df_1,
+            df_2,
+            levene_f,
+            levene_p: f_p(levene_f, df_1 as f64, df_2 as f64)?,
+            brown_forsythe_f,
+            brown_forsythe_p: f_p(brown_forsythe_f, df_1 as f64, df_2 as f64)?,
+        })
+    }
+
+    /// Either test rejects equal variances.
+    pub fn is_questionable(&self) -> bool {
+        self.levene_p < HOMOGENEITY_ALPHA || self.brown_forsythe_p < HOMOGENEITY_ALPHA
+    }
+
+    pub fn print(&self) {
+        report!("Levene's Test (mean-centered)");
+        report!("..F({}, {}): {}", self.df_1, self.df_2, self.levene_f);
+        report!("..p: {}", self.levene_p);
+        report!("Brown-Forsythe Test (median-centered)");
+        report!(
+            "..F({}, {}): {}",
+            self.df_1,
+            self.df_2,
+            self.brown_forsythe_f
+        );
+        report!("..p: {}", self.brown_forsythe_p);
+        if self.is_questionable() {
+            report_warning!(
+                "Variances of {} may not be equal; consider Welch's t or a nonparametric test",
+                self.name
+            );
+        }
+    }
+}
+
+/// One way ANOVA F on z_ij = |y_ij - center_i|.
+fn absolute_deviation_f(groups: &[Vec<f64>], centers: &[f64], df_1: usize, df_2: usize) -> f64 {
+    let deviations = groups
+        .iter()
+        .zip(centers.iter())
+        .map(|(group, center)| {
+            group
+                .iter()
+                .map(|y| (y - center).abs())
+                .collect::<Vec<f64>>()
+        })
+        .collect::<Vec<Vec<f64>>>();
+
+    let n = deviations.iter().map(|group| group.len()).sum::<usize>() as f64;
+    let grand_mean = deviations.iter().flatten().sum::<f64>() / n;
+    let group_means = deviations
+        .iter()
+        .map(|group| group.iter().sum::<f64>() / group.len() as f64)
+        .collect::<Vec<f64>>();
+
+    let sum_of_squares_between = deviations
+        .iter()
+        .zip(group_means.iter())
+        .map(|(group, mean)| group.len() as f64 * f64::powi(mean - grand_mean, 2))
+        .sum::<f64>();
+    let sum_of_squares_within = deviations
+        .iter()
+        .zip(group_means.iter())
+        .map(|(group, mean)| group.iter().map(|z| f64::powi(z - mean, 2)).sum::<f64>())
+        .sum::<f64>();
+
+    (sum_of_squares_between / df_1 as f64) / (sum_of_squares_within / df_2 as f64)
+}
@@ -0,0 +1,115 @@
+use crate::functions::apa::{format_p, format_statistic};
+use crate::functions::distributions::two_tailed_p_from_t;
+use crate::functions::matrix::{multiply, Matrix};
+use crate::logging;
+use crate::reporter::report;
+use anyhow::{anyhow, Error, Result};
+use std::collections::BTreeMap;
+
+/// Coefficient tests that allow observations sharing a cluster (such as the repeated
+/// measures of one subject) to be correlated, from the CR1 sandwich estimate of the
+/// coefficients' covariance. Each t is tested on G - 1 degrees of freedom for G clusters.
+#[derive(Debug, Clone)]
+pub struct ClusterRobust {
+    pub cluster_name: String,
+    pub clusters: usize,
+    pub df: usize,
+    pub standard_errors: Vec<f64>,
+    pub t_values: Vec<f64>,
+    pub p_values: Vec<f64>,
+}
+
+impl ClusterRobust {
+    /// `design` holds one row per observation (a leading 1 for the intercept), with its
+    /// residual and the cluster it belongs to; `xtx_inverse` is (X'X)^-1.
+    pub fn new(
+        cluster_name: String,
+        design: &Matrix,
+        xtx_inverse: &Matrix,
+        coefficients: &[f64],
+        residuals: &[f64],
+        clusters: &[&String],
+    ) -> Result<ClusterRobust, Error> {
+        let n = design.len();
+        let k = coefficients.len();
+        if residuals.len() != n || clusters.len() != n {
+            return Err(anyhow!("provided data are not of same length"));
+        }
+
+        // u_g = sum of x_i * e_i over the rows of cluster g
+        let mut scores: BTreeMap<&String, Vec<f64>> = BTreeMap::new();
+        for ((row, residual), cluster) in design.iter().zip(residuals).zip(clusters) {
+            let score = scores.entry(cluster).or_insert_with(|| vec![0.0; k]);
+            for (total, x) in score.iter_mut().zip(row) {
+                *total += x * residual;
+            }
+        }
+        let g = scores.len();
+        if g < 2 {
+            return Err(anyhow!(
+                "Cluster-robust standard errors need at least two clusters in '{}'",
+                cluster_name
+            ));
+        }
+
+        // V = c * (X'X)^-1 (sum of u_g u_g') (X'X)^-1, c = G / (G - 1) * (n - 1) / (n - k)
+        let mut meat = vec![vec![0.0; k]; k];
+        for score in scores.values() {
+            for (a, row) in meat.iter_mut().enumerate() {
+                for (b, cell) in row.iter_mut().enumerate() {
+                    *cell += score[a] * score[b];
+                }
+            }
+        }
+        let correction = g as f64 / (g - 1) as f64 * (n - 1) as f64 / (n - k) as f64;
+        let covariance = multiply(&multiply(xtx_inverse, &meat)?, xtx_inverse)?;
+
+        let df = g - 1;
+        let standard_errors = (0..k)
+            .map(|j| f64::sqrt(correction * covariance[j][j]))
+            .collect::<Vec<f64>>();
+        let t_values = coefficients
+            .iter()
+            .zip(standard_errors.iter())
+            .map(|(coefficient, se)| coefficient / se)
+            .collect::<Vec<f64>>();
+        let p_values = t_values
+            .iter()
+            .map(|t| two_tailed_p_from_t(*t, df as f64))
+            .collect::<Result<Vec<f64>, Error>>()?;
+
+        Ok(ClusterRobust {
+            cluster_name,
+            clusters: g,
+            df,
+            standard_errors,
+            t_values,
+            p_values,
+        })
+    }
+
+    /// Prints the tests, naming the coefficients in order.
+    pub fn print(&self, names: &[String]) {
+        report!(
+            "{}",
+            logging::format_title(&format!(
+                "Cluster-Robust Standard Errors by {}",
+                self.cluster_name
+            ))
+        );
+        report!("Clusters......................{}", self.clusters);
+        report!("df............................{}", self.df);
+        for (index, name) in names.iter().enumerate() {
+            report!("{}", name);
+            report!("..SE: {}", self.standard_errors[index]);
+            report!("..t: {}", self.t_values[index]);
+            report!("..p (two-tailed): {}", self.p_values[index]);
+            report!(
+                "..APA: t({}) = {}, {}",
+                self.df,
+                format_statistic(self.t_values[index]),
+                format_p(self.p_values[index])
+            );
+        }
+    }
+}
@@ -0,0 +1,448 @@
+use crate::functions::apa::{format_bounded, format_p, format_statistic};
+use crate::functions::distributions::{chi_square_p, ln_gamma, two_tailed_p_from_z};
+use crate::functions::formula::Design;
+use crate::functions::matrix::{invert, Matrix};
+use crate::functions::optimize::maximize;
+use crate::logging;
+use crate::reporter::{report, report_warning};
+use anyhow::{anyhow, Error, Result};
+use clap::ValueEnum;
+
+/// Significance level of the likelihood ratio test for overdispersion.
+const OVERDISPERSION_ALPHA: f64 = 0.05;
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountModel {
+    /// Poisson, or negative binomial when the counts are significantly overdispersed
+    Auto,
+    Poisson,
+    NegativeBinomial,
+}
+
+impl CountModel {
+    pub fn label(self) -> &'static str {
+        match self {
+            CountModel::Auto => "Poisson, or negative binomial if overdispersed",
+            CountModel::Poisson => "Poisson",
+            CountModel::NegativeBinomial => "Negative binomial",
+        }
+    }
+}
+
+/// A log-linear regression of a count response, ln E(y) = x'b, fit by maximum
+/// likelihood. The Poisson model assumes the variance equals the mean. The negative
+/// binomial (NB2) model lets it grow as mu + alpha mu^2, for counts that are more
+/// spread out than that (overdispersed). exp(b) is the incidence rate ratio, the factor
+/// the expected count changes by per unit of a predictor.
+#[derive(Debug, Clone)]
+pub struct CountRegression {
+    pub name: String,
+    pub description: String,
+    _n: usize,
+    _p: usize, // predictors, excluding the intercept
+
+    // provided
+    _design: Design,
+    _model: CountModel,
+
+    // calculated
+    _standard_errors: Vec<f64>, // intercept, then coefficients
+    _z_values: Vec<f64>,
+    _p_values: Vec<f64>,
+    _alpha_standard_error: f64,
+    _null_log_likelihood: f64, // intercept-only model of the same family
+    _pearson_chi_square: f64,  // of the Poisson fit
+    _overdispersion_test: Option<(f64, f64)>, // likelihood ratio and p against Poisson
+
+    _statistic_run: bool,
+    pub negative_binomial: bool, // the model reported
+    pub coefficients: Vec<f64>,  // intercept first
+    pub alpha: f64,              // 0 for Poisson
+    pub dispersion: f64,         // Pearson chi-square / df of the Poisson fit
+    pub log_likelihood: f64,
+    pub chi_square: f64, // likelihood ratio against the intercept-only model
+    pub p: f64,
+    pub pseudo_r_squared: f64, // McFadden's
+    pub apa: String,
+}
+
+impl CountRegression {
+    pub fn new(
+        name: String,
+        description: String,
+        design: Design,
+        model: CountModel,
+    ) -> Result<CountRegression, Error> {
+        if design.columns.is_empty() {
+            return Err(anyhow!("A count regression needs at least one predictor"));
+        }
+        if let Some(value) = design
+            .response
+            .iter()
+            .find(|value| **value < 0.0 || value.fract() != 0.0)
+        {
+            return Err(anyhow!(
+                "The response '{}' must be a count (a whole number of zero or more), but one value is {}",
+                design.response_name,
+                value
+            ));
+        }
+        if design.response.iter().all(|value| *value == 0.0) {
+            return Err(anyhow!(
+                "The response '{}' is zero in every row, so there is nothing to model",
+                design.response_name
+            ));
+        }
+        let n = design.response.len();
+        let k = design.columns.len() + 1;
+        if n <= k {
+            return Err(anyhow!(
+                "More observations than coefficients are required for a count regression ({} rows for {} coefficients)",
+                n,
+                k
+            ));
+        }
+
+        let mut new_count_regression = CountRegression {
+            name,
+            description,
+            _n: n,
+            _p: k - 1,
+            _design: design,
+            _model: model,
+            _standard_errors: vec![],
+            _z_values: vec![],
+            _p_values: vec![],
+            _alpha_standard_error: 0.0,
+            _null_log_likelihood: 0.0,
+            _pearson_chi_square: 0.0,
+            _overdispersion_test: None,
+            _statistic_run: false,
+            negative_binomial: false,
+            coefficients: vec![],
+            alpha: 0.0,
+            dispersion: 0.0,
+            log_likelihood: 0.0,
+            chi_square: 0.0,
+            p: 0.0,
+            pseudo_r_squared: 0.0,
+            apa: String::new(),
+        };
+
+        new_count_regression.run_statistic()?;
+
+        Ok(new_count_regression)
+    }
+
+    fn run_statistic(&mut self) -> Result<(), Error> {
+        report!("...Calculating 'Count Regression'...");
+
+        let k = self._p + 1;
+        let y = &self._design.response;
+        let rows: Matrix = (0..self._n)
+            .map(|row| {
+                std::iter::once(1.0)
+                    .chain(self._design.columns.iter().map(|column| column.values[row]))
+                    .collect()
+            })
+            .collect();
+        let intercept_only: Matrix = vec![vec![1.0]; self._n];
+        let mean = y.iter().sum::<f64>() / self._n as f64;
+
+        // Poisson, starting from the intercept-only fit b_0 = ln(mean)
+        let mut start = vec![0.0; k];
+        start[0] = mean.ln();
+        let (poisson, poisson_log_likelihood, poisson_hessian) =
+            maximize(start, |b| poisson_likelihood(b, &rows, y))
+                .map_err(|error| anyhow!("The Poisson regression {}", error))?;
+        let poisson_null_log_likelihood = poisson_likelihood(&[mean.ln()], &intercept_only, y)
+            .map(|(log_likelihood, _, _)| log_likelihood)
+            .unwrap_or(f64::NAN);
+
+        // Pearson chi-square = sum (y - mu)^2 / mu, on n - k df
+        self._pearson_chi_square = rows
+            .iter()
+            .zip(y)
+            .map(|(x, y)| {
+                let mu = linear_predictor(x, &poisson).exp();
+                (y - mu).powi(2) / mu
+            })
+            .sum();
+        self.dispersion = self._pearson_chi_square / (self._n - k) as f64;
+
+        // negative binomial, from the Poisson coefficients and the moment estimate of alpha
+        // (fit to test overdispersion whenever the counts are more spread out than Poisson)
+        let negative_binomial = if self._model == CountModel::NegativeBinomial
+            || self.dispersion > 1.0
+        {
+            let fit = fit_negative_binomial(&rows, y, &poisson).and_then(|fit| {
+                let null = fit_negative_binomial(&intercept_only, y, &[mean.ln()])?;
+                Ok((fit, null.1))
+            });
+            match fit {
+                Ok(fit) => Some(fit),
+                Err(error) if self._model != CountModel::NegativeBinomial => {
+                    report_warning!(
+                        "The negative binomial regression could not be fit to test for overdispersion: {}",
+                        error
+                    );
+                    None
+                }
+                Err(error) => return Err(error),
+            }
+        } else {
+            None
+        };
+
+        // LR = 2 (LL_NB - LL_Poisson); alpha = 0 is on the boundary, so p is halved
+        if let Some(((_, log_likelihood, _), _)) = &negative_binomial {
+            let likelihood_ratio = f64::max(2.0 * (log_likelihood - poisson_log_likelihood), 0.0);
+            self._overdispersion_test =
+                Some((likelihood_ratio, chi_square_p(likelihood_ratio, 1.0)? / 2.0));
+        }
+        self.negative_binomial = match (self._model, self._overdispersion_test) {
+            (CountModel::NegativeBinomial, _) => true,
+            (CountModel::Auto, Some((_, p))) => p < OVERDISPERSION_ALPHA,
+            _ => false,
+        };
+
+        let (parameters, log_likelihood, hessian) = match negative_binomial {
+            Some((fit, null)) if self.negative_binomial => {
+                self._null_log_likelihood = null;
+                fit
+            }
+            _ => {
+                self._null_log_likelihood = poisson_null_log_likelihood;
+                (poisson, poisson_log_likelihood, poisson_hessian)
+            }
+        };
+
+        // Var = (-H)^-1 at the maximum
+        let covariance = invert(
+            &hessian
+                .iter()
+                .map(|row| row.iter().map(|cell| -cell).collect())
+                .collect(),
+        )?;
+        self._standard_errors = (0..k).map(|j| f64::sqrt(covariance[j][j])).collect();
+        self.coefficients = parameters[..k].to_vec();
+        if self.negative_binomial {
+            // alpha = e^a, so SE(alpha) = alpha SE(a)
+            self.alpha = parameters[k].exp();
+            self._alpha_standard_error = self.alpha * f64::sqrt(covariance[k][k]);
+        }
+        self._z_values = self
+            .coefficients
+            .iter()
+            .zip(self._standard_errors.iter())
+            .map(|(coefficient, se)| coefficient / se)
+            .collect();
+        self._p_values = self
+            ._z_values
+            .iter()
+            .map(|z| two_tailed_p_from_z(*z))
+            .collect::<Result<Vec<f64>, Error>>()?;
+        self.log_likelihood = log_likelihood;
+
+        // chi-square = 2 (LL - LL_0), pseudo R^2 = 1 - LL / LL_0
+        self.chi_square = 2.0 * (self.log_likelihood - self._null_log_likelihood);
+        self.p = chi_square_p(self.chi_square, self._p as f64)?;
+        self.pseudo_r_squared = 1.0 - self.log_likelihood / self._null_log_likelihood;
+
+        self.apa = format!(
+            "χ²({}, N = {}) = {}, {}, McFadden R² = {}",
+            self._p,
+            self._n,
+            format_statistic(self.chi_square),
+            format_p(self.p),
+            format_bounded(self.pseudo_r_squared)
+        );
+
+        self._statistic_run = true;
+
+        Ok(())
+    }
+
+    pub fn print(mut self) -> Result<(), Error> {
+        if self._statistic_run {
+            report!("{}", logging::format_title(&self.name));
+            report!("Description: '{}'", self.description);
+            report!("n.............................{}", self._n);
+            report!("p.............................{}", self._p);
+            report!(
+                "Response......................{}",
+                self._design.response_name
+            );
+            report!(
+                "Model.........................{}",
+                if self.negative_binomial {
+                    "Negative Binomial"
+                } else {
+                    "Poisson"
+                }
+            );
+            for (index, coefficient) in self.coefficients.iter().enumerate() {
+                if index == 0 {
+                    report!("Intercept");
+                } else {
+                    report!("{}", self._design.columns[index - 1].name);
+                }
+                report!("..b: {}", coefficient);
+                report!("..SE: {}", self._standard_errors[index]);
+                report!("..z: {}", self._z_values[index]);
+                report!("..p (two-tailed): {}", self._p_values[index]);
+                if index > 0 {
+                    report!("..Incidence Rate Ratio: {}", f64::exp(*coefficient));
+                }
+            }
+            if self.negative_binomial {
+                report!("Alpha.........................{}", self.alpha);
+                report!(
+                    "Alpha SE......................{}",
+                    self._alpha_standard_error
+                );
+            }
+            report!("Log-Likelihood................{}", self.log_likelihood);
+            report!(
+                "Log-Likelihood (intercept)....{}",
+                self._null_log_likelihood
+            );
+            report!("Likelihood Ratio Chi-Square...{}", self.chi_square);
+            report!("df............................{}", self._p);
+            report!("p.............................{}", self.p);
+            report!("McFadden R^2..................{}", self.pseudo_r_squared);
+            report!("APA...........................{}", self.apa);
+
+            report!("{}", logging::format_title("Overdispersion"));
+            report!("Pearson Chi-Square (Poisson)..{}", self._pearson_chi_square);
+            report!("df............................{}", self._n - self._p - 1);
+            report!("Dispersion....................{}", self.dispersion);
+            match self._overdispersion_test {
+                Some((likelihood_ratio, p)) => {
+                    report!("Likelihood Ratio (alpha = 0)");
+                    report!("..Chi-Square: {}", likelihood_ratio);
+                    report!("..p (one-tailed): {}", p);
+                    if !self.negative_binomial && p < OVERDISPERSION_ALPHA {
+                        report_warning!(
+                            "The counts are overdispersed (p < .05), so the Poisson standard errors are too small; consider --model negative-binomial"
+                        );
+                    }
+                }
+                None if self.dispersion <= 1.0 => report!(
+                    "The counts are no more spread out than a Poisson model expects, so it is kept"
+                ),
+                None => report!("Not available"),
+            }
+            Ok(())
+        } else {
+            self.run_statistic()?;
+            self.print()
+        }
+    }
+}
+
+fn linear_predictor(x: &[f64], coefficients: &[f64]) -> f64 {
+    x.iter().zip(coefficients).map(|(x, b)| x * b).sum()
+}
+
+/// Poisson log-likelihood, gradient, and Hessian in the coefficients, or None where a
+/// fitted mean overflows.
+fn poisson_likelihood(
+    coefficients: &[f64],
+    rows: &Matrix,
+    y: &[f64],
+) -> Option<(f64, Vec<f64>, Matrix)> {
+    let k = coefficients.len();
+    let mut log_likelihood = 0.0;
+    let mut gradient = vec![0.0; k];
+    let mut hessian = vec![vec![0.0; k]; k];
+    for (x, y) in rows.iter().zip(y) {
+        let mu = linear_predictor(x, coefficients).exp();
+        if !mu.is_finite() {
+            return None;
+        }
+        // ln L = y ln mu - mu - ln y!; g = x (y - mu); H = -x x' mu
+        log_likelihood += y * mu.ln() - mu - ln_gamma(y + 1.0);
+        for a in 0..k {
+            gradient[a] += x[a] * (y - mu);
+            for b in 0..k {
+                hessian[a][b] -= x[a] * x[b] * mu;
+            }
+        }
+    }
+    Some((log_likelihood, gradient, hessian))
+}
+
+/// Negative binomial log-likelihood, gradient, and Hessian in the coefficients and
+/// a = ln(alpha), the last parameter, or None where a fitted mean overflows.
+fn negative_binomial_likelihood(
+    parameters: &[f64],
+    rows: &Matrix,
+    y: &[f64],
+) -> Option<(f64, Vec<f64>, Matrix)> {
+    let m = parameters.len();
+    let k = m - 1;
+    let (coefficients, alpha) = (&parameters[..k], parameters[k].exp());
+    let mut log_likelihood = 0.0;
+    let mut gradient = vec![0.0; m];
+    let mut hessian = vec![vec![0.0; m]; m];
+    for (x, y) in rows.iter().zip(y) {
+        let mu = linear_predictor(x, coefficients).exp();
+        let d = 1.0 + alpha * mu;
+        if !mu.is_finite() || !d.is_finite() {
+            return None;
+        }
+        // ln L = sum_{j<y} ln(1 + alpha j) - ln y! + y ln mu - (y + 1/alpha) ln(1 + alpha mu)
+        let (mut log_terms, mut first_terms, mut second_terms) = (0.0, 0.0, 0.0);
+        for j in 0..*y as usize {
+            let j = j as f64;
+            log_terms += f64::ln(1.0 + alpha * j);
+            first_terms += j / (1.0 + alpha * j);
+            second_terms += (j / (1.0 + alpha * j)).powi(2);
+        }
+        log_likelihood += log_terms - ln_gamma(y + 1.0) + y * mu.ln() - (y + 1.0 / alpha) * d.ln();
+
+        // derivatives in b and alpha, then a = ln(alpha) by the chain rule
+        let g_alpha = first_terms + d.ln() / alpha.powi(2) - (y + 1.0 / alpha) * mu / d;
+        let h_alpha = -second_terms + 2.0 * mu / (alpha.powi(2) * d) - 2.0 * d.ln() / alpha.powi(3)
+            + (y + 1.0 / alpha) * mu.powi(2) / d.powi(2);
+        gradient[k] += alpha * g_alpha;
+        hessian[k][k] += alpha.powi(2) * h_alpha + alpha * g_alpha;
+        for a in 0..k {
+            gradient[a] += x[a] * (y - mu) / d;
+            let cross = -alpha * x[a] * mu * (y - mu) / d.powi(2);
+            hessian[a][k] += cross;
+            hessian[k][a] += cross;
+            for b in 0..k {
+                hessian[a][b] -= x[a] * x[b] * mu * (1.0 + alpha * y) / d.powi(2);
+            }
+        }
+    }
+    Some((log_likelihood, gradient, hessian))
+}
+
+/// Fits the negative binomial model from `coefficients`, with alpha started at its
+/// moment estimate sum((y - mu)^2 - mu) / sum(mu^2) (at least 0.01). Returns the
+/// parameters (a = ln(alpha) last), the maximum, and the Hessian there.
+fn fit_negative_binomial(
+    rows: &Matrix,
+    y: &[f64],
+    coefficients: &[f64],
+) -> Result<(Vec<f64>, f64, Matrix), Error> {
+    let mu = rows
+        .iter()
+        .map(|x| linear_predictor(x, coefficients).exp())
+        .collect::<Vec<f64>>();
+    let alpha = mu
+        .iter()
+        .zip(y)
+        .map(|(mu, y)| (y - mu).powi(2) - mu)
+        .sum::<f64>()
+        / mu.iter().map(|mu| mu.powi(2)).sum::<f64>();
+    let mut start = coefficients.to_vec();
+    start.push(f64::max(alpha, 0.01).ln());
+    maximize(start, |parameters| {
+        negative_binomial_likelihood(parameters, rows, y)
+    })
+    .map_err(|error| anyhow!("The negative binomial regression {}", error))
+}
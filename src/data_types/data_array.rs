@@ -1,9 +1,10 @@
 use crate::data_types::data_array::categorical::DataArray as categorical_data_array;
 use crate::data_types::data_array::continuous::DataArray as continuous_data_array;
+use crate::functions::csv::is_missing;
 use crate::logging;
+use crate::reporter::report;
 use anyhow::Error;
-use log::info;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 pub(self) mod continuous {
     #[derive(Clone, Default, Debug)]
@@ -95,12 +96,11 @@ impl ContinuousDataArray {
         // s = sqrt(s^2)
         new_data_array.standard_deviation = f64::sqrt(new_data_array.variance);
 
-        // z = x / s
+        // z = (x - mean) / s
         new_data_array.z_scores = new_data_array
-            .data_array
-            .data
+            .deviations
             .iter()
-            .map(|x| x.1 / new_data_array.standard_deviation)
+            .map(|deviation| deviation / new_data_array.standard_deviation)
             .collect();
 
         // pub fn get_probability_density(&self, x: f64) -> Result<f64, Error> {
@@ -122,20 +122,20 @@ impl ContinuousDataArray {
     }
 
     pub fn print(&self) {
-        info!("{}", logging::format_title(&*self.name));
-        info!("Data Type.....................Continuous",);
-        info!("Column Index..................{}", self.column_index);
+        report!("{}", logging::format_title(&*self.name));
+        report!("Data Type.....................Continuous",);
+        report!("Column Index..................{}", self.column_index);
         // debug!("Data: {:?}", &self.data);
-        info!("N.............................{}", self.n);
-        info!(
+        report!("N.............................{}", self.n);
+        report!(
             "Population....................{}",
             self.population.unwrap_or_default()
         );
-        info!("Mean..........................{}", self.mean);
-        info!("Sum of Squares................{}", self.sum_of_squares);
+        report!("Mean..........................{}", self.mean);
+        report!("Sum of Squares................{}", self.sum_of_squares);
         // debug!("Deviations: {:?}", self.deviations.clone().unwrap_or_default());
-        info!("Variance......................{}", self.variance);
-        info!("Standard deviation............{}", self.standard_deviation);
+        report!("Variance......................{}", self.variance);
+        report!("Standard deviation............{}", self.standard_deviation);
         // debug!("Z-Scores: {:?}", self.z_scores.clone().unwrap_or_default());
     }
 }
@@ -147,7 +147,7 @@ pub struct CategoricalDataArray<'a> {
     pub name: String,
     pub population: Option<bool>,
     pub n: usize,
-    pub levels: HashMap<&'a String, Vec<usize>>,
+    pub levels: BTreeMap<&'a String, Vec<usize>>, // sorted by level, so output order is stable
 }
 
 impl<'a> CategoricalDataArray<'a> {
@@ -181,16 +181,45 @@ impl<'a> CategoricalDataArray<'a> {
     }
 
     pub fn print(&self) {
-        info!("{}", logging::format_title(&*self.name));
-        info!("Data Type.....................Categorical",);
-        info!("Column Index..................{}", self.column_index);
+        report!("{}", logging::format_title(&*self.name));
+        report!("Data Type.....................Categorical",);
+        report!("Column Index..................{}", self.column_index);
         // debug!("Data: {:?}", &self.data);
-        info!("N.............................{}", self.n);
-        info!(
+        report!("N.............................{}", self.n);
+        report!(
             "Population....................{}",
             self.population.unwrap_or_default()
         );
-        info!("Levels........................{:#?}", self.levels);
+        report!("Levels........................{:#?}", self.levels);
+    }
+
+    /// Whether any row holds `level`.
+    pub fn has_level(&self, level: &str) -> bool {
+        self.levels.keys().any(|key| key.as_str() == level)
+    }
+
+    /// Levels (other than missing cells) held by fewer than `min_count` rows.
+    pub fn sparse_levels(&self, min_count: usize) -> Vec<&'a String> {
+        self.levels
+            .iter()
+            .filter(|(level, rows)| !is_missing(level) && rows.len() < min_count)
+            .map(|(level, _)| *level)
+            .collect()
+    }
+
+    /// The values in row order, with every level in `from` replaced by `to`.
+    pub fn recoded(&self, from: &[&str], to: &str) -> Vec<String> {
+        self.data_array
+            .data
+            .iter()
+            .map(|(_, value)| {
+                if from.contains(&value.as_str()) {
+                    to.to_string()
+                } else {
+                    (*value).clone()
+                }
+            })
+            .collect()
     }
 
     pub fn get_level_indices(&self, level_name: &String) -> Vec<&usize> {
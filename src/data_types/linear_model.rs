@@ -0,0 +1,212 @@
+use crate::data_types::data_array::ContinuousDataArray;
+use crate::data_types::multiple_regression::MultipleRegression;
+use crate::functions::apa::{format_bounded, format_p, format_statistic};
+use crate::functions::distributions::f_p;
+use crate::functions::formula::Design;
+use crate::functions::matrix::{invert, multiply, multiply_vector, transpose, Matrix};
+use crate::logging;
+use crate::reporter::report;
+use anyhow::{anyhow, Error, Result};
+
+/// A linear model fit from a formula design: the regression on its (dummy coded)
+/// columns, followed by a sequential (Type I) F test for each term.
+#[derive(Debug, Clone)]
+pub struct LinearModel {
+    pub name: String,
+    pub description: String,
+    _n: usize,
+
+    // provided
+    _design: Design,
+
+    // calculated
+    _y_data: ContinuousDataArray,
+    _x_data: Vec<ContinuousDataArray>,
+    _df_error: usize,
+    _sum_of_squares_error: f64,
+    _mean_square_error: f64,
+
+    _statistic_run: bool,
+    pub terms: Vec<TermTest>,
+}
+
+/// A term's share of the sum of squares, given the terms listed before it.
+#[derive(Debug, Clone)]
+pub struct TermTest {
+    pub name: String,
+    pub df: usize,
+    pub sum_of_squares: f64,
+    pub mean_square: f64,
+    pub f: f64,
+    pub p: f64,
+    pub apa: String,
+}
+
+impl LinearModel {
+    pub fn new(name: String, description: String, design: Design) -> Result<LinearModel, Error> {
+        if design.columns.is_empty() {
+            return Err(anyhow!("A linear model needs at least one predictor"));
+        }
+        let n = design.response.len();
+        if n <= design.columns.len() + 1 {
+            return Err(anyhow!(
+                "More observations than coefficients are required for a linear model ({} rows for {} coefficients)",
+                n,
+                design.columns.len() + 1
+            ));
+        }
+
+        let mut new_linear_model = LinearModel {
+            name,
+            description,
+            _n: n,
+            _y_data: ContinuousDataArray::new(
+                design.response_name.clone(),
+                &design.response,
+                0,
+                Some(false),
+            )?,
+            _x_data: design
+                .columns
+                .iter()
+                .enumerate()
+                .map(|(index, column)| {
+                    ContinuousDataArray::new(
+                        column.name.clone(),
+                        &column.values,
+                        index + 1,
+                        Some(false),
+                    )
+                })
+                .collect::<Result<Vec<ContinuousDataArray>, Error>>()?,
+            _df_error: n - design.columns.len() - 1,
+            _design: design,
+            _sum_of_squares_error: 0.0,
+            _mean_square_error: 0.0,
+            _statistic_run: false,
+            terms: vec![],
+        };
+
+        new_linear_model.run_statistic()?;
+
+        Ok(new_linear_model)
+    }
+
+    fn run_statistic(&mut self) -> Result<(), Error> {
+        report!("...Calculating 'Linear Model'...");
+
+        // SS(term | earlier terms) = SSE(earlier terms) - SSE(earlier terms + term)
+        let mut sum_of_squares_errors = vec![self._y_data.sum_of_squares];
+        for term in self._design.terms.iter() {
+            sum_of_squares_errors.push(sum_of_squares_error(&self._design, term.columns.end)?);
+        }
+        self._sum_of_squares_error = *sum_of_squares_errors.last().unwrap();
+        self._mean_square_error = self._sum_of_squares_error / self._df_error as f64;
+
+        self.terms = self
+            ._design
+            .terms
+            .iter()
+            .enumerate()
+            .map(|(index, term)| {
+                let df = term.columns.len();
+                let sum_of_squares =
+                    sum_of_squares_errors[index] - sum_of_squares_errors[index + 1];
+                let mean_square = sum_of_squares / df as f64;
+                let f = mean_square / self._mean_square_error;
+                let p = f_p(f, df as f64, self._df_error as f64)?;
+
+                // partial eta^2 = SS_term / (SS_term + SSE)
+                let partial_eta_squared =
+                    sum_of_squares / (sum_of_squares + self._sum_of_squares_error);
+                Ok(TermTest {
+                    name: term.name.clone(),
+                    df,
+                    sum_of_squares,
+                    mean_square,
+                    f,
+                    p,
+                    apa: format!(
+                        "F({}, {}) = {}, {}, ηp² = {}",
+                        df,
+                        self._df_error,
+                        format_statistic(f),
+                        format_p(p),
+                        format_bounded(partial_eta_squared)
+                    ),
+                })
+            })
+            .collect::<Result<Vec<TermTest>, Error>>()?;
+
+        self._statistic_run = true;
+
+        Ok(())
+    }
+
+    pub fn print(mut self) -> Result<(), Error> {
+        if self._statistic_run {
+            MultipleRegression::new(
+                self.name.clone(),
+                self.description.clone(),
+                &self._y_data,
+                self._x_data.iter().collect(),
+            )?
+            .print()?;
+
+            report!(
+                "{}",
+                logging::format_title("Sequential (Type I) Sums of Squares")
+            );
+            if !self._design.dropped_rows.is_empty() {
+                report!(
+                    "Rows left out (missing values)..{}",
+                    self._design.dropped_rows.len()
+                );
+            }
+            for term in self.terms.iter() {
+                report!("{}", term.name);
+                report!("..df: {}", term.df);
+                report!("..SS: {}", term.sum_of_squares);
+                report!("..MS: {}", term.mean_square);
+                report!("..F: {}", term.f);
+                report!("..p: {}", term.p);
+                report!("..APA: {}", term.apa);
+            }
+            report!("Residuals");
+            report!("..df: {}", self._df_error);
+            report!("..SS: {}", self._sum_of_squares_error);
+            report!("..MS: {}", self._mean_square_error);
+            Ok(())
+        } else {
+            self.run_statistic()?;
+            self.print()
+        }
+    }
+}
+
+/// SSE of the regression on an intercept and the first `column_count` design columns.
+fn sum_of_squares_error(design: &Design, column_count: usize) -> Result<f64, Error> {
+    let matrix: Matrix = (0..design.response.len())
+        .map(|row| {
+            std::iter::once(1.0)
+                .chain(
+                    design.columns[..column_count]
+                        .iter()
+                        .map(|column| column.values[row]),
+                )
+                .collect()
+        })
+        .collect();
+
+    // beta-hat = (X'X)^-1 X'y
+    let transposed = transpose(&matrix);
+    let coefficients = multiply_vector(
+        &invert(&multiply(&transposed, &matrix)?)?,
+        &multiply_vector(&transposed, &design.response)?,
+    )?;
+    Ok(multiply_vector(&matrix, &coefficients)?
+        .iter()
+        .zip(design.response.iter())
+        .map(|(y_hat, y)| f64::powi(y - y_hat, 2))
+        .sum())
+}
@@ -1,3 +1,10 @@
+pub mod assumptions;
+pub mod count_regression;
+pub mod cluster_robust;
 pub mod data_array;
+pub mod linear_model;
+pub mod outliers;
 pub mod statistics;
 pub mod multiple_regression;
+pub mod ordinal_regression;
+pub mod multiple_response;
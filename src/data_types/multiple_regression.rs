@@ -1,307 +1,530 @@
-// use anyhow::{Error, Result};
-// use log::info;
-// use crate::data_types::data_array::DataArray;
-// use crate::data_types::data_relationship::DataRelationship;
-// use crate::logging;
-// 
-// #[derive(Default, Debug)]
-// pub struct MultipleRegression {
-//     pub name: String,
-//     pub n: f64, // total count of all observations
-//     pub p: f64, // total count of predictor variables
-//     pub x_data_arrays: Vec<DataArray>,
-//     pub y_data_array: DataArray,
-// 
-//     pub data_relationships: Vec<DataRelationship>,
-// 
-//     pub group_means: Vec<f64>,
-//     pub grand_mean: f64,
-// 
-//     pub sum_of_squares_between_groups: f64, // SSB
-//     pub sum_of_squares_within_groups: f64, // SSW
-//     pub sum_of_squares_error: f64, // SSE
-//     pub sum_of_squares_total: f64, // SST
-//     pub explained_sum_of_squares: f64, // ESS (or sum of squares due to regression)
-// 
-//     pub degrees_of_freedom_between_groups: f64, // dfB
-//     pub degrees_of_freedom_within_groups: f64, // dfW
-// 
-//     pub mean_square_between_groups: f64, // MSB
-//     pub mean_square_error: f64, // MSE = SSE / (n - p)
-//     pub mean_square_regression: f64, // MSR
-//     pub root_mean_square_error: f64, // RMSE
-// 
-//     pub f_type_1: f64,
-//     pub coefficient_of_multiple_determination: f64, // R^2
-//     pub coefficient_of_multiple_determination_adjusted: f64, // R^2 adjusted
-// 
-//     pub explained_variance: f64, // between-group variability
-//     pub unexplained_variance: f64, // within-group variability
-//     pub one_way_anova_f_test: f64, //
-// 
-//     _sum_of_all_data_points_in_all_groups: f64,
-// 
-//     // Sum of Squares Between Groups (SSB): representing the variability between different groups
-//     // in the data; it is often denoted as "SSB" (Sum of Squares Between) and is a key component in
-//     // determining if there are significant differences between groups in an analysis of variance test
-// 
-//     // Degrees of Freedom Between Groups (dfB): the variation between group means
-//     // Degrees of Freedom Within Groups (dfW): the variation within each group around their own mean
-// 
-//     // Mean Square Between (MSB): estimates the average variation among the group means,
-//     // allowing you to compare it to the variation within groups to determine if there are
-//     // statistically significant differences between the groups
-// 
-//     // Mean Square Error (MSE): essentially representing the "unexplained variance" within the data,
-//     // used to compare the variability between groups and determine if significant differences exist
-//     // between them
-// }
-// 
-// impl MultipleRegression {
-//     pub fn new(name: String, y_data_array: &DataArray, x_data_arrays: Vec<&DataArray>) -> Result<MultipleRegression, Error> {
-//         let mut new_multiple_regression: MultipleRegression = MultipleRegression::default();
-//         new_multiple_regression.name = name;
-//         new_multiple_regression.x_data_arrays = x_data_arrays.clone().into_iter()
-//             .map(|data_array: &DataArray| data_array.clone()).collect();
-//         new_multiple_regression.y_data_array = y_data_array.clone();
-//         new_multiple_regression.p = f64::from(x_data_arrays.len() as i32);
-// 
-//         // create an array of relationships of all x data to y data
-//         for x_data_array in new_multiple_regression.x_data_arrays.iter() {
-//             new_multiple_regression.data_relationships.push(DataRelationship::new(
-//                 String::from(x_data_array.name.clone() + " vs " + new_multiple_regression.y_data_array.name.as_str()),
-//                 &x_data_array,
-//                 &new_multiple_regression.y_data_array)?
-//             );
-//         }
-// 
-//         // ANOVA test:
-// 
-//         for (i, data_array) in new_multiple_regression.x_data_arrays.iter().enumerate() {
-//             // collect all the means
-//             new_multiple_regression.group_means.push(data_array.mean.clone());
-// 
-//             // keep tally of all data points
-//             new_multiple_regression.n += f64::from(data_array.data.len() as i32);
-// 
-//             // reverse the means back into sums for each data array
-//             new_multiple_regression._sum_of_all_data_points_in_all_groups += new_multiple_regression.group_means.get(i).unwrap()
-//                 * new_multiple_regression.x_data_arrays.get(i).unwrap().data.len() as f64;
-//         }
-// 
-//         // calculate the grand mean by dividing the sum of all data across arrays by the total data points across arrays
-//         new_multiple_regression.grand_mean = new_multiple_regression._sum_of_all_data_points_in_all_groups
-//             / new_multiple_regression.n as f64;
-// 
-//         // SSE (or SSR) = sum of squares errors (or residuals)
-//         for relationship in new_multiple_regression.data_relationships.iter() {
-//             for residual in relationship.residuals.iter() {
-//                 new_multiple_regression.sum_of_squares_error +=
-//                     f64::powi(*residual, 2);
-//             }
-//             // ESS = sum of squares of fitted values minus the y_mean
-//             for fitted in relationship.fitted_values.iter() {
-//                 new_multiple_regression.explained_sum_of_squares +=
-//                     f64::powi(fitted - relationship.data_y.mean, 2);
-//             }
-//             // SST = sum of squares of observed values minus the y_mean
-//             for observed in relationship.data_y.data.iter() {
-//                 new_multiple_regression.sum_of_squares_total +=
-//                     f64::powi(observed - relationship.data_y.mean, 2);
-//             }
-//         }
-// 
-//         for data_array in new_multiple_regression.x_data_arrays.iter() {
-//             // sum of squares between groups (SSB) = sum(n(mean - grand_mean)^2)
-//             new_multiple_regression.sum_of_squares_between_groups +=
-//                 f64::powi(data_array.mean - new_multiple_regression.grand_mean, 2)
-//                     * data_array.data.len() as f64;
-// 
-//             // for datum in data_array.data.iter() {
-//             //     // sum of squares total (each datum - grand mean, squared, then summed) (SST)
-//             //     // (METHOD 1; probably slower and more intensive)
-//             //     // new_multiple_regression.sum_of_squares_total += f64::powi(datum - new_multiple_regression.grand_mean, 2);
-//             //
-//             // }
-//         }
-// 
-//         // Sum of Squared Total (SST) = SSB + SSE (true for simple linear models, but not always)
-//         // new_multiple_regression.sum_of_squares_total = new_multiple_regression.sum_of_squares_between_groups
-//         //     + new_multiple_regression.sum_of_squared_residuals;
-// 
-//         // SSR = SST - SSE (true for simple linear models, but not always)
-//         // new_multiple_regression.sum_of_squares_regression = new_multiple_regression.sum_of_squares_total
-//         //     - new_multiple_regression.sum_of_squared_residuals;
-// 
-//         // Degrees of freedom between groups (dfB): Number of groups - 1
-//         new_multiple_regression.degrees_of_freedom_between_groups = new_multiple_regression.p - 1.0;
-// 
-//         // Degrees of freedom within groups (dfW): Total number of data points - Total number of groups
-//         // - 1 if intercept is being used, which is a recommended default
-//         new_multiple_regression.degrees_of_freedom_within_groups = new_multiple_regression.n
-//             - new_multiple_regression.p - 1.0;
-// 
-//         // Mean Square Between Groups (MSB): SSB / dfB
-//         new_multiple_regression.mean_square_between_groups = new_multiple_regression.sum_of_squares_between_groups
-//             / new_multiple_regression.degrees_of_freedom_between_groups as f64;
-// 
-//         // Mean Square Error (MSE): SSE / dfW
-//         new_multiple_regression.mean_square_error = new_multiple_regression.sum_of_squares_error
-//             / new_multiple_regression.degrees_of_freedom_within_groups as f64;
-// 
-//         // Mean Square Regression (MSR): dividing the regression sum of squares by p
-//         new_multiple_regression.mean_square_regression = new_multiple_regression.explained_sum_of_squares
-//             / new_multiple_regression.p;
-// 
-//         // Root Mean Square Error: SSE / n
-//         new_multiple_regression.root_mean_square_error = new_multiple_regression.sum_of_squares_error
-//             / new_multiple_regression.n as f64;
-// 
-//         // F = MSB / MSE
-//         new_multiple_regression.f_type_1 = new_multiple_regression.mean_square_between_groups
-//             / new_multiple_regression.mean_square_error;
-// 
-//         // Interpreting the ANOVA table:
-//         // F-statistic: Compare the calculated F-statistic to a critical value from an F-distribution table
-//         // based on your chosen significance level and degrees of freedom. If the calculated F-statistic is
-//         // larger than the critical value, you can reject the null hypothesis and conclude that there are
-//         // significant differences between groups.
-// 
-//         // R^2, coefficient of multiple determination = SSR/SST
-//         new_multiple_regression.coefficient_of_multiple_determination =
-//             new_multiple_regression.sum_of_squares_error
-//                 / new_multiple_regression.sum_of_squares_total;
-// 
-//         // R^2 adjusted = 1 - ((n - 1) / (n - p - 1)) * (1 - R^2)
-//         new_multiple_regression.coefficient_of_multiple_determination_adjusted =
-//             1.0 - ((new_multiple_regression.n - 1.0) / (new_multiple_regression.n - new_multiple_regression.p - 1.0))
-//                 * (new_multiple_regression.explained_sum_of_squares / new_multiple_regression.sum_of_squares_total);
-// 
-//         Ok(new_multiple_regression)
-//     }
-// 
-//     pub fn print_multiple_regression(&self) {
-//         info!("{}", logging::format_title(&*self.name));
-//         info!("Total Variables...............{}", self.x_data_arrays.len());
-//         info!("Group Means...................{:?}", self.group_means);
-//         info!("Grand Mean....................{}", self.grand_mean);
-//         info!("SST...........................{}", self.sum_of_squares_total);
-//         info!("SSB...........................{}", self.sum_of_squares_between_groups);
-//         info!("SSE...........................{}", self.sum_of_squares_error);
-//         info!("ESS...........................{}", self.explained_sum_of_squares);
-//         info!("dfB...........................{}", self.degrees_of_freedom_between_groups);
-//         info!("dfW...........................{}", self.degrees_of_freedom_within_groups);
-//         info!("MSB...........................{}", self.mean_square_between_groups);
-//         info!("MSE...........................{}", self.mean_square_error);
-//         info!("MSR...........................{}", self.mean_square_regression);
-//         info!("RMSD..........................{}", self.root_mean_square_error);
-//         info!("F Type 1......................{}", self.f_type_1);
-//         info!("R^2...........................{}", self.coefficient_of_multiple_determination);
-//         info!("R^2 adjusted..................{}", self.coefficient_of_multiple_determination_adjusted);
-//         info!("{}", logging::format_title(""));
-//         // info!("ADDITIONAL DEBUG INFO");
-//         // info!("Total Points in all Data.....................{}", self.n);
-//         // info!("Sum of All Data in all Groups..................{}", self.sum_of_all_data_points_in_all_groups);
-//     }
-// }
-// 
+use crate::data_types::cluster_robust::ClusterRobust;
+use crate::data_types::data_array::{CategoricalDataArray, ContinuousDataArray};
+use crate::functions::apa::{format_bounded, format_p, format_statistic};
+use crate::functions::distributions::{f_p, two_tailed_p_from_t};
+use crate::functions::matrix::{invert, multiply, multiply_vector, transpose, Matrix};
+use crate::logging;
+use crate::reporter::{report, report_warning};
+use anyhow::{anyhow, Error, Result};
+
+/// A variance inflation factor above this marks a predictor as collinear with the others.
+const VIF_THRESHOLD: f64 = 10.0;
+
+#[derive(Debug, Clone)]
+pub struct MultipleRegression<'a> {
+    pub name: String,
+    pub description: String,
+    _n: usize, // total count of observations
+    _p: usize, // total count of predictor variables
+    _df_regression: usize,
+    _df_error: usize,
+
+    // provided
+    _y_data: &'a ContinuousDataArray,
+    _x_data: Vec<&'a ContinuousDataArray>,
+
+    // calculated
+    _design: Matrix,          // X
+    _xtx_inverse: Matrix,     // (X'X)^-1
+    _fitted_values: Vec<f64>, // y-hat
+    _residuals: Vec<f64>,     // e_i = y_i - y-hat
+
+    _sum_of_squares_total: f64,     // SST = sum((y_i - y_mean)^2)
+    _sum_of_squares_error: f64,     // SSE = sum(e_i^2)
+    _explained_sum_of_squares: f64, // ESS = SST - SSE
+
+    _mean_square_regression: f64,  // MSR = ESS / p
+    _mean_square_error: f64,       // MSE = SSE / (n - p - 1)
+    _residual_standard_error: f64, // sqrt(MSE)
+
+    _standard_errors: Vec<f64>, // sqrt(diag(MSE * (X'X)^-1))
+    _t_values: Vec<f64>,
+    _p_values: Vec<f64>,
+    _cluster_robust: Option<ClusterRobust>,
+
+    // diagnostics
+    _variance_inflation_factors: Vec<f64>, // 1 / (1 - R_j^2), one per predictor
+    _leverages: Vec<f64>,                  // h_ii = x_i' (X'X)^-1 x_i
+    _cooks_distances: Vec<f64>,            // D_i = e_i^2 h_ii / (k MSE (1 - h_ii)^2)
+    _influential_rows: Vec<usize>,         // 0-based data rows with D_i > 4 / n
+    pub durbin_watson: f64,                // sum((e_i - e_i-1)^2) / SSE, in row order
+
+    _statistic_run: bool,
+    pub coefficients: Vec<f64>, // intercept first, then one per predictor
+    pub f: f64,
+    pub p: f64,
+    pub r_squared: f64,
+    pub r_squared_adjusted: f64,
+    pub apa: String,
+}
+
+impl<'a> MultipleRegression<'a> {
+    pub fn new(
+        name: String,
+        description: String,
+        y_data: &'a ContinuousDataArray,
+        x_data: Vec<&'a ContinuousDataArray>,
+    ) -> Result<MultipleRegression<'a>, Error> {
+        if x_data.is_empty() {
+            return Err(anyhow!(
+                "At least one predictor is required for a multiple regression"
+            ));
+        }
+        if x_data.iter().any(|x| x.n != y_data.n) {
+            return Err(anyhow!("provided data are not of same length"));
+        }
+        if y_data.n <= x_data.len() + 1 {
+            return Err(anyhow!(
+                "More observations than coefficients are required for a multiple regression"
+            ));
+        }
+
+        let mut new_multiple_regression = MultipleRegression {
+            name,
+            description,
+            _n: y_data.n,
+            _p: x_data.len(),
+            _df_regression: x_data.len(),
+            _df_error: y_data.n - x_data.len() - 1,
+            _y_data: y_data,
+            _x_data: x_data,
+            _design: vec![],
+            _xtx_inverse: vec![],
+            _fitted_values: vec![],
+            _residuals: vec![],
+            _sum_of_squares_total: 0.0,
+            _sum_of_squares_error: 0.0,
+            _explained_sum_of_squares: 0.0,
+            _mean_square_regression: 0.0,
+            _mean_square_error: 0.0,
+            _residual_standard_error: 0.0,
+            _standard_errors: vec![],
+            _t_values: vec![],
+            _p_values: vec![],
+            _cluster_robust: None,
+            _variance_inflation_factors: vec![],
+            _leverages: vec![],
+            _cooks_distances: vec![],
+            _influential_rows: vec![],
+            durbin_watson: 0.0,
+            _statistic_run: false,
+            coefficients: vec![],
+            f: 0.0,
+            p: 0.0,
+            r_squared: 0.0,
+            r_squared_adjusted: 0.0,
+            apa: String::new(),
+        };
+
+        new_multiple_regression.run_statistic()?;
+
+        Ok(new_multiple_regression)
+    }
+
+    fn run_statistic(&mut self) -> Result<(), Error> {
+        report!("...Calculating 'Multiple Regression'...");
+
+        let y = self
+            ._y_data
+            .data_array
+            .data
+            .iter()
+            .map(|y| y.1)
+            .collect::<Vec<f64>>();
+
+        // design matrix X: a leading column of 1s for the intercept, then each predictor
+        self._design = (0..self._n)
+            .map(|row| {
+                let mut design_row = Vec::with_capacity(self._p + 1);
+                design_row.push(1.0);
+                design_row.extend(self._x_data.iter().map(|x| x.data_array.data[row].1));
+                design_row
+            })
+            .collect();
+
+        // beta-hat = (X'X)^-1 X'y
+        let design_transposed = transpose(&self._design);
+        self._xtx_inverse = invert(&multiply(&design_transposed, &self._design)?)?;
+        self.coefficients = multiply_vector(
+            &self._xtx_inverse,
+            &multiply_vector(&design_transposed, &y)?,
+        )?;
+
+        self._fitted_values = multiply_vector(&self._design, &self.coefficients)?;
+        self._residuals = y
+            .iter()
+            .zip(self._fitted_values.iter())
+            .map(|(y_i, y_hat)| y_i - y_hat)
+            .collect();
+
+        self._sum_of_squares_total = y
+            .iter()
+            .map(|y_i| f64::powi(y_i - self._y_data.mean, 2))
+            .sum::<f64>();
+        self._sum_of_squares_error = self
+            ._residuals
+            .iter()
+            .map(|residual| f64::powi(*residual, 2))
+            .sum::<f64>();
+        self._explained_sum_of_squares = self._sum_of_squares_total - self._sum_of_squares_error;
+
+        self._mean_square_regression = self._explained_sum_of_squares / self._df_regression as f64;
+        self._mean_square_error = self._sum_of_squares_error / self._df_error as f64;
+        self._residual_standard_error = f64::sqrt(self._mean_square_error);
+
+        // SE(beta_j) = sqrt(MSE * [(X'X)^-1]_jj), t = beta_j / SE(beta_j)
+        self._standard_errors = (0..=self._p)
+            .map(|j| f64::sqrt(self._mean_square_error * self._xtx_inverse[j][j]))
+            .collect();
+        self._t_values = self
+            .coefficients
+            .iter()
+            .zip(self._standard_errors.iter())
+            .map(|(coefficient, se)| coefficient / se)
+            .collect();
+        self._p_values = self
+            ._t_values
+            .iter()
+            .map(|t| two_tailed_p_from_t(*t, self._df_error as f64))
+            .collect::<Result<Vec<f64>, Error>>()?;
+
+        // F = MSR / MSE
+        self.f = self._mean_square_regression / self._mean_square_error;
+        self.p = f_p(self.f, self._df_regression as f64, self._df_error as f64)?;
+
+        // R^2 = ESS / SST
+        self.r_squared = self._explained_sum_of_squares / self._sum_of_squares_total;
+
+        // R^2 adjusted = 1 - (1 - R^2) * ((n - 1) / (n - p - 1))
+        self.r_squared_adjusted =
+            1.0 - (1.0 - self.r_squared) * ((self._n as f64 - 1.0) / self._df_error as f64);
+
+        self.run_diagnostics()?;
+
+        self.apa = format!(
+            "F({}, {}) = {}, {}, R² = {}",
+            self._df_regression,
+            self._df_error,
+            format_statistic(self.f),
+            format_p(self.p),
+            format_bounded(self.r_squared)
+        );
+
+        self._statistic_run = true;
+
+        Ok(())
+    }
+
+    fn run_diagnostics(&mut self) -> Result<(), Error> {
+        let k = (self._p + 1) as f64;
+
+        // VIF_j = [R^-1]_jj for the correlation matrix R of the predictors, which is
+        // 1 / (1 - R_j^2) for R_j^2 from regressing predictor j on the others
+        let correlations = self
+            ._x_data
+            .iter()
+            .map(|x_a| {
+                self._x_data
+                    .iter()
+                    .map(|x_b| {
+                        x_a.data_array
+                            .data
+                            .iter()
+                            .zip(x_b.data_array.data.iter())
+                            .map(|(a, b)| (a.1 - x_a.mean) * (b.1 - x_b.mean))
+                            .sum::<f64>()
+                            / f64::sqrt(x_a.sum_of_squares * x_b.sum_of_squares)
+                    })
+                    .collect()
+            })
+            .collect::<Matrix>();
+        let correlations_inverse = invert(&correlations)?;
+        self._variance_inflation_factors =
+            (0..self._p).map(|j| correlations_inverse[j][j]).collect();
+
+        self.durbin_watson = self
+            ._residuals
+            .windows(2)
+            .map(|pair| f64::powi(pair[1] - pair[0], 2))
+            .sum::<f64>()
+            / self._sum_of_squares_error;
+
+        self._leverages = self
+            ._design
+            .iter()
+            .map(|x| {
+                multiply_vector(&self._xtx_inverse, x)
+                    .map(|projected| x.iter().zip(projected).map(|(a, b)| a * b).sum())
+            })
+            .collect::<Result<Vec<f64>, Error>>()?;
+        self._cooks_distances = self
+            ._residuals
+            .iter()
+            .zip(self._leverages.iter())
+            .map(|(residual, h)| {
+                f64::powi(*residual, 2) * h / (k * self._mean_square_error * f64::powi(1.0 - h, 2))
+            })
+            .collect();
+        let cutoff = 4.0 / self._n as f64;
+        self._influential_rows = self
+            ._cooks_distances
+            .iter()
+            .zip(self._y_data.data_array.data.iter())
+            .filter(|(d, _)| **d > cutoff)
+            .map(|(_, (row, _))| *row)
+            .collect();
+
+        Ok(())
+    }
+
+    /// Adds coefficient tests with standard errors robust to correlated observations within
+    /// each level of `clusters` (a subject ID, say), read from the same rows as the data.
+    pub fn with_clusters(
+        mut self,
+        clusters: &CategoricalDataArray,
+    ) -> Result<MultipleRegression<'a>, Error> {
+        let observation_clusters = self
+            ._y_data
+            .data_array
+            .data
+            .iter()
+            .map(|(row, _)| clusters.data_array.data[*row].1)
+            .collect::<Vec<&String>>();
+        self._cluster_robust = Some(ClusterRobust::new(
+            clusters.name.clone(),
+            &self._design,
+            &self._xtx_inverse,
+            &self.coefficients,
+            &self._residuals,
+            &observation_clusters,
+        )?);
+        Ok(self)
+    }
+
+    /// y-hat for each observation, in row order.
+    pub fn fitted_values(&self) -> &[f64] {
+        &self._fitted_values
+    }
+
+    /// Residuals divided by their own standard error, s sqrt(1 - h_ii) (internally
+    /// studentized).
+    pub fn studentized_residuals(&self) -> Vec<f64> {
+        self._residuals
+            .iter()
+            .zip(self._leverages.iter())
+            .map(|(residual, h)| residual / (self._residual_standard_error * f64::sqrt(1.0 - h)))
+            .collect()
+    }
+
+    /// Leverage (the diagonal of the hat matrix) for each observation, in row order.
+    pub fn leverages(&self) -> &[f64] {
+        &self._leverages
+    }
+
+    /// Cook's distance for each observation, in row order.
+    pub fn cooks_distances(&self) -> &[f64] {
+        &self._cooks_distances
+    }
+
+    /// y - y-hat for each observation, in row order.
+    pub fn residuals(&self) -> &[f64] {
+        &self._residuals
+    }
+
+    /// Residuals divided by the residual standard error.
+    pub fn standardized_residuals(&self) -> Vec<f64> {
+        self._residuals
+            .iter()
+            .map(|residual| residual / self._residual_standard_error)
+            .collect()
+    }
+
+    pub fn print(mut self) -> Result<(), Error> {
+        if self._statistic_run {
+            report!("{}", logging::format_title(&self.name));
+            report!("Description: '{}'", self.description);
+            report!("n.............................{}", self._n);
+            report!("p.............................{}", self._p);
+            report!("Dependent.....................{}", self._y_data.name);
+            for (index, coefficient) in self.coefficients.iter().enumerate() {
+                if index == 0 {
+                    report!("Intercept");
+                } else {
+                    report!("{}", self._x_data[index - 1].name);
+                }
+                report!("..b: {}", coefficient);
+                report!("..SE: {}", self._standard_errors[index]);
+                report!("..t: {}", self._t_values[index]);
+                report!("..p (two-tailed): {}", self._p_values[index]);
+            }
+            report!(
+                "SST...........................{}",
+                self._sum_of_squares_total
+            );
+            report!(
+                "SSE...........................{}",
+                self._sum_of_squares_error
+            );
+            report!(
+                "ESS...........................{}",
+                self._explained_sum_of_squares
+            );
+            report!("df (regression)...............{}", self._df_regression);
+            report!("df (error)....................{}", self._df_error);
+            report!(
+                "MSR...........................{}",
+                self._mean_square_regression
+            );
+            report!("MSE...........................{}", self._mean_square_error);
+            report!(
+                "Residual Standard Error.......{}",
+                self._residual_standard_error
+            );
+            report!("F.............................{}", self.f);
+            report!("p.............................{}", self.p);
+            report!("R^2...........................{}", self.r_squared);
+            report!("R^2 adjusted..................{}", self.r_squared_adjusted);
+            report!("APA...........................{}", self.apa);
+            self.print_diagnostics();
+            if let Some(cluster_robust) = &self._cluster_robust {
+                let names = std::iter::once(String::from("Intercept"))
+                    .chain(self._x_data.iter().map(|x| x.name.clone()))
+                    .collect::<Vec<String>>();
+                cluster_robust.print(&names);
+            }
+            Ok(())
+        } else {
+            self.run_statistic()?;
+            self.print()
+        }
+    }
+
+    fn print_diagnostics(&self) {
+        let n = self._n as f64;
+        let k = (self._p + 1) as f64;
+        report!("{}", logging::format_title("Regression Diagnostics"));
+        report!("Durbin-Watson.................{}", self.durbin_watson);
+        if self._p > 1 {
+            for (x, vif) in self
+                ._x_data
+                .iter()
+                .zip(self._variance_inflation_factors.iter())
+            {
+                report!("{}", x.name);
+                report!("..VIF: {}", vif);
+            }
+            let collinear = self
+                ._x_data
+                .iter()
+                .zip(self._variance_inflation_factors.iter())
+                .filter(|(_, vif)| **vif > VIF_THRESHOLD)
+                .map(|(x, _)| x.name.as_str())
+                .collect::<Vec<&str>>();
+            if !collinear.is_empty() {
+                report_warning!(
+                    "Collinear predictors (VIF > {}) have unstable coefficients: {}. Interaction and power terms are collinear with their own variables by construction",
+                    VIF_THRESHOLD,
+                    collinear.join(", ")
+                );
+            }
+        }
+        report!(
+            "Mean Leverage.................{}",
+            self._leverages.iter().sum::<f64>() / n
+        );
+        report!(
+            "Max Leverage..................{}",
+            self._leverages.iter().copied().fold(0.0, f64::max)
+        );
+        report!(
+            "Max Cook's Distance...........{}",
+            self._cooks_distances.iter().copied().fold(0.0, f64::max)
+        );
+        for (index, (row, _)) in self._y_data.data_array.data.iter().enumerate() {
+            if self._influential_rows.contains(row) {
+                report!("Row {}", row);
+                report!("..Leverage: {}", self._leverages[index]);
+                report!("..Cook's Distance: {}", self._cooks_distances[index]);
+                if self._leverages[index] > 2.0 * k / n {
+                    report!("..High leverage (h > 2k/n = {})", 2.0 * k / n);
+                }
+            }
+        }
+        if !self._influential_rows.is_empty() {
+            report_warning!(
+                "{} row(s) may be influential (Cook's distance > 4/n = {:.4}): {:?}",
+                self._influential_rows.len(),
+                4.0 / n,
+                self._influential_rows
+            );
+        }
+        if !(1.0..=3.0).contains(&self.durbin_watson) {
+            report_warning!(
+                "The residuals are autocorrelated in row order (Durbin-Watson = {:.2}, outside 1 to 3)",
+                self.durbin_watson
+            );
+        }
+    }
+}
+
 // /*
 // Type I, also called “sequential” sum of squares:
-// 
+//
 // anova_type_1 = MSB / MSE
-// 
+//
 // SS(A) for factor A.
-// 
+//
 // SS(B | A) for factor B.
-// 
+//
 // SS(AB | B, A) for interaction AB.
-// 
+//
 // This tests the main effect of factor A, followed by the main effect of
 // factor B after the main effect of A, followed by the interaction effect AB after the main effects.
-// 
+//
 // Because of the sequential nature and the fact that the two main factors are tested in a particular order,
 // this type of sums of squares will give different results for unbalanced data depending on which main
 // effect is considered first.
-// 
+//
 // For unbalanced data, this approach tests for a difference in the weighted marginal means.
 // In practical terms, this means that the results are dependent on the realized sample sizes, namely
 // the proportions in the particular data set. In other words, it is testing the first factor without
 // controlling for the other factor.
-// 
+//
 // Note that this is often not the hypothesis that is of interest when dealing with unbalanced data.
 //  */
-// 
+//
 // /*
 // Type II:
-// 
+//
 // SS(A | B) for factor A.
-// 
+//
 // SS(B | A) for factor B.
-// 
+//
 // This type tests for each main effect after the other main effect.
-// 
+//
 // Note that no significant interaction is assumed (in other words, you should test for interaction
 // first (SS(AB | A, B)) and only if AB is not significant, continue with the analysis for main effects).
-// 
+//
 // If there is indeed no interaction, then type II is statistically more powerful than type III.
-// 
+//
 // Computationally, this is equivalent to running a type I analysis with different orders of the
 // factors, and taking the appropriate output (the second, where one main effect is run after the other,
 // in the example above).
 //  */
-// 
+//
 // /*
 // Type III:
-// 
+//
 // S(A | B, AB) for factor A.
-// 
+//
 // SS(B | A, AB) for factor B.
-// 
+//
 // This type tests for the presence of a main effect after the other main effect and interaction.
 // This approach is therefore valid in the presence of significant interactions.
-// 
+//
 // However, it is often not interesting to interpret a main effect if interactions are present
 // (generally speaking, if a significant interaction is present, the main effects should not be
 // further analysed).
-// 
+//
 // If the interactions are not significant, type II gives a more powerful test.
 //  */
-// 
-// /*
-// Q = quantile function
-// 
-// Left-tailed test:
-// (−∞,Q(α)]
-// 
-// Right-tailed test:
-// [Q(1−α),∞)
-// 
-// Two-tailed test:
-// (−∞,Q(α/2)] ∪ [Q(1−α/2),∞)
-//  */
-// 
-// /*
-// u = quantile function of the standard normal distribution
-// 
-// Left-tailed Z critical value:
-// u(α)
-// 
-// Right-tailed Z critical value:
-// u(1−α)
-// 
-// Two-tailed Z critical value:
-// ±u(1−α/2)
-//  */
-// 
-// /*
-// Qt,d = quantile function of the t distribution with d degrees of freedom
-// 
-// Left-tailed t critical value:
-// Qt,d(α)
-// 
-// Right-tailed t critical value:
-// Qt,d(1−α)
-// 
-// Two-tailed t critical values:
-// ±Qt,d(1−α/2)
-//  */
\ No newline at end of file
@@ -0,0 +1,330 @@
+use crate::data_types::data_array::CategoricalDataArray;
+use crate::functions::apa::{format_bounded, format_p, format_statistic};
+use crate::functions::csv::is_missing;
+use crate::functions::distributions::chi_square_p;
+use crate::functions::stats_math::{cramers_v, phi};
+use crate::logging;
+use crate::reporter::{report, report_warning};
+use anyhow::{anyhow, Error, Result};
+
+/// Expected cell counts below this make a chi-square test unreliable.
+const MINIMUM_EXPECTED_COUNT: f64 = 5.0;
+
+/// A "select all that apply" column, where each cell lists the chosen options separated
+/// by a delimiter (for example "Red;Blue"). The options become indicator (0/1) variables,
+/// summarized in a frequency table and compared with Cochran's Q; with a grouping column,
+/// each option is also tested for independence from the groups.
+#[derive(Debug, Clone)]
+pub struct MultipleResponse<'a> {
+    pub name: String,
+    pub description: String,
+
+    // provided
+    _values: &'a [String],
+    _delimiter: String,
+    _groups: Option<&'a CategoricalDataArray<'a>>,
+
+    // calculated
+    _n: usize,                   // respondents
+    _n_responses: usize,         // options chosen, summed over respondents
+    _indicators: Vec<Vec<bool>>, // one per option, one entry per respondent
+    _cochran_df: usize,
+    _option_tests: Vec<OptionTest>,
+
+    _statistic_run: bool,
+    pub options: Vec<String>, // most often chosen first
+    pub counts: Vec<usize>,
+    pub cochran_q: f64,
+    pub p: f64,
+    pub apa: String,
+}
+
+/// Chi-square test of independence between choosing one option and the groups.
+#[derive(Debug, Clone)]
+pub struct OptionTest {
+    pub option: String,
+    pub chi_square: f64,
+    pub df: usize,
+    pub p: f64,
+    pub cramers_v: f64,
+    pub phi: Option<f64>, // two groups only; positive when choosing goes with the second group
+    pub minimum_expected: f64,
+    pub apa: String,
+}
+
+/// Splits each cell on `delimiter` into the set of options it lists, returning the
+/// distinct options (in order of first appearance) and one indicator column per option.
+/// Missing cells select nothing.
+pub fn indicator_columns(values: &[String], delimiter: &str) -> (Vec<String>, Vec<Vec<bool>>) {
+    let selections = values
+        .iter()
+        .map(|value| {
+            if is_missing(value) {
+                vec![]
+            } else {
+                value
+                    .split(delimiter)
+                    .map(str::trim)
+                    .filter(|option| !option.is_empty())
+                    .collect::<Vec<&str>>()
+            }
+        })
+        .collect::<Vec<Vec<&str>>>();
+
+    let mut options: Vec<String> = vec![];
+    for option in selections.iter().flatten() {
+        if !options.iter().any(|existing| existing == option) {
+            options.push(option.to_string());
+        }
+    }
+    let indicators = options
+        .iter()
+        .map(|option| {
+            selections
+                .iter()
+                .map(|selection| selection.contains(&option.as_str()))
+                .collect()
+        })
+        .collect();
+    (options, indicators)
+}
+
+impl<'a> MultipleResponse<'a> {
+    pub fn new(
+        name: String,
+        description: String,
+        values: &'a [String],
+        delimiter: &str,
+        groups: Option<&'a CategoricalDataArray<'a>>,
+    ) -> Result<MultipleResponse<'a>, Error> {
+        if delimiter.is_empty() {
+            return Err(anyhow!(
+                "The delimiter of a multiple response column cannot be empty"
+            ));
+        }
+        if groups.is_some_and(|groups| groups.n != values.len()) {
+            return Err(anyhow!("provided data are not of same length"));
+        }
+
+        let mut new_multiple_response = MultipleResponse {
+            name,
+            description,
+            _values: values,
+            _delimiter: delimiter.to_string(),
+            _groups: groups,
+            _n: values.len(),
+            _n_responses: 0,
+            _indicators: vec![],
+            _cochran_df: 0,
+            _option_tests: vec![],
+            _statistic_run: false,
+            options: vec![],
+            counts: vec![],
+            cochran_q: 0.0,
+            p: 0.0,
+            apa: String::new(),
+        };
+
+        new_multiple_response.run_statistic()?;
+
+        Ok(new_multiple_response)
+    }
+
+    fn run_statistic(&mut self) -> Result<(), Error> {
+        report!("...Calculating 'Multiple Response'...");
+
+        let (options, indicators) = indicator_columns(self._values, &self._delimiter);
+        if options.len() < 2 {
+            return Err(anyhow!(
+                "A multiple response analysis needs at least two options, found {}",
+                options.len()
+            ));
+        }
+        let counts = indicators
+            .iter()
+            .map(|indicator| indicator.iter().filter(|chosen| **chosen).count())
+            .collect::<Vec<usize>>();
+
+        // most often chosen first; ties keep their order of appearance
+        let mut order = (0..options.len()).collect::<Vec<usize>>();
+        order.sort_by(|a, b| counts[*b].cmp(&counts[*a]));
+        self.options = order.iter().map(|index| options[*index].clone()).collect();
+        self.counts = order.iter().map(|index| counts[*index]).collect();
+        self._indicators = order
+            .iter()
+            .map(|index| indicators[*index].clone())
+            .collect();
+        self._n_responses = self.counts.iter().sum();
+
+        // Q = (k - 1) * (k * sum(C_j^2) - N^2) / (k * N - sum(R_i^2)),
+        // C_j option totals, R_i respondent totals, N grand total
+        let k = self.options.len() as f64;
+        let grand_total = self._n_responses as f64;
+        let sum_column_squares = self
+            .counts
+            .iter()
+            .map(|count| f64::powi(*count as f64, 2))
+            .sum::<f64>();
+        let sum_row_squares = (0..self._n)
+            .map(|row| {
+                f64::powi(
+                    self._indicators
+                        .iter()
+                        .filter(|indicator| indicator[row])
+                        .count() as f64,
+                    2,
+                )
+            })
+            .sum::<f64>();
+        let denominator = k * grand_total - sum_row_squares;
+        if denominator == 0.0 {
+            return Err(anyhow!(
+                "Cochran's Q is undefined when every respondent chose either all options or none"
+            ));
+        }
+        self._cochran_df = self.options.len() - 1;
+        self.cochran_q =
+            (k - 1.0) * (k * sum_column_squares - f64::powi(grand_total, 2)) / denominator;
+        self.p = chi_square_p(self.cochran_q, self._cochran_df as f64)?;
+        self.apa = format!(
+            "Q({}) = {}, {}",
+            self._cochran_df,
+            format_statistic(self.cochran_q),
+            format_p(self.p)
+        );
+
+        if let Some(groups) = self._groups {
+            self._option_tests = self
+                .options
+                .iter()
+                .zip(self._indicators.iter())
+                .map(|(option, indicator)| option_test(option, indicator, groups))
+                .collect::<Result<Vec<OptionTest>, Error>>()?;
+        }
+
+        self._statistic_run = true;
+
+        Ok(())
+    }
+
+    pub fn print(mut self) -> Result<(), Error> {
+        if self._statistic_run {
+            report!("{}", logging::format_title(&self.name));
+            report!("Description: '{}'", self.description);
+            report!("Respondents...................{}", self._n);
+            report!("Responses.....................{}", self._n_responses);
+            for (option, count) in self.options.iter().zip(self.counts.iter()) {
+                report!("{}", option);
+                report!("..count: {}", count);
+                report!(
+                    "..% of respondents: {:.1}",
+                    100.0 * *count as f64 / self._n as f64
+                );
+                report!(
+                    "..% of responses: {:.1}",
+                    100.0 * *count as f64 / self._n_responses as f64
+                );
+            }
+            report!("Cochran's Q...................{}", self.cochran_q);
+            report!("df............................{}", self._cochran_df);
+            report!("p.............................{}", self.p);
+            report!("APA...........................{}", self.apa);
+
+            if let Some(groups) = self._groups {
+                report!(
+                    "{}",
+                    logging::format_title(&format!("Options by {}", groups.name))
+                );
+                for test in self._option_tests.iter() {
+                    report!("{}", test.option);
+                    report!("..Chi-square({}): {}", test.df, test.chi_square);
+                    report!("..p: {}", test.p);
+                    match test.phi {
+                        Some(phi) => report!("..phi: {}", phi),
+                        None => report!("..Cramér's V: {}", test.cramers_v),
+                    }
+                    report!("..APA: {}", test.apa);
+                    if test.minimum_expected < MINIMUM_EXPECTED_COUNT {
+                        report_warning!(
+                            "'{}' has an expected count of {:.2} (below {}); the chi-square \
+                             approximation may be unreliable",
+                            test.option,
+                            test.minimum_expected,
+                            MINIMUM_EXPECTED_COUNT
+                        );
+                    }
+                }
+            }
+            Ok(())
+        } else {
+            self.run_statistic()?;
+            self.print()
+        }
+    }
+}
+
+/// Pearson chi-square on the 2 x g table of chosen / not chosen by group.
+fn option_test(
+    option: &str,
+    indicator: &[bool],
+    groups: &CategoricalDataArray,
+) -> Result<OptionTest, Error> {
+    let levels = groups.levels.values().collect::<Vec<&Vec<usize>>>();
+    if levels.len() < 2 {
+        return Err(anyhow!(
+            "Comparing options across '{}' needs at least two groups",
+            groups.name
+        ));
+    }
+
+    let n = indicator.len() as f64;
+    let chosen_total = indicator.iter().filter(|chosen| **chosen).count() as f64;
+    let mut chi_square = 0.0;
+    let mut minimum_expected = f64::INFINITY;
+    for rows in levels.iter() {
+        let chosen = rows.iter().filter(|row| indicator[**row]).count() as f64;
+        let group_total = rows.len() as f64;
+        for (observed, row_total) in [
+            (chosen, chosen_total),
+            (group_total - chosen, n - chosen_total),
+        ] {
+            let expected = row_total * group_total / n;
+            minimum_expected = minimum_expected.min(expected);
+            if expected > 0.0 {
+                chi_square += f64::powi(observed - expected, 2) / expected;
+            }
+        }
+    }
+
+    let df = levels.len() - 1;
+    let p = chi_square_p(chi_square, df as f64)?;
+    let cramers_v = cramers_v(chi_square, n, 2, levels.len())?;
+    let phi = match levels[..] {
+        [first, second] => {
+            let chosen = |rows: &Vec<usize>| rows.iter().filter(|row| indicator[**row]).count();
+            let (a, b) = (chosen(first) as f64, chosen(second) as f64);
+            let (c, d) = (first.len() as f64 - a, second.len() as f64 - b);
+            // undefined when no one, or everyone, chose the option
+            phi([[b, a], [d, c]]).ok()
+        }
+        _ => None,
+    };
+    Ok(OptionTest {
+        option: option.to_string(),
+        chi_square,
+        df,
+        p,
+        cramers_v,
+        phi,
+        minimum_expected,
+        apa: format!(
+            "χ²({}, N = {}) = {}, {}, {} = {}",
+            df,
+            indicator.len(),
+            format_statistic(chi_square),
+            format_p(p),
+            if levels.len() == 2 { "φ" } else { "V" },
+            format_bounded(cramers_v)
+        ),
+    })
+}
@@ -0,0 +1,515 @@
+use crate::functions::apa::{format_bounded, format_p, format_statistic};
+use crate::functions::distributions::{chi_square_p, two_tailed_p_from_z};
+use crate::functions::formula::Design;
+use crate::functions::matrix::{invert, multiply, multiply_vector, transpose, Matrix};
+use crate::functions::optimize::maximize;
+use crate::logging;
+use crate::reporter::{report, report_warning};
+use anyhow::{anyhow, Error, Result};
+
+/// A proportional odds (cumulative logit) model of an ordinal response with K levels,
+/// logit P(Y <= j) = theta_j - x'b for each cut point j = 1..K-1, fit by maximum
+/// likelihood. A positive coefficient moves the response toward its higher levels, and
+/// exp(b) is the odds ratio of being above any cut point. Brant's test checks the
+/// proportional odds assumption: that one set of coefficients fits every cut point.
+#[derive(Debug, Clone)]
+pub struct OrdinalRegression {
+    pub name: String,
+    pub description: String,
+    _n: usize,
+    _p: usize, // design columns
+
+    // provided
+    _design: Design,      // response holds each row's 0-based level
+    _levels: Vec<String>, // lowest first
+
+    // calculated
+    _standard_errors: Vec<f64>, // thresholds, then coefficients
+    _z_values: Vec<f64>,
+    _p_values: Vec<f64>,
+    _null_log_likelihood: f64,
+    _brant: Option<BrantTest>,
+
+    _statistic_run: bool,
+    pub thresholds: Vec<f64>,   // theta_j, one per cut point
+    pub coefficients: Vec<f64>, // one per design column
+    pub log_likelihood: f64,
+    pub chi_square: f64, // likelihood ratio against the thresholds-only model
+    pub p: f64,
+    pub pseudo_r_squared: f64, // McFadden's
+    pub apa: String,
+}
+
+/// Brant's Wald test of equal coefficients across the cut points, from a binary logistic
+/// regression at each cut point, for all coefficients at once and for each one.
+#[derive(Debug, Clone)]
+pub struct BrantTest {
+    pub chi_square: f64,
+    pub df: usize,
+    pub p: f64,
+    pub columns: Vec<(f64, f64)>, // chi-square and p per coefficient, df = K - 2
+}
+
+impl OrdinalRegression {
+    pub fn new(
+        name: String,
+        description: String,
+        design: Design,
+        levels: Vec<String>,
+    ) -> Result<OrdinalRegression, Error> {
+        if design.columns.is_empty() {
+            return Err(anyhow!(
+                "An ordinal regression needs at least one predictor"
+            ));
+        }
+        if levels.len() < 2 {
+            return Err(anyhow!(
+                "An ordinal regression needs a response with at least two levels"
+            ));
+        }
+        let n = design.response.len();
+        let parameter_count = levels.len() - 1 + design.columns.len();
+        if n <= parameter_count {
+            return Err(anyhow!(
+                "More observations than parameters are required for an ordinal regression ({} rows for {} parameters)",
+                n,
+                parameter_count
+            ));
+        }
+
+        let mut new_ordinal_regression = OrdinalRegression {
+            name,
+            description,
+            _n: n,
+            _p: design.columns.len(),
+            _design: design,
+            _levels: levels,
+            _standard_errors: vec![],
+            _z_values: vec![],
+            _p_values: vec![],
+            _null_log_likelihood: 0.0,
+            _brant: None,
+            _statistic_run: false,
+            thresholds: vec![],
+            coefficients: vec![],
+            log_likelihood: 0.0,
+            chi_square: 0.0,
+            p: 0.0,
+            pseudo_r_squared: 0.0,
+            apa: String::new(),
+        };
+
+        new_ordinal_regression.run_statistic()?;
+
+        Ok(new_ordinal_regression)
+    }
+
+    fn run_statistic(&mut self) -> Result<(), Error> {
+        report!("...Calculating 'Ordinal Regression'...");
+
+        let cuts = self._levels.len() - 1;
+        let response = self
+            ._design
+            .response
+            .iter()
+            .map(|level| *level as usize)
+            .collect::<Vec<usize>>();
+        let rows: Matrix = (0..self._n)
+            .map(|row| {
+                self._design
+                    .columns
+                    .iter()
+                    .map(|column| column.values[row])
+                    .collect()
+            })
+            .collect();
+
+        // start from the thresholds-only fit, theta_j = logit of the share at or below j
+        let mut counts = vec![0usize; self._levels.len()];
+        for level in response.iter() {
+            counts[*level] += 1;
+        }
+        let mut parameters = (0..cuts)
+            .map(|cut| {
+                let below = counts[..=cut].iter().sum::<usize>() as f64 / self._n as f64;
+                f64::ln(below / (1.0 - below))
+            })
+            .collect::<Vec<f64>>();
+        parameters.extend(vec![0.0; self._p]);
+        self._null_log_likelihood = counts
+            .iter()
+            .map(|count| *count as f64 * f64::ln(*count as f64 / self._n as f64))
+            .sum();
+
+        let (parameters, log_likelihood, hessian) = maximize(parameters, |parameters| {
+            cumulative_logit_likelihood(parameters, &rows, &response, cuts)
+        })
+        .map_err(|error| anyhow!("The ordinal regression {}", error))?;
+
+        // Var = (-H)^-1 at the maximum
+        let covariance = invert(
+            &hessian
+                .iter()
+                .map(|row| row.iter().map(|cell| -cell).collect())
+                .collect(),
+        )?;
+        self._standard_errors = (0..parameters.len())
+            .map(|j| f64::sqrt(covariance[j][j]))
+            .collect();
+        self._z_values = parameters
+            .iter()
+            .zip(self._standard_errors.iter())
+            .map(|(parameter, se)| parameter / se)
+            .collect();
+        self._p_values = self
+            ._z_values
+            .iter()
+            .map(|z| two_tailed_p_from_z(*z))
+            .collect::<Result<Vec<f64>, Error>>()?;
+        self.thresholds = parameters[..cuts].to_vec();
+        self.coefficients = parameters[cuts..].to_vec();
+        self.log_likelihood = log_likelihood;
+
+        // chi-square = 2 (LL - LL_0), pseudo R^2 = 1 - LL / LL_0
+        self.chi_square = 2.0 * (self.log_likelihood - self._null_log_likelihood);
+        self.p = chi_square_p(self.chi_square, self._p as f64)?;
+        self.pseudo_r_squared = 1.0 - self.log_likelihood / self._null_log_likelihood;
+
+        self._brant = if cuts >= 2 {
+            match brant_test(&rows, &response, cuts) {
+                Ok(brant) => Some(brant),
+                Err(error) => {
+                    report_warning!("The Brant test could not be run: {}", error);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        self.apa = format!(
+            "χ²({}, N = {}) = {}, {}, McFadden R² = {}",
+            self._p,
+            self._n,
+            format_statistic(self.chi_square),
+            format_p(self.p),
+            format_bounded(self.pseudo_r_squared)
+        );
+
+        self._statistic_run = true;
+
+        Ok(())
+    }
+
+    pub fn print(mut self) -> Result<(), Error> {
+        if self._statistic_run {
+            let cuts = self.thresholds.len();
+            report!("{}", logging::format_title(&self.name));
+            report!("Description: '{}'", self.description);
+            report!("n.............................{}", self._n);
+            report!("p.............................{}", self._p);
+            report!(
+                "Response......................{}",
+                self._design.response_name
+            );
+            report!("Levels........................{}", self._levels.join(" < "));
+            for (index, threshold) in self.thresholds.iter().enumerate() {
+                report!(
+                    "Threshold {} | {}",
+                    self._levels[index],
+                    self._levels[index + 1]
+                );
+                report!("..theta: {}", threshold);
+                report!("..SE: {}", self._standard_errors[index]);
+            }
+            for (index, coefficient) in self.coefficients.iter().enumerate() {
+                report!("{}", self._design.columns[index].name);
+                report!("..b: {}", coefficient);
+                report!("..SE: {}", self._standard_errors[cuts + index]);
+                report!("..z: {}", self._z_values[cuts + index]);
+                report!("..p (two-tailed): {}", self._p_values[cuts + index]);
+                report!("..Odds Ratio: {}", f64::exp(*coefficient));
+            }
+            report!("Log-Likelihood................{}", self.log_likelihood);
+            report!(
+                "Log-Likelihood (thresholds)...{}",
+                self._null_log_likelihood
+            );
+            report!("Likelihood Ratio Chi-Square...{}", self.chi_square);
+            report!("df............................{}", self._p);
+            report!("p.............................{}", self.p);
+            report!("McFadden R^2..................{}", self.pseudo_r_squared);
+            report!("APA...........................{}", self.apa);
+
+            report!(
+                "{}",
+                logging::format_title("Proportional Odds (Brant Test)")
+            );
+            match &self._brant {
+                Some(brant) => {
+                    report!("Omnibus");
+                    report!("..Chi-Square: {}", brant.chi_square);
+                    report!("..df: {}", brant.df);
+                    report!("..p: {}", brant.p);
+                    for (index, (chi_square, p)) in brant.columns.iter().enumerate() {
+                        report!("{}", self._design.columns[index].name);
+                        report!("..Chi-Square: {}", chi_square);
+                        report!("..df: {}", cuts - 1);
+                        report!("..p: {}", p);
+                    }
+                    if brant.p < 0.05 {
+                        report_warning!(
+                            "The coefficients differ between cut points (p < .05), so the proportional odds assumption may not hold"
+                        );
+                    }
+                }
+                None if cuts < 2 => {
+                    report!("With two levels there is one cut point, so there is nothing to test");
+                }
+                None => report!("Not available"),
+            }
+            Ok(())
+        } else {
+            self.run_statistic()?;
+            self.print()
+        }
+    }
+}
+
+fn logistic(t: f64) -> f64 {
+    1.0 / (1.0 + f64::exp(-t))
+}
+
+/// Log-likelihood of the cumulative logit model, with its gradient and Hessian in the
+/// parameters (thresholds first, then coefficients), or None where a probability is not
+/// positive (thresholds out of order).
+fn cumulative_logit_likelihood(
+    parameters: &[f64],
+    rows: &Matrix,
+    response: &[usize],
+    cuts: usize,
+) -> Option<(f64, Vec<f64>, Matrix)> {
+    let m = parameters.len();
+    let (thresholds, coefficients) = parameters.split_at(cuts);
+    let mut log_likelihood = 0.0;
+    let mut gradient = vec![0.0; m];
+    let mut hessian = vec![vec![0.0; m]; m];
+
+    for (x, level) in rows.iter().zip(response.iter().copied()) {
+        let xb = x.iter().zip(coefficients).map(|(x, b)| x * b).sum::<f64>();
+        // F, its density f = F(1 - F), and f' = f(1 - 2F) at the cut points above and
+        // below the level; the ends of the scale are F = 1 and F = 0 and don't move
+        let at = |cut: usize| {
+            let cdf = logistic(thresholds[cut] - xb);
+            let density = cdf * (1.0 - cdf);
+            (cdf, density, density * (1.0 - 2.0 * cdf))
+        };
+        let (upper, upper_density, upper_slope) = match level < cuts {
+            true => at(level),
+            false => (1.0, 0.0, 0.0),
+        };
+        let (lower, lower_density, lower_slope) = match level > 0 {
+            true => at(level - 1),
+            false => (0.0, 0.0, 0.0),
+        };
+        let probability = upper - lower;
+        if probability <= 0.0 || !probability.is_finite() {
+            return None;
+        }
+        log_likelihood += probability.ln();
+
+        // first and second derivatives of the probability
+        let mut first = vec![0.0; m];
+        let mut second = vec![vec![0.0; m]; m];
+        if level < cuts {
+            first[level] += upper_density;
+            second[level][level] += upper_slope;
+        }
+        if level > 0 {
+            first[level - 1] -= lower_density;
+            second[level - 1][level - 1] -= lower_slope;
+        }
+        for (c, x_c) in x.iter().enumerate() {
+            first[cuts + c] = -x_c * (upper_density - lower_density);
+            if level < cuts {
+                second[level][cuts + c] -= x_c * upper_slope;
+                second[cuts + c][level] -= x_c * upper_slope;
+            }
+            if level > 0 {
+                second[level - 1][cuts + c] += x_c * lower_slope;
+                second[cuts + c][level - 1] += x_c * lower_slope;
+            }
+            for (d, x_d) in x.iter().enumerate() {
+                second[cuts + c][cuts + d] += x_c * x_d * (upper_slope - lower_slope);
+            }
+        }
+
+        // d ln P = dP / P, d^2 ln P = d^2 P / P - dP dP' / P^2
+        for a in 0..m {
+            gradient[a] += first[a] / probability;
+            for b in 0..m {
+                hessian[a][b] +=
+                    second[a][b] / probability - first[a] * first[b] / probability.powi(2);
+            }
+        }
+    }
+    Some((log_likelihood, gradient, hessian))
+}
+
+/// Brant's test: a logistic regression of "above cut point j" on the predictors for each
+/// j, then a Wald test that the slopes agree across the cut points, using the covariance
+/// of the fits, Cov(b_j, b_l) = A_j X' W_jl X A_l with A_j = (X' W_jj X)^-1 and
+/// W_jl = diag(pi_l (1 - pi_j)) for j <= l.
+fn brant_test(rows: &Matrix, response: &[usize], cuts: usize) -> Result<BrantTest, Error> {
+    let design: Matrix = rows
+        .iter()
+        .map(|x| std::iter::once(1.0).chain(x.iter().copied()).collect())
+        .collect();
+    let k = design[0].len();
+    let p = k - 1;
+    let design_transposed = transpose(&design);
+
+    let mut slopes = vec![];
+    let mut fitted = vec![];
+    let mut information_inverses = vec![];
+    for cut in 0..cuts {
+        let above = response
+            .iter()
+            .map(|level| *level > cut)
+            .collect::<Vec<bool>>();
+        let (coefficients, pi) = fit_logit(&design, &above).map_err(|error| {
+            anyhow!("the logistic regression at cut point {} {}", cut + 1, error)
+        })?;
+        information_inverses.push(invert(&weighted_cross_product(
+            &design_transposed,
+            &design,
+            pi.iter().map(|pi| pi * (1.0 - pi)),
+        )?)?);
+        slopes.extend_from_slice(&coefficients[1..]);
+        fitted.push(pi);
+    }
+
+    // covariance of the stacked slopes, (cuts * p) square
+    let mut covariance = vec![vec![0.0; cuts * p]; cuts * p];
+    for j in 0..cuts {
+        for l in j..cuts {
+            let block = if j == l {
+                information_inverses[j].clone()
+            } else {
+                let middle = weighted_cross_product(
+                    &design_transposed,
+                    &design,
+                    fitted[l]
+                        .iter()
+                        .zip(fitted[j].iter())
+                        .map(|(pi_l, pi_j)| pi_l * (1.0 - pi_j)),
+                )?;
+                multiply(
+                    &multiply(&information_inverses[j], &middle)?,
+                    &information_inverses[l],
+                )?
+            };
+            for a in 0..p {
+                for b in 0..p {
+                    covariance[j * p + a][l * p + b] = block[a + 1][b + 1];
+                    covariance[l * p + b][j * p + a] = block[a + 1][b + 1];
+                }
+            }
+        }
+    }
+
+    // H0: b_1 = b_j for j = 2..cuts, over the chosen coefficients
+    let wald = |included: &[usize]| -> Result<(f64, usize, f64), Error> {
+        let contrasts: Matrix = (1..cuts)
+            .flat_map(|cut| {
+                included.iter().map(move |c| {
+                    let mut row = vec![0.0; cuts * p];
+                    row[*c] = 1.0;
+                    row[cut * p + c] = -1.0;
+                    row
+                })
+            })
+            .collect();
+        let difference = multiply_vector(&contrasts, &slopes)?;
+        let variance = multiply(&multiply(&contrasts, &covariance)?, &transpose(&contrasts))?;
+        let chi_square = difference
+            .iter()
+            .zip(multiply_vector(&invert(&variance)?, &difference)?)
+            .map(|(d, v)| d * v)
+            .sum::<f64>();
+        let df = contrasts.len();
+        Ok((chi_square, df, chi_square_p(chi_square, df as f64)?))
+    };
+
+    let (chi_square, df, p_value) = wald(&(0..p).collect::<Vec<usize>>())?;
+    let columns = (0..p)
+        .map(|c| wald(&[c]).map(|(chi_square, _, p)| (chi_square, p)))
+        .collect::<Result<Vec<(f64, f64)>, Error>>()?;
+    Ok(BrantTest {
+        chi_square,
+        df,
+        p: p_value,
+        columns,
+    })
+}
+
+/// Logistic regression of `outcome` on the design (a leading 1 for the intercept) by
+/// Newton's method, returning the coefficients and fitted probabilities.
+fn fit_logit(design: &Matrix, outcome: &[bool]) -> Result<(Vec<f64>, Vec<f64>), Error> {
+    let design_transposed = transpose(design);
+    let likelihood = |coefficients: &[f64]| {
+        let pi = design
+            .iter()
+            .map(|x| logistic(x.iter().zip(coefficients).map(|(x, b)| x * b).sum::<f64>()))
+            .collect::<Vec<f64>>();
+        let log_likelihood = pi
+            .iter()
+            .zip(outcome)
+            .map(|(pi, y)| if *y { pi.ln() } else { (1.0 - pi).ln() })
+            .sum::<f64>();
+        if !log_likelihood.is_finite() {
+            return None;
+        }
+        // g = X'(y - pi), H = -X' diag(pi (1 - pi)) X
+        let residuals = pi
+            .iter()
+            .zip(outcome)
+            .map(|(pi, y)| if *y { 1.0 - pi } else { -pi })
+            .collect::<Vec<f64>>();
+        let gradient = multiply_vector(&design_transposed, &residuals).ok()?;
+        let hessian = weighted_cross_product(
+            &design_transposed,
+            design,
+            pi.iter().map(|pi| -pi * (1.0 - pi)),
+        )
+        .ok()?;
+        Some((log_likelihood, gradient, hessian))
+    };
+    let (coefficients, _, _) = maximize(vec![0.0; design[0].len()], likelihood)?;
+    let pi = design
+        .iter()
+        .map(|x| {
+            logistic(
+                x.iter()
+                    .zip(coefficients.iter())
+                    .map(|(x, b)| x * b)
+                    .sum::<f64>(),
+            )
+        })
+        .collect();
+    Ok((coefficients, pi))
+}
+
+/// X' diag(w) X.
+fn weighted_cross_product(
+    design_transposed: &Matrix,
+    design: &Matrix,
+    weights: impl Iterator<Item = f64>,
+) -> Result<Matrix, Error> {
+    let weighted: Matrix = design
+        .iter()
+        .zip(weights)
+        .map(|(row, weight)| row.iter().map(|x| x * weight).collect())
+        .collect();
+    multiply(design_transposed, &weighted)
+}
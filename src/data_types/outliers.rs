@@ -0,0 +1,130 @@
+use crate::functions::csv::{is_missing, CSVData};
+use crate::functions::distributions::t_quantile;
+use crate::functions::stats_math::{mean, quantile, standard_deviation};
+use anyhow::{Error, Result};
+use clap::ValueEnum;
+
+/// Values farther than this many standard deviations from the mean are z-score outliers.
+pub const Z_THRESHOLD: f64 = 3.0;
+
+/// Tukey's fences sit this many interquartile ranges beyond the first and third quartiles.
+pub const IQR_MULTIPLIER: f64 = 1.5;
+
+/// Significance level for each round of Grubbs' test.
+pub const GRUBBS_ALPHA: f64 = 0.05;
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutlierMethod {
+    /// |z| > 3
+    Z,
+    /// Beyond 1.5 IQR outside the quartiles
+    Iqr,
+    /// Grubbs' test at alpha = 0.05, repeated until no further outlier is found
+    Grubbs,
+}
+
+impl OutlierMethod {
+    pub fn label(self) -> String {
+        match self {
+            OutlierMethod::Z => format!("|z| > {}", Z_THRESHOLD),
+            OutlierMethod::Iqr => format!("{} IQR fences", IQR_MULTIPLIER),
+            OutlierMethod::Grubbs => format!("Grubbs, alpha = {}", GRUBBS_ALPHA),
+        }
+    }
+
+    /// Indices into `data` of the values this method flags, in ascending order.
+    pub fn flag(self, data: &[f64]) -> Result<Vec<usize>, Error> {
+        match self {
+            OutlierMethod::Z => z_score_outliers(data, Z_THRESHOLD),
+            OutlierMethod::Iqr => iqr_outliers(data, IQR_MULTIPLIER),
+            OutlierMethod::Grubbs => grubbs_outliers(data, GRUBBS_ALPHA),
+        }
+    }
+}
+
+/// Indices of values whose z-score exceeds `threshold` in absolute value.
+pub fn z_score_outliers(data: &[f64], threshold: f64) -> Result<Vec<usize>, Error> {
+    if data.len() < 3 {
+        return Ok(vec![]);
+    }
+    let data = data.to_vec();
+    let data_mean = mean(&data)?;
+    let data_sd = standard_deviation(Some(&data), None, None)?;
+    if data_sd == 0.0 {
+        return Ok(vec![]);
+    }
+    Ok((0..data.len())
+        .filter(|index| ((data[*index] - data_mean) / data_sd).abs() > threshold)
+        .collect())
+}
+
+/// Indices of values below Q1 - k * IQR or above Q3 + k * IQR (Tukey's fences).
+pub fn iqr_outliers(data: &[f64], k: f64) -> Result<Vec<usize>, Error> {
+    if data.len() < 4 {
+        return Ok(vec![]);
+    }
+    let q1 = quantile(data, 0.25)?;
+    let q3 = quantile(data, 0.75)?;
+    let (lower, upper) = (q1 - k * (q3 - q1), q3 + k * (q3 - q1));
+    Ok((0..data.len())
+        .filter(|index| data[*index] < lower || data[*index] > upper)
+        .collect())
+}
+
+/// Indices flagged by two-sided Grubbs' tests, each round removing the value farthest
+/// from the mean while G = max|x - mean| / s exceeds
+/// G_crit = ((n - 1) / sqrt(n)) * sqrt(t^2 / (n - 2 + t^2)), t = t(1 - alpha / 2n, n - 2).
+pub fn grubbs_outliers(data: &[f64], alpha: f64) -> Result<Vec<usize>, Error> {
+    let mut remaining = (0..data.len()).collect::<Vec<usize>>();
+    let mut flagged = vec![];
+    while remaining.len() >= 3 {
+        let values = remaining
+            .iter()
+            .map(|index| data[*index])
+            .collect::<Vec<f64>>();
+        let data_mean = mean(&values)?;
+        let data_sd = standard_deviation(Some(&values), None, None)?;
+        if data_sd == 0.0 {
+            break;
+        }
+        let (position, g) = values
+            .iter()
+            .map(|x| (x - data_mean).abs() / data_sd)
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap();
+
+        let n = values.len() as f64;
+        let t = t_quantile(1.0 - alpha / (2.0 * n), n - 2.0)?;
+        let g_critical = (n - 1.0) / f64::sqrt(n) * f64::sqrt(t * t / (n - 2.0 + t * t));
+        if g <= g_critical {
+            break;
+        }
+        flagged.push(remaining.remove(position));
+    }
+    flagged.sort();
+    Ok(flagged)
+}
+
+/// 0-based CSV rows whose value in `column` is flagged by `method`. Missing and
+/// unparsable cells are ignored.
+pub fn column_outlier_rows(
+    csv_data: &CSVData,
+    column: usize,
+    method: OutlierMethod,
+) -> Result<Vec<usize>, Error> {
+    let values = (0..csv_data.column_count)
+        .filter(|row| !is_missing(&csv_data.data[csv_data.row_length * row + column]))
+        .filter_map(|row| {
+            csv_data
+                .get_datum::<f64>(row, column, None)
+                .ok()
+                .map(|value| (row, value))
+        })
+        .collect::<Vec<(usize, f64)>>();
+    Ok(method
+        .flag(&values.iter().map(|(_, value)| *value).collect::<Vec<f64>>())?
+        .into_iter()
+        .map(|index| values[index].0)
+        .collect())
+}
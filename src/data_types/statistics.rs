@@ -1,10 +1,19 @@
+use crate::data_types::assumptions::{print_assumptions_table, HomogeneityOfVariance, Normality};
+use crate::data_types::cluster_robust::ClusterRobust;
 use crate::data_types::data_array::{CategoricalDataArray, ContinuousDataArray};
+use crate::functions::apa::{format_bounded, format_p, format_statistic};
+use crate::functions::distributions::{
+    chi_square_p, f_p, two_tailed_p_from_t, two_tailed_p_from_z,
+};
+use crate::functions::matrix::{invert, multiply, multiply_vector, transpose, Matrix};
 use crate::functions::stats_math::{
-    covariance, differences, mean, pooled_variance, sum_of_squares, variance,
+    covariance, differences, has_no_spread, kendall_tau_b, mean, pearson_r_method_1,
+    pooled_variance, ranks, spearman_rho, sum_of_squares, t_statistic_from_r, tie_correction,
+    variance,
 };
 use crate::logging;
+use crate::reporter::report;
 use anyhow::{anyhow, Error};
-use log::info;
 
 #[derive(Debug, Clone)]
 pub struct SingleSampleT<'a> {
@@ -21,9 +30,13 @@ pub struct SingleSampleT<'a> {
     // calculated
     _variance: f64,
     _standard_deviation: f64,
+    _cohens_d: f64,
+    _normality: Vec<Normality>,
 
     _statistic_run: bool,
     pub t: f64,
+    pub p: f64,
+    pub apa: String,
 }
 
 impl<'a> SingleSampleT<'a> {
@@ -37,13 +50,17 @@ impl<'a> SingleSampleT<'a> {
             name,
             description,
             _n: data.data_array.data.len(),
-            _df: data.data_array.data.len() - 1,
+            _df: 0,
             _data: data,
             _mu: mu,
             _variance: data.variance,
             _standard_deviation: data.standard_deviation,
+            _cohens_d: 0.0,
+            _normality: vec![],
             _statistic_run: false,
             t: 0.0,
+            p: 0.0,
+            apa: String::new(),
         };
 
         new_sst.run_statistic()?;
@@ -52,21 +69,56 @@ impl<'a> SingleSampleT<'a> {
     }
 
     fn run_statistic(&mut self) -> anyhow::Result<(), Error> {
-        info!("...Calculating 'Single Sample t'...");
+        report!("...Calculating 'Single Sample t'...");
         self._n = self._data.data_array.data.len();
+        if self._n < 2 {
+            return Err(anyhow!(
+                "At least two values are required--cannot run 'Single Sample t'"
+            ));
+        }
+        let values = self
+            ._data
+            .data_array
+            .data
+            .iter()
+            .map(|x| x.1)
+            .collect::<Vec<f64>>();
+        if has_no_spread(&values) {
+            return Err(anyhow!(
+                "All values are equal--cannot run 'Single Sample t'"
+            ));
+        }
         self._df = self._n - 1;
-        self.t = (self._data.mean - self._mu) / self._standard_deviation;
+        self.t =
+            (self._data.mean - self._mu) / (self._standard_deviation / f64::sqrt(self._n as f64));
+        self.p = two_tailed_p_from_t(self.t, self._df as f64)?;
+
+        // d = (x_bar - mu) / s
+        self._cohens_d = (self._data.mean - self._mu) / self._standard_deviation;
+        self.apa = format!(
+            "t({}) = {}, {}, d = {}",
+            self._df,
+            format_statistic(self.t),
+            format_p(self.p),
+            format_statistic(self._cohens_d)
+        );
+
+        self._normality = vec![Normality::from_data_array(self._data)?];
         self._statistic_run = true;
         Ok(())
     }
 
-    pub fn print(mut self) {
+    pub fn print(mut self) -> anyhow::Result<(), Error> {
         if self._statistic_run {
-            info!("Single Sample t = {}", self.t)
+            report!("Single Sample t = {}", self.t);
+            report!("APA: {}", self.apa);
+            self._normality
+                .iter()
+                .for_each(|check| check.warn_if_questionable());
+            Ok(())
         } else {
-            self.run_statistic()
-                .expect("Error running single sample t test");
-            self.print();
+            self.run_statistic()?;
+            self.print()
         }
     }
 }
@@ -88,9 +140,13 @@ pub struct PairedSamplesT<'a> {
     _sum_of_squares_differences: f64,
     _variance_of_differences: f64,
     _s_sub_d_bar: f64,
+    _cohens_d: f64,
+    _normality: Vec<Normality>,
 
     _statistic_run: bool,
     pub t: f64,
+    pub p: f64,
+    pub apa: String,
 }
 
 impl<'a> PairedSamplesT<'a> {
@@ -105,7 +161,7 @@ impl<'a> PairedSamplesT<'a> {
                 name,
                 description,
                 _n: data_x.data_array.data.len(),
-                _df: data_x.data_array.data.len() - 1,
+                _df: 0,
                 _data_x: data_x,
                 _data_y: data_y,
                 _differences: vec![],
@@ -113,8 +169,12 @@ impl<'a> PairedSamplesT<'a> {
                 _sum_of_squares_differences: 0.0,
                 _variance_of_differences: 0.0,
                 _s_sub_d_bar: 0.0,
+                _cohens_d: 0.0,
+                _normality: vec![],
                 _statistic_run: false,
                 t: 0.0,
+                p: 0.0,
+                apa: String::new(),
             };
 
             new_pst.run_statistic()?;
@@ -127,9 +187,14 @@ impl<'a> PairedSamplesT<'a> {
 
     fn run_statistic(&mut self) -> anyhow::Result<(), Error> {
         if self._data_x.data_array.data.len() == self._data_y.data_array.data.len() {
-            info!("...Calculating 'Paired Sample t'...");
+            report!("...Calculating 'Paired Sample t'...");
 
             self._n = self._data_x.data_array.data.len();
+            if self._n < 2 {
+                return Err(anyhow!(
+                    "At least two pairs are required--cannot run 'Paired Sample t'"
+                ));
+            }
             self._df = self._n - 1;
 
             let data_x = &self
@@ -147,6 +212,11 @@ impl<'a> PairedSamplesT<'a> {
                 .map(|y| y.1)
                 .collect::<Vec<f64>>();
             self._differences = differences(data_x, data_y)?;
+            if has_no_spread(&self._differences) {
+                return Err(anyhow!(
+                    "The paired differences have no spread--cannot run 'Paired Sample t'"
+                ));
+            }
             self._mean_of_differences = self._differences.iter().sum::<f64>() / data_x.len() as f64;
             self._sum_of_squares_differences = self
                 ._differences
@@ -160,8 +230,28 @@ impl<'a> PairedSamplesT<'a> {
                     } else {
                         1.0
                     });
-            self._s_sub_d_bar = f64::sqrt(self._variance_of_differences);
+            self._s_sub_d_bar = f64::sqrt(self._variance_of_differences / self._n as f64);
             self.t = (self._mean_of_differences - 0.0) / self._s_sub_d_bar;
+            self.p = two_tailed_p_from_t(self.t, self._df as f64)?;
+
+            // d = d_bar / s_d
+            self._cohens_d = self._mean_of_differences / f64::sqrt(self._variance_of_differences);
+            self.apa = format!(
+                "t({}) = {}, {}, d = {}",
+                self._df,
+                format_statistic(self.t),
+                format_p(self.p),
+                format_statistic(self._cohens_d)
+            );
+
+            // the paired t assumes the differences, not the raw scores, are normal
+            self._normality = vec![Normality::new(
+                format!(
+                    "Differences of {} and {}",
+                    self._data_x.name, self._data_y.name
+                ),
+                &self._differences,
+            )?];
 
             self._statistic_run = true;
 
@@ -173,13 +263,17 @@ impl<'a> PairedSamplesT<'a> {
         }
     }
 
-    pub fn print(mut self) {
+    pub fn print(mut self) -> anyhow::Result<(), Error> {
         if self._statistic_run {
-            info!("Paired Sample t = {}", self.t)
+            report!("Paired Sample t = {}", self.t);
+            report!("APA: {}", self.apa);
+            self._normality
+                .iter()
+                .for_each(|check| check.warn_if_questionable());
+            Ok(())
         } else {
-            self.run_statistic()
-                .expect("Error running paired sample t test");
-            self.print();
+            self.run_statistic()?;
+            self.print()
         }
     }
 }
@@ -200,9 +294,14 @@ pub struct IndependentGroupsT<'a> {
     _variance_level_2: f64,
     _pooled_variance: f64,
     _standard_deviation_differences_between_means: f64,
+    _cohens_d: f64,
+    _normality: Vec<Normality>,
+    _homogeneity: Option<HomogeneityOfVariance>,
 
     _statistic_run: bool,
     pub t: f64,
+    pub p: f64,
+    pub apa: String,
 }
 
 impl<'a> IndependentGroupsT<'a> {
@@ -226,8 +325,13 @@ impl<'a> IndependentGroupsT<'a> {
                 _variance_level_2: 0.0,
                 _pooled_variance: 0.0,
                 _standard_deviation_differences_between_means: 0.0,
+                _cohens_d: 0.0,
+                _normality: Vec::with_capacity(2),
+                _homogeneity: None,
                 _statistic_run: false,
                 t: 0.0,
+                p: 0.0,
+                apa: String::new(),
             };
 
             new_igt.run_statistic()?;
@@ -264,6 +368,19 @@ impl<'a> IndependentGroupsT<'a> {
 
         let level_1_continuous_data = &separated_continuous_data[0];
         let level_2_continuous_data = &separated_continuous_data[1];
+        if level_1_continuous_data.len() < 2 || level_2_continuous_data.len() < 2 {
+            return Err(anyhow!(
+                "Each level needs at least two values--cannot run 'Independent Groups t'"
+            ));
+        }
+        let level_values = |level: &Vec<&f64>| level.iter().map(|x| **x).collect::<Vec<f64>>();
+        if has_no_spread(&level_values(level_1_continuous_data))
+            && has_no_spread(&level_values(level_2_continuous_data))
+        {
+            return Err(anyhow!(
+                "The values within each level have no spread--cannot run 'Independent Groups t'"
+            ));
+        }
 
         self._variance_level_1 =
             variance(level_1_continuous_data, self._continuous_data.population)?;
@@ -282,50 +399,194 @@ impl<'a> IndependentGroupsT<'a> {
                 + (self._pooled_variance / self._level_row_indices[1].len() as f64),
         );
 
-        self.t = (mean(&level_1_continuous_data)? - mean(&level_2_continuous_data)?)
-            / self._standard_deviation_differences_between_means;
+        let difference_between_means =
+            mean(level_1_continuous_data)? - mean(level_2_continuous_data)?;
+        self.t = difference_between_means / self._standard_deviation_differences_between_means;
+        self.p = two_tailed_p_from_t(self.t, self._df as f64)?;
+
+        // d = (x_bar_1 - x_bar_2) / s_pooled
+        self._cohens_d = difference_between_means / f64::sqrt(self._pooled_variance);
+        self.apa = format!(
+            "t({}) = {}, {}, d = {}",
+            self._df,
+            format_statistic(self.t),
+            format_p(self.p),
+            format_statistic(self._cohens_d)
+        );
+
+        self._normality = level_normality(
+            self._categorical_data,
+            self._continuous_data,
+            &separated_continuous_data,
+        )?;
+        self._homogeneity = Some(HomogeneityOfVariance::new(
+            format!(
+                "'{}' across the levels of '{}'",
+                self._continuous_data.name, self._categorical_data.name
+            ),
+            self._categorical_data,
+            self._continuous_data,
+        )?);
 
         self._statistic_run = true;
 
         Ok(())
     }
 
-    pub fn print(mut self) {
+    pub fn print(mut self) -> anyhow::Result<(), Error> {
         if self._statistic_run {
-            info!("{}", logging::format_title(&*self.name));
-            info!("Description: '{}'", self.description);
-            info!("Level 1: '{}'", self._categorical_data.data_array.data[0].1);
-            info!("Level 2: '{}'", self._categorical_data.data_array.data[1].1);
-            info!("Variance Level 1: {}", self._variance_level_1);
-            info!("Variance Level 2: {}", self._variance_level_2);
-            info!("Pooled variance: {}", self._pooled_variance);
-            info!(
+            print_assumptions_table(&self._normality, self._homogeneity.as_ref());
+            report!("{}", logging::format_title(&*self.name));
+            report!("Description: '{}'", self.description);
+            for (index, level_name) in self._categorical_data.levels.keys().enumerate() {
+                report!("Level {}: '{}'", index + 1, level_name);
+            }
+            report!("Variance Level 1: {}", self._variance_level_1);
+            report!("Variance Level 2: {}", self._variance_level_2);
+            report!("Pooled variance: {}", self._pooled_variance);
+            report!(
                 "Standard Deviation: {}",
                 self._standard_deviation_differences_between_means
             );
-            info!("Independent Groups t: {}", self.t);
+            report!("Independent Groups t: {}", self.t);
+            report!("APA: {}", self.apa);
+            if let Some(homogeneity) = &self._homogeneity {
+                homogeneity.print();
+            }
+            self._normality
+                .iter()
+                .for_each(|check| check.warn_if_questionable());
+            Ok(())
         } else {
-            self.run_statistic().expect("Error running statistic");
-            self.print();
+            self.run_statistic()?;
+            self.print()
         }
     }
 }
 
+/// Normality of the continuous data within each level, in the order the levels were separated.
+fn level_normality(
+    categorical_data: &CategoricalDataArray,
+    continuous_data: &ContinuousDataArray,
+    separated_continuous_data: &[Vec<&f64>],
+) -> anyhow::Result<Vec<Normality>, Error> {
+    categorical_data
+        .levels
+        .keys()
+        .zip(separated_continuous_data.iter())
+        .map(|(level_name, level_data)| {
+            Normality::new(
+                format!(
+                    "{} ({} = {})",
+                    continuous_data.name, categorical_data.name, level_name
+                ),
+                &level_data.iter().map(|x| **x).collect::<Vec<f64>>(),
+            )
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone)]
 pub struct ZTest<'a> {
     pub name: String,
     pub description: String,
-    pub n: usize,
-    pub df: usize,
+    _n: usize,
 
-    pub data: &'a ContinuousDataArray,
+    _data: &'a ContinuousDataArray,
 
     // provided
-    pub mu: f64,
-    pub standard_deviation: f64,
+    _mu: f64,
+    _standard_deviation: f64, // known population sigma
 
     // calculated
+    _standard_error: f64,
+    _cohens_d: f64,
+
+    _statistic_run: bool,
     pub z: f64,
+    pub p: f64,
+    pub apa: String,
+}
+
+impl<'a> ZTest<'a> {
+    pub fn new(
+        name: String,
+        description: String,
+        data: &'a ContinuousDataArray,
+        mu: f64,
+        standard_deviation: f64,
+    ) -> anyhow::Result<ZTest<'a>, Error> {
+        if standard_deviation <= 0.0 {
+            return Err(anyhow!(
+                "A z test requires a positive population standard deviation"
+            ));
+        }
+        if data.n == 0 {
+            return Err(anyhow!("A z test requires at least one observation"));
+        }
+
+        let mut new_z_test = ZTest {
+            name,
+            description,
+            _n: data.n,
+            _data: data,
+            _mu: mu,
+            _standard_deviation: standard_deviation,
+            _standard_error: 0.0,
+            _cohens_d: 0.0,
+            _statistic_run: false,
+            z: 0.0,
+            p: 0.0,
+            apa: String::new(),
+        };
+
+        new_z_test.run_statistic()?;
+
+        Ok(new_z_test)
+    }
+
+    fn run_statistic(&mut self) -> anyhow::Result<(), Error> {
+        report!("...Calculating 'Z Test'...");
+        self._n = self._data.n;
+
+        // SE = sigma / sqrt(n)
+        self._standard_error = self._standard_deviation / f64::sqrt(self._n as f64);
+
+        // z = (x_bar - mu) / SE
+        self.z = (self._data.mean - self._mu) / self._standard_error;
+        self.p = two_tailed_p_from_z(self.z)?;
+
+        // d = (x_bar - mu) / sigma
+        self._cohens_d = (self._data.mean - self._mu) / self._standard_deviation;
+        self.apa = format!(
+            "z = {}, {}, d = {}",
+            format_statistic(self.z),
+            format_p(self.p),
+            format_statistic(self._cohens_d)
+        );
+
+        self._statistic_run = true;
+        Ok(())
+    }
+
+    pub fn print(mut self) -> anyhow::Result<(), Error> {
+        if self._statistic_run {
+            report!("{}", logging::format_title(&self.name));
+            report!("Description: '{}'", self.description);
+            report!("n: {}", self._n);
+            report!("Sample Mean: {}", self._data.mean);
+            report!("mu: {}", self._mu);
+            report!("sigma: {}", self._standard_deviation);
+            report!("Standard Error: {}", self._standard_error);
+            report!("z: {}", self.z);
+            report!("p (two-tailed): {}", self.p);
+            report!("APA: {}", self.apa);
+            Ok(())
+        } else {
+            self.run_statistic()?;
+            self.print()
+        }
+    }
 }
 
 pub struct ANOVA<'a> {
@@ -348,9 +609,14 @@ pub struct ANOVA<'a> {
 
     _mean_square_between_groups: f64,
     _mean_square_within_groups: f64,
+    _eta_squared: f64,
+    _normality: Vec<Normality>,
+    _homogeneity: Option<HomogeneityOfVariance>,
 
     _statistic_run: bool,
     pub f: f64,
+    pub p: f64,
+    pub apa: String,
 }
 
 impl<'a> ANOVA<'a> {
@@ -377,8 +643,13 @@ impl<'a> ANOVA<'a> {
                 _sum_of_squares_within_groups: 0.0,
                 _mean_square_between_groups: 0.0,
                 _mean_square_within_groups: 0.0,
+                _eta_squared: 0.0,
+                _normality: Vec::with_capacity(categorical_data.levels.len()),
+                _homogeneity: None,
                 _statistic_run: false,
                 f: 0.0,
+                p: 0.0,
+                apa: String::new(),
             };
 
             new_anova.run_statistic()?;
@@ -445,32 +716,745 @@ impl<'a> ANOVA<'a> {
         self._mean_square_within_groups = self._sum_of_squares_within_groups / self._df_w as f64;
 
         self.f = self._mean_square_between_groups / self._mean_square_within_groups;
+        self.p = f_p(self.f, self._df_b as f64, self._df_w as f64)?;
+
+        // eta^2 = SSB / (SSB + SSW)
+        self._eta_squared = self._sum_of_squares_between_groups
+            / (self._sum_of_squares_between_groups + self._sum_of_squares_within_groups);
+        self.apa = format!(
+            "F({}, {}) = {}, {}, η² = {}",
+            self._df_b,
+            self._df_w,
+            format_statistic(self.f),
+            format_p(self.p),
+            format_bounded(self._eta_squared)
+        );
+
+        self._normality = level_normality(
+            self._categorical_data,
+            self._continuous_data,
+            &separated_continuous_data,
+        )?;
+        self._homogeneity = Some(HomogeneityOfVariance::new(
+            format!(
+                "'{}' across the levels of '{}'",
+                self._continuous_data.name, self._categorical_data.name
+            ),
+            self._categorical_data,
+            self._continuous_data,
+        )?);
 
         self._statistic_run = true;
 
         Ok(())
     }
 
-    pub fn print(mut self) {
+    pub fn print(mut self) -> anyhow::Result<(), Error> {
         if self._statistic_run {
-            info!("{}", logging::format_title(&*self.name));
-            info!("Description: '{}'", self.description);
+            print_assumptions_table(&self._normality, self._homogeneity.as_ref());
+            report!("{}", logging::format_title(&*self.name));
+            report!("Description: '{}'", self.description);
             for (index, (level_name, _)) in self._categorical_data.levels.iter().enumerate() {
-                info!("Level {}: {}", index, level_name);
-                info!("..n: {}", self._level_row_indices[index].len());
-                info!("..mean: {}", self._level_means[index]);
+                report!("Level {}: {}", index, level_name);
+                report!("..n: {}", self._level_row_indices[index].len());
+                report!("..mean: {}", self._level_means[index]);
+            }
+            report!("Grand Mean: {}", self._grand_mean);
+            report!("dfB: {}", self._df_b);
+            report!("dfW: {}", self._df_w);
+            report!("SSB: {}", self._sum_of_squares_between_groups);
+            report!("SSW: {}", self._sum_of_squares_within_groups);
+            report!("MSB: {}", self._mean_square_between_groups);
+            report!("MSW: {}", self._mean_square_within_groups);
+            report!("F: {}", self.f);
+            report!("APA: {}", self.apa);
+            if let Some(homogeneity) = &self._homogeneity {
+                homogeneity.print();
+            }
+            self._normality
+                .iter()
+                .for_each(|check| check.warn_if_questionable());
+            Ok(())
+        } else {
+            self.run_statistic()?;
+            self.print()
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MannWhitneyU<'a> {
+    pub name: String,
+    pub description: String,
+    _level_names: Vec<&'a String>,
+    _n_1: usize,
+    _n_2: usize,
+
+    // provided
+    _categorical_data: &'a CategoricalDataArray<'a>,
+    _continuous_data: &'a ContinuousDataArray,
+
+    // calculated
+    _rank_sum_1: f64,
+    _rank_sum_2: f64,
+    _u_1: f64,
+    _u_2: f64,
+    _mean_u: f64,
+    _standard_deviation_u: f64,
+    _z: f64,
+
+    _statistic_run: bool,
+    pub u: f64,
+    pub p: f64,
+    pub apa: String,
+}
+
+impl<'a> MannWhitneyU<'a> {
+    pub fn new(
+        name: String,
+        description: String,
+        categorical_data: &'a CategoricalDataArray,
+        continuous_data: &'a ContinuousDataArray,
+    ) -> anyhow::Result<MannWhitneyU<'a>, Error> {
+        if categorical_data.levels.keys().len() == 2 {
+            let mut new_mwu = MannWhitneyU {
+                name,
+                description,
+                _level_names: Vec::with_capacity(2),
+                _n_1: 0,
+                _n_2: 0,
+                _categorical_data: categorical_data,
+                _continuous_data: continuous_data,
+                _rank_sum_1: 0.0,
+                _rank_sum_2: 0.0,
+                _u_1: 0.0,
+                _u_2: 0.0,
+                _mean_u: 0.0,
+                _standard_deviation_u: 0.0,
+                _z: 0.0,
+                _statistic_run: false,
+                u: 0.0,
+                p: 0.0,
+                apa: String::new(),
+            };
+
+            new_mwu.run_statistic()?;
+
+            Ok(new_mwu)
+        } else {
+            Err(anyhow!(
+                "A categorical variable with two levels is required to run a Mann-Whitney U test"
+            ))
+        }
+    }
+
+    fn run_statistic(&mut self) -> anyhow::Result<(), Error> {
+        report!("...Calculating 'Mann-Whitney U'...");
+
+        // sort level names so that "level 1" is stable between runs
+        self._level_names = self._categorical_data.levels.keys().copied().collect();
+        self._level_names.sort();
+
+        let level_1_data = self
+            ._categorical_data
+            .get_level_data(self._level_names[0], self._continuous_data)?;
+        let level_2_data = self
+            ._categorical_data
+            .get_level_data(self._level_names[1], self._continuous_data)?;
+
+        self._n_1 = level_1_data.len();
+        self._n_2 = level_2_data.len();
+        let n_1 = self._n_1 as f64;
+        let n_2 = self._n_2 as f64;
+        let n = n_1 + n_2;
+
+        // rank both groups together, the first n_1 ranks belong to level 1
+        let combined = level_1_data
+            .iter()
+            .chain(level_2_data.iter())
+            .map(|x| **x)
+            .collect::<Vec<f64>>();
+        let combined_ranks = ranks(&combined)?;
+
+        // folded from 0.0: an empty f64 sum is -0.0, which would print as -0
+        self._rank_sum_1 = combined_ranks[..self._n_1]
+            .iter()
+            .fold(0.0, |sum, rank| sum + rank);
+        self._rank_sum_2 = combined_ranks[self._n_1..]
+            .iter()
+            .fold(0.0, |sum, rank| sum + rank);
+
+        // U_i = R_i - n_i(n_i + 1) / 2
+        self._u_1 = self._rank_sum_1 - n_1 * (n_1 + 1.0) / 2.0;
+        self._u_2 = self._rank_sum_2 - n_2 * (n_2 + 1.0) / 2.0;
+        self.u = f64::min(self._u_1, self._u_2);
+
+        // normal approximation, with ties: sd = sqrt((n_1 n_2 / 12) * ((n + 1) - sum(t^3 - t) / (n(n - 1))))
+        self._mean_u = n_1 * n_2 / 2.0;
+        self._standard_deviation_u = f64::sqrt(
+            (n_1 * n_2 / 12.0) * ((n + 1.0) - tie_correction(&combined)? / (n * (n - 1.0))),
+        );
+        // every value tied leaves U no spread, and z undefined
+        if self._standard_deviation_u.is_nan() || self._standard_deviation_u < 1e-12 {
+            return Err(anyhow!("All values are tied--cannot run 'Mann-Whitney U'"));
+        }
+
+        // continuity correction of 0.5 toward the mean
+        self._z = f64::min(0.0, self.u - self._mean_u + 0.5) / self._standard_deviation_u;
+        self.p = two_tailed_p_from_z(self._z)?;
+
+        // r = |z| / sqrt(N)
+        self.apa = format!(
+            "U = {}, z = {}, {}, r = {}",
+            format_statistic(self.u),
+            format_statistic(self._z),
+            format_p(self.p),
+            format_bounded(self._z.abs() / f64::sqrt(n))
+        );
+
+        self._statistic_run = true;
+
+        Ok(())
+    }
+
+    pub fn print(mut self) -> anyhow::Result<(), Error> {
+        if self._statistic_run {
+            report!("{}", logging::format_title(&self.name));
+            report!("Description: '{}'", self.description);
+            report!("Level 1: '{}'", self._level_names[0]);
+            report!("..n: {}", self._n_1);
+            report!("..rank sum: {}", self._rank_sum_1);
+            report!("..U: {}", self._u_1);
+            report!("Level 2: '{}'", self._level_names[1]);
+            report!("..n: {}", self._n_2);
+            report!("..rank sum: {}", self._rank_sum_2);
+            report!("..U: {}", self._u_2);
+            report!("Mean of U: {}", self._mean_u);
+            report!("Standard Deviation of U: {}", self._standard_deviation_u);
+            report!("z: {}", self._z);
+            report!("Mann-Whitney U: {}", self.u);
+            report!("p (two-tailed, normal approximation): {}", self.p);
+            report!("APA: {}", self.apa);
+            Ok(())
+        } else {
+            self.run_statistic()?;
+            self.print()
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WilcoxonSignedRank<'a> {
+    pub name: String,
+    pub description: String,
+    _n: usize,
+    _n_zero_differences: usize,
+
+    // provided
+    _data_x: &'a ContinuousDataArray,
+    _data_y: &'a ContinuousDataArray,
+
+    // calculated
+    _differences: Vec<f64>,
+    _positive_rank_sum: f64,
+    _negative_rank_sum: f64,
+    _mean_w: f64,
+    _standard_deviation_w: f64,
+    _z: f64,
+
+    _statistic_run: bool,
+    pub w: f64,
+    pub p: f64,
+    pub apa: String,
+}
+
+impl<'a> WilcoxonSignedRank<'a> {
+    pub fn new(
+        name: String,
+        description: String,
+        data_x: &'a ContinuousDataArray,
+        data_y: &'a ContinuousDataArray,
+    ) -> anyhow::Result<WilcoxonSignedRank<'a>, Error> {
+        if data_x.data_array.data.len() == data_y.data_array.data.len() {
+            let mut new_wsr = WilcoxonSignedRank {
+                name,
+                description,
+                _n: 0,
+                _n_zero_differences: 0,
+                _data_x: data_x,
+                _data_y: data_y,
+                _differences: vec![],
+                _positive_rank_sum: 0.0,
+                _negative_rank_sum: 0.0,
+                _mean_w: 0.0,
+                _standard_deviation_w: 0.0,
+                _z: 0.0,
+                _statistic_run: false,
+                w: 0.0,
+                p: 0.0,
+                apa: String::new(),
+            };
+
+            new_wsr.run_statistic()?;
+
+            Ok(new_wsr)
+        } else {
+            Err(anyhow!("provided data are not of same length"))
+        }
+    }
+
+    fn run_statistic(&mut self) -> anyhow::Result<(), Error> {
+        report!("...Calculating 'Wilcoxon Signed-Rank'...");
+
+        let data_x = &self
+            ._data_x
+            .data_array
+            .data
+            .iter()
+            .map(|x| x.1)
+            .collect::<Vec<f64>>();
+        let data_y = &self
+            ._data_y
+            .data_array
+            .data
+            .iter()
+            .map(|y| y.1)
+            .collect::<Vec<f64>>();
+
+        // zero differences carry no sign and are dropped (Wilcoxon's method)
+        let all_differences = differences(data_x, data_y)?;
+        self._differences = all_differences
+            .iter()
+            .copied()
+            .filter(|d| *d != 0.0)
+            .collect();
+        self._n_zero_differences = all_differences.len() - self._differences.len();
+        self._n = self._differences.len();
+
+        if self._n == 0 {
+            return Err(anyhow!(
+                "All paired differences are zero--cannot run 'Wilcoxon Signed-Rank'"
+            ));
+        }
+
+        let absolute_differences = self
+            ._differences
+            .iter()
+            .map(|d| d.abs())
+            .collect::<Vec<f64>>();
+        let absolute_ranks = ranks(&absolute_differences)?;
+
+        self._positive_rank_sum = self
+            ._differences
+            .iter()
+            .zip(absolute_ranks.iter())
+            .filter(|(d, _)| **d > 0.0)
+            .fold(0.0, |sum, (_, rank)| sum + rank);
+        self._negative_rank_sum = self
+            ._differences
+            .iter()
+            .zip(absolute_ranks.iter())
+            .filter(|(d, _)| **d < 0.0)
+            .fold(0.0, |sum, (_, rank)| sum + rank);
+        self.w = f64::min(self._positive_rank_sum, self._negative_rank_sum);
+
+        // normal approximation, with ties: var = n(n + 1)(2n + 1) / 24 - sum(t^3 - t) / 48
+        let n = self._n as f64;
+        self._mean_w = n * (n + 1.0) / 4.0;
+        self._standard_deviation_w = f64::sqrt(
+            n * (n + 1.0) * (2.0 * n + 1.0) / 24.0 - tie_correction(&absolute_differences)? / 48.0,
+        );
+        if self._standard_deviation_w.is_nan() || self._standard_deviation_w < 1e-12 {
+            return Err(anyhow!(
+                "The paired differences have no spread--cannot run 'Wilcoxon Signed-Rank'"
+            ));
+        }
+
+        // continuity correction of 0.5 toward the mean
+        self._z = f64::min(0.0, self.w - self._mean_w + 0.5) / self._standard_deviation_w;
+        self.p = two_tailed_p_from_z(self._z)?;
+
+        // r = |z| / sqrt(n)
+        self.apa = format!(
+            "W = {}, z = {}, {}, r = {}",
+            format_statistic(self.w),
+            format_statistic(self._z),
+            format_p(self.p),
+            format_bounded(self._z.abs() / f64::sqrt(n))
+        );
+
+        self._statistic_run = true;
+
+        Ok(())
+    }
+
+    pub fn print(mut self) -> anyhow::Result<(), Error> {
+        if self._statistic_run {
+            report!("{}", logging::format_title(&self.name));
+            report!("Description: '{}'", self.description);
+            report!("n (non-zero differences): {}", self._n);
+            report!("Zero differences dropped: {}", self._n_zero_differences);
+            report!("W+: {}", self._positive_rank_sum);
+            report!("W-: {}", self._negative_rank_sum);
+            report!("Mean of W: {}", self._mean_w);
+            report!("Standard Deviation of W: {}", self._standard_deviation_w);
+            report!("z: {}", self._z);
+            report!("Wilcoxon W: {}", self.w);
+            report!("p (two-tailed, normal approximation): {}", self.p);
+            report!("APA: {}", self.apa);
+            Ok(())
+        } else {
+            self.run_statistic()?;
+            self.print()
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct KruskalWallis<'a> {
+    pub name: String,
+    pub description: String,
+    _level_names: Vec<&'a String>,
+    _df: usize,
+
+    // provided
+    _categorical_data: &'a CategoricalDataArray<'a>,
+    _continuous_data: &'a ContinuousDataArray,
+
+    // calculated
+    _level_n: Vec<usize>,
+    _level_rank_sums: Vec<f64>,
+    _level_mean_ranks: Vec<f64>,
+    _uncorrected_h: f64,
+    _tie_correction_factor: f64,
+
+    _statistic_run: bool,
+    pub h: f64,
+    pub p: f64,
+    pub apa: String,
+}
+
+impl<'a> KruskalWallis<'a> {
+    pub fn new(
+        name: String,
+        description: String,
+        categorical_data: &'a CategoricalDataArray,
+        continuous_data: &'a ContinuousDataArray,
+    ) -> anyhow::Result<KruskalWallis<'a>, Error> {
+        if categorical_data.levels.len() >= 2 {
+            let mut new_kw = KruskalWallis {
+                name,
+                description,
+                _level_names: Vec::with_capacity(categorical_data.levels.len()),
+                _df: categorical_data.levels.len() - 1,
+                _categorical_data: categorical_data,
+                _continuous_data: continuous_data,
+                _level_n: Vec::with_capacity(categorical_data.levels.len()),
+                _level_rank_sums: Vec::with_capacity(categorical_data.levels.len()),
+                _level_mean_ranks: Vec::with_capacity(categorical_data.levels.len()),
+                _uncorrected_h: 0.0,
+                _tie_correction_factor: 1.0,
+                _statistic_run: false,
+                h: 0.0,
+                p: 0.0,
+                apa: String::new(),
+            };
+
+            new_kw.run_statistic()?;
+
+            Ok(new_kw)
+        } else {
+            Err(anyhow!(
+                "Categorical data consisting of at least two levels is required for a Kruskal-Wallis test"
+            ))
+        }
+    }
+
+    fn run_statistic(&mut self) -> anyhow::Result<(), Error> {
+        report!("...Calculating 'Kruskal-Wallis H'...");
+
+        self._level_names = self._categorical_data.levels.keys().copied().collect();
+        self._level_names.sort();
+
+        let mut combined: Vec<f64> = Vec::with_capacity(self._continuous_data.n);
+        self._level_n.clear();
+        for level_name in self._level_names.iter() {
+            let level_data = self
+                ._categorical_data
+                .get_level_data(level_name, self._continuous_data)?;
+            self._level_n.push(level_data.len());
+            combined.extend(level_data.into_iter().copied());
+        }
+
+        // rank all observations together, then sum ranks within each level
+        let combined_ranks = ranks(&combined)?;
+        self._level_rank_sums.clear();
+        self._level_mean_ranks.clear();
+        let mut offset = 0;
+        for level_n in self._level_n.iter() {
+            let rank_sum = combined_ranks[offset..offset + level_n].iter().sum::<f64>();
+            self._level_rank_sums.push(rank_sum);
+            self._level_mean_ranks.push(rank_sum / *level_n as f64);
+            offset += level_n;
+        }
+
+        // H = (12 / (N(N + 1))) * sum(R_i^2 / n_i) - 3(N + 1)
+        let n = combined.len() as f64;
+        self._uncorrected_h = 12.0 / (n * (n + 1.0))
+            * self
+                ._level_rank_sums
+                .iter()
+                .zip(self._level_n.iter())
+                .map(|(rank_sum, level_n)| f64::powi(*rank_sum, 2) / *level_n as f64)
+                .sum::<f64>()
+            - 3.0 * (n + 1.0);
+
+        // ties: H / (1 - sum(t^3 - t) / (N^3 - N))
+        self._tie_correction_factor = 1.0 - tie_correction(&combined)? / (f64::powi(n, 3) - n);
+        // every value tied leaves no ranks to compare, and H undefined
+        if self._tie_correction_factor < 1e-12 {
+            return Err(anyhow!(
+                "All values are tied--cannot run 'Kruskal-Wallis H'"
+            ));
+        }
+        self.h = self._uncorrected_h / self._tie_correction_factor;
+
+        self.p = chi_square_p(self.h, self._df as f64)?;
+
+        // epsilon^2 = H / (N - 1)
+        self.apa = format!(
+            "H({}) = {}, {}, ε² = {}",
+            self._df,
+            format_statistic(self.h),
+            format_p(self.p),
+            format_bounded(self.h / (n - 1.0))
+        );
+
+        self._statistic_run = true;
+
+        Ok(())
+    }
+
+    pub fn print(mut self) -> anyhow::Result<(), Error> {
+        if self._statistic_run {
+            report!("{}", logging::format_title(&self.name));
+            report!("Description: '{}'", self.description);
+            for (index, level_name) in self._level_names.iter().enumerate() {
+                report!("Level {}: {}", index, level_name);
+                report!("..n: {}", self._level_n[index]);
+                report!("..rank sum: {}", self._level_rank_sums[index]);
+                report!("..mean rank: {}", self._level_mean_ranks[index]);
+            }
+            report!("df: {}", self._df);
+            report!("H (uncorrected): {}", self._uncorrected_h);
+            report!("Tie correction: {}", self._tie_correction_factor);
+            report!("Kruskal-Wallis H: {}", self.h);
+            report!("p (chi-square approximation): {}", self.p);
+            report!("APA: {}", self.apa);
+            Ok(())
+        } else {
+            self.run_statistic()?;
+            self.print()
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Correlation<'a> {
+    pub name: String,
+    pub description: String,
+    _n: usize,
+    _df: usize,
+
+    // provided
+    _data_x: &'a ContinuousDataArray,
+    _data_y: &'a ContinuousDataArray,
+
+    // calculated
+    _pearson_t: f64,
+    _spearman_t: f64,
+    _kendall_z: f64,
+    _cluster_robust: Option<ClusterRobust>, // of the standardized slope, which is r
+
+    _statistic_run: bool,
+    pub pearson_r: f64,
+    pub pearson_p: f64,
+    pub spearman_rho: f64,
+    pub spearman_p: f64,
+    pub kendall_tau: f64,
+    pub kendall_p: f64,
+    pub apa: String,
+}
+
+impl<'a> Correlation<'a> {
+    pub fn new(
+        name: String,
+        description: String,
+        data_x: &'a ContinuousDataArray,
+        data_y: &'a ContinuousDataArray,
+    ) -> anyhow::Result<Correlation<'a>, Error> {
+        if data_x.data_array.data.len() == data_y.data_array.data.len() {
+            let mut new_correlation = Correlation {
+                name,
+                description,
+                _n: data_x.data_array.data.len(),
+                _df: 0,
+                _data_x: data_x,
+                _data_y: data_y,
+                _pearson_t: 0.0,
+                _spearman_t: 0.0,
+                _kendall_z: 0.0,
+                _cluster_robust: None,
+                _statistic_run: false,
+                pearson_r: 0.0,
+                pearson_p: 0.0,
+                spearman_rho: 0.0,
+                spearman_p: 0.0,
+                kendall_tau: 0.0,
+                kendall_p: 0.0,
+                apa: String::new(),
+            };
+
+            new_correlation.run_statistic()?;
+
+            Ok(new_correlation)
+        } else {
+            Err(anyhow!("provided data are not of same length"))
+        }
+    }
+
+    fn run_statistic(&mut self) -> anyhow::Result<(), Error> {
+        report!("...Calculating 'Correlation'...");
+
+        self._n = self._data_x.data_array.data.len();
+        if self._n < 3 {
+            return Err(anyhow!(
+                "At least three pairs of observations are required to test a correlation"
+            ));
+        }
+        self._df = self._n - 2;
+
+        let data_x = &self
+            ._data_x
+            .data_array
+            .data
+            .iter()
+            .map(|x| x.1)
+            .collect::<Vec<f64>>();
+        let data_y = &self
+            ._data_y
+            .data_array
+            .data
+            .iter()
+            .map(|y| y.1)
+            .collect::<Vec<f64>>();
+
+        // t = r * sqrt(N - 2) / sqrt(1 - r^2), for both Pearson r and Spearman rho
+        self.pearson_r = pearson_r_method_1(data_x, data_y, self._data_x.population)?;
+        self._pearson_t = t_statistic_from_r(self.pearson_r, self._n)?;
+        self.pearson_p = two_tailed_p_from_t(self._pearson_t, self._df as f64)?;
+
+        self.spearman_rho = spearman_rho(data_x, data_y)?;
+        self._spearman_t = t_statistic_from_r(self.spearman_rho, self._n)?;
+        self.spearman_p = two_tailed_p_from_t(self._spearman_t, self._df as f64)?;
+
+        (self.kendall_tau, self._kendall_z) = kendall_tau_b(data_x, data_y)?;
+        self.kendall_p = two_tailed_p_from_z(self._kendall_z)?;
+
+        self.apa = format!(
+            "r({df}) = {}, {}; rs({df}) = {}, {}; τb = {}, {}",
+            format_bounded(self.pearson_r),
+            format_p(self.pearson_p),
+            format_bounded(self.spearman_rho),
+            format_p(self.spearman_p),
+            format_bounded(self.kendall_tau),
+            format_p(self.kendall_p),
+            df = self._df
+        );
+
+        self._statistic_run = true;
+
+        Ok(())
+    }
+
+    /// Adds a test of Pearson r whose standard error is robust to correlated observations
+    /// within each level of `clusters` (a subject ID, say), read from the same rows as the
+    /// data: the cluster-robust test of the slope of z(y) on z(x), which is r.
+    pub fn with_clusters(
+        mut self,
+        clusters: &CategoricalDataArray,
+    ) -> anyhow::Result<Correlation<'a>, Error> {
+        let design: Matrix = self
+            ._data_x
+            .z_scores
+            .iter()
+            .map(|z| vec![1.0, *z])
+            .collect();
+        let z_y = &self._data_y.z_scores;
+        let design_transposed = transpose(&design);
+        let xtx_inverse = invert(&multiply(&design_transposed, &design)?)?;
+        let coefficients =
+            multiply_vector(&xtx_inverse, &multiply_vector(&design_transposed, z_y)?)?;
+        let residuals = multiply_vector(&design, &coefficients)?
+            .iter()
+            .zip(z_y.iter())
+            .map(|(fitted, z)| z - fitted)
+            .collect::<Vec<f64>>();
+        let observation_clusters = self
+            ._data_x
+            .data_array
+            .data
+            .iter()
+            .map(|(row, _)| clusters.data_array.data[*row].1)
+            .collect::<Vec<&String>>();
+        self._cluster_robust = Some(ClusterRobust::new(
+            clusters.name.clone(),
+            &design,
+            &xtx_inverse,
+            &coefficients,
+            &residuals,
+            &observation_clusters,
+        )?);
+        Ok(self)
+    }
+
+    pub fn print(mut self) -> anyhow::Result<(), Error> {
+        if self._statistic_run {
+            report!("{}", logging::format_title(&self.name));
+            report!("Description: '{}'", self.description);
+            report!("n: {}", self._n);
+            report!("df: {}", self._df);
+            report!("Pearson r: {}", self.pearson_r);
+            report!("..t: {}", self._pearson_t);
+            report!("..p (two-tailed): {}", self.pearson_p);
+            report!("Spearman rho: {}", self.spearman_rho);
+            report!("..t: {}", self._spearman_t);
+            report!("..p (two-tailed): {}", self.spearman_p);
+            report!("Kendall tau-b: {}", self.kendall_tau);
+            report!("..z: {}", self._kendall_z);
+            report!("..p (two-tailed, normal approximation): {}", self.kendall_p);
+            report!("APA: {}", self.apa);
+            if let Some(cluster_robust) = &self._cluster_robust {
+                report!(
+                    "Pearson r, clustered by '{}' ({} clusters)",
+                    cluster_robust.cluster_name,
+                    cluster_robust.clusters
+                );
+                report!("..SE: {}", cluster_robust.standard_errors[1]);
+                report!("..t: {}", cluster_robust.t_values[1]);
+                report!("..p (two-tailed): {}", cluster_robust.p_values[1]);
+                report!(
+                    "..APA: r = {}, t({}) = {}, {}",
+                    format_bounded(self.pearson_r),
+                    cluster_robust.df,
+                    format_statistic(cluster_robust.t_values[1]),
+                    format_p(cluster_robust.p_values[1])
+                );
             }
-            info!("Grand Mean: {}", self._grand_mean);
-            info!("dfB: {}", self._df_b);
-            info!("dfW: {}", self._df_w);
-            info!("SSB: {}", self._sum_of_squares_between_groups);
-            info!("SSW: {}", self._sum_of_squares_within_groups);
-            info!("MSB: {}", self._mean_square_between_groups);
-            info!("MSW: {}", self._mean_square_within_groups);
-            info!("F: {}", self.f);
+            Ok(())
         } else {
-            self.run_statistic().expect("Error running statistic");
-            self.print();
+            self.run_statistic()?;
+            self.print()
         }
     }
 }
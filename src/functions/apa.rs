@@ -0,0 +1,25 @@
+//! Pieces of APA-style result strings, e.g. "t(24) = 2.31, p = .030, d = 0.46".
+
+/// A test statistic or an unbounded effect size, to two decimals.
+pub fn format_statistic(value: f64) -> String {
+    // keep a rounded-away sign off zero
+    let rounded = (value * 100.0).round() / 100.0;
+    format!("{:.2}", if rounded == 0.0 { 0.0 } else { value })
+}
+
+/// A value that can't exceed 1 in magnitude (r, R^2, eta^2), to two decimals without
+/// the leading zero.
+pub fn format_bounded(value: f64) -> String {
+    format_statistic(value).replacen("0.", ".", 1)
+}
+
+/// "p = .030", or "p < .001" / "p > .999" past three decimals.
+pub fn format_p(p: f64) -> String {
+    if p < 0.001 {
+        String::from("p < .001")
+    } else if p > 0.999 {
+        String::from("p > .999")
+    } else {
+        format!("p = {}", format!("{:.3}", p).replacen("0.", ".", 1))
+    }
+}
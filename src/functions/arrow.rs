@@ -0,0 +1,130 @@
+use crate::functions::csv::{is_missing, CSVData};
+use anyhow::{anyhow, Error, Result};
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float32Type, Float64Type};
+use arrow_array::{Array, ArrayRef, Float64Array, Int64Array, RecordBatch, StringArray};
+use arrow_cast::display::array_value_to_string;
+use arrow_ipc::reader::FileReader;
+use arrow_schema::{DataType, Field, Schema};
+use std::fs::File;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// File extensions read as Arrow IPC (Feather v2) instead of CSV.
+pub const ARROW_EXTENSIONS: [&str; 3] = ["arrow", "feather", "ipc"];
+
+pub fn is_arrow_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| ARROW_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+impl CSVData {
+    /// Converts to a RecordBatch, typing each column as Int64 or Float64 when every
+    /// present cell parses as one, and as Utf8 otherwise. Missing cells become nulls.
+    #[allow(dead_code)] // for library callers handing data to Polars; the CLI only reads Arrow
+    pub fn to_record_batch(&self) -> Result<RecordBatch, Error> {
+        let mut fields = Vec::with_capacity(self.row_length);
+        let mut columns: Vec<ArrayRef> = Vec::with_capacity(self.row_length);
+
+        for column in 0..self.row_length {
+            let cells = (0..self.column_count)
+                .map(|row| self.data[self.row_length * row + column].as_str())
+                .collect::<Vec<&str>>();
+            let name = self
+                .headers
+                .get(column)
+                .cloned()
+                .unwrap_or_else(|| format!("Column {}", column));
+
+            if let Some(integers) = parse_cells::<i64>(&cells) {
+                fields.push(Field::new(name, DataType::Int64, true));
+                columns.push(Arc::new(Int64Array::from(integers)));
+            } else if let Some(numbers) = parse_cells::<f64>(&cells) {
+                fields.push(Field::new(name, DataType::Float64, true));
+                columns.push(Arc::new(Float64Array::from(numbers)));
+            } else {
+                fields.push(Field::new(name, DataType::Utf8, true));
+                columns.push(Arc::new(StringArray::from(
+                    cells
+                        .iter()
+                        .map(|cell| (!is_missing(cell)).then_some(*cell))
+                        .collect::<Vec<Option<&str>>>(),
+                )));
+            }
+        }
+
+        Ok(RecordBatch::try_new(
+            Arc::new(Schema::new(fields)),
+            columns,
+        )?)
+    }
+
+    /// Converts RecordBatches sharing one schema into CSVData, rendering every value as
+    /// text the way `import_csv_data` would have read it. Nulls become empty (missing) cells.
+    pub fn from_record_batches(batches: &[RecordBatch]) -> Result<CSVData, Error> {
+        let first = batches
+            .first()
+            .ok_or_else(|| anyhow!("At least one record batch is required"))?;
+        let schema = first.schema();
+
+        let mut csv_data = CSVData::default();
+        csv_data.headers = schema
+            .fields()
+            .iter()
+            .map(|field| field.name().clone())
+            .collect();
+        csv_data.row_length = schema.fields().len();
+
+        for batch in batches {
+            if batch.schema() != schema {
+                return Err(anyhow!("Record batches do not share the same schema"));
+            }
+            for row in 0..batch.num_rows() {
+                for column in batch.columns() {
+                    csv_data.data.push(if column.is_null(row) {
+                        String::new()
+                    } else {
+                        cell_to_string(column, row)?
+                    });
+                }
+                csv_data.column_count += 1;
+            }
+        }
+        csv_data.infer_column_types();
+        Ok(csv_data)
+    }
+}
+
+/// Parses every present cell as `T`, keeping missing cells as `None`; fails if any
+/// present cell does not parse.
+fn parse_cells<T: FromStr>(cells: &[&str]) -> Option<Vec<Option<T>>> {
+    cells
+        .iter()
+        .map(|cell| {
+            if is_missing(cell) {
+                Some(None)
+            } else {
+                cell.parse::<T>().ok().map(Some)
+            }
+        })
+        .collect()
+}
+
+/// Floats use Rust's formatting (so 2.0 reads back as "2", as it would from a CSV);
+/// everything else uses Arrow's display formatting.
+fn cell_to_string(column: &ArrayRef, row: usize) -> Result<String, Error> {
+    Ok(match column.data_type() {
+        DataType::Float64 => column.as_primitive::<Float64Type>().value(row).to_string(),
+        DataType::Float32 => column.as_primitive::<Float32Type>().value(row).to_string(),
+        _ => array_value_to_string(column, row)?.trim().to_string(),
+    })
+}
+
+/// Reads an Arrow IPC file (as written by Polars' `write_ipc` or pyarrow's Feather v2).
+pub fn import_arrow_data(file_path: &Path) -> Result<CSVData, Error> {
+    let reader = FileReader::try_new(File::open(file_path)?, None)?;
+    let batches = reader.collect::<Result<Vec<RecordBatch>, _>>()?;
+    CSVData::from_record_batches(&batches)
+}
@@ -0,0 +1,642 @@
+//! A minimal vector canvas that writes SVG and rasterizes to PNG without any image
+//! libraries, so charts can be exported as files for papers.
+
+use std::fmt::Write;
+
+/// Text is drawn in a 5x7 pixel font on a 6x10 cell, scaled by a whole number.
+const GLYPH_WIDTH: usize = 5;
+const GLYPH_HEIGHT: usize = 7;
+const CELL_WIDTH: usize = 6;
+const CELL_HEIGHT: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Color(pub u8, pub u8, pub u8);
+
+impl Color {
+    pub const WHITE: Color = Color(0xff, 0xff, 0xff);
+    pub const BLACK: Color = Color(0x33, 0x33, 0x33);
+    pub const GRID: Color = Color(0xe0, 0xe6, 0xf1);
+    pub const BLUE: Color = Color(0x54, 0x70, 0xc6);
+    pub const RED: Color = Color(0xee, 0x66, 0x66);
+
+    fn hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// Where a line of text sits relative to the point it is drawn at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    Middle,
+    End,
+}
+
+#[derive(Debug, Clone)]
+enum Shape {
+    Line {
+        from: (f64, f64),
+        to: (f64, f64),
+        color: Color,
+        width: f64,
+    },
+    Rect {
+        corner: (f64, f64),
+        size: (f64, f64),
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    },
+    Circle {
+        center: (f64, f64),
+        radius: f64,
+        fill: Color,
+    },
+    // `at` is the vertical middle of the text; vertical text reads bottom to top
+    Text {
+        at: (f64, f64),
+        text: String,
+        scale: usize,
+        anchor: Anchor,
+        color: Color,
+        vertical: bool,
+    },
+}
+
+/// Shapes on a white `width` x `height` pixel page, with y growing downward.
+#[derive(Debug, Clone)]
+pub struct Canvas {
+    width: usize,
+    height: usize,
+    shapes: Vec<Shape>,
+}
+
+impl Canvas {
+    pub fn new(width: usize, height: usize) -> Canvas {
+        Canvas {
+            width,
+            height,
+            shapes: vec![],
+        }
+    }
+
+    pub fn line(&mut self, from: (f64, f64), to: (f64, f64), color: Color, width: f64) {
+        self.shapes.push(Shape::Line {
+            from,
+            to,
+            color,
+            width,
+        });
+    }
+
+    pub fn rect(
+        &mut self,
+        corner: (f64, f64),
+        size: (f64, f64),
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) {
+        self.shapes.push(Shape::Rect {
+            corner,
+            size,
+            fill,
+            stroke,
+        });
+    }
+
+    pub fn circle(&mut self, center: (f64, f64), radius: f64, fill: Color) {
+        self.shapes.push(Shape::Circle {
+            center,
+            radius,
+            fill,
+        });
+    }
+
+    pub fn text(&mut self, at: (f64, f64), text: &str, scale: usize, anchor: Anchor) {
+        self.shapes.push(Shape::Text {
+            at,
+            text: text.to_string(),
+            scale,
+            anchor,
+            color: Color::BLACK,
+            vertical: false,
+        });
+    }
+
+    /// Text rotated a quarter turn counterclockwise, for a vertical axis name.
+    pub fn vertical_text(&mut self, at: (f64, f64), text: &str, scale: usize) {
+        self.shapes.push(Shape::Text {
+            at,
+            text: text.to_string(),
+            scale,
+            anchor: Anchor::Middle,
+            color: Color::BLACK,
+            vertical: true,
+        });
+    }
+
+    /// The width in pixels `text` takes at `scale`.
+    pub fn text_width(text: &str, scale: usize) -> f64 {
+        (text.chars().count() * CELL_WIDTH * scale) as f64
+    }
+
+    pub fn to_svg(&self) -> String {
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n",
+            self.width, self.height
+        );
+        svg.push_str("<rect width=\"100%\" height=\"100%\" fill=\"#ffffff\"/>\n");
+        for shape in &self.shapes {
+            // writing to a String cannot fail
+            let _ = match shape {
+                Shape::Line {
+                    from,
+                    to,
+                    color,
+                    width,
+                } => writeln!(
+                    svg,
+                    "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"{}\" stroke-width=\"{}\"/>",
+                    from.0, from.1, to.0, to.1, color.hex(), width
+                ),
+                Shape::Rect {
+                    corner,
+                    size,
+                    fill,
+                    stroke,
+                } => writeln!(
+                    svg,
+                    "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\" stroke=\"{}\"/>",
+                    corner.0,
+                    corner.1,
+                    size.0,
+                    size.1,
+                    fill.map_or(String::from("none"), |fill| fill.hex()),
+                    stroke.map_or(String::from("none"), |stroke| stroke.hex())
+                ),
+                Shape::Circle {
+                    center,
+                    radius,
+                    fill,
+                } => writeln!(
+                    svg,
+                    "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{}\" fill=\"{}\"/>",
+                    center.0,
+                    center.1,
+                    radius,
+                    fill.hex()
+                ),
+                Shape::Text {
+                    at,
+                    text,
+                    scale,
+                    anchor,
+                    color,
+                    vertical,
+                } => writeln!(
+                    svg,
+                    "<text x=\"{:.1}\" y=\"{:.1}\" font-family=\"monospace\" font-size=\"{}\" text-anchor=\"{}\" dominant-baseline=\"central\" fill=\"{}\"{}>{}</text>",
+                    at.0,
+                    at.1,
+                    CELL_HEIGHT * scale,
+                    match anchor {
+                        Anchor::Middle => "middle",
+                        Anchor::End => "end",
+                    },
+                    color.hex(),
+                    if *vertical {
+                        format!(" transform=\"rotate(-90 {:.1} {:.1})\"", at.0, at.1)
+                    } else {
+                        String::new()
+                    },
+                    escape_xml(text)
+                ),
+            };
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut pixels = Pixels {
+            width: self.width,
+            height: self.height,
+            data: vec![0xff; self.width * self.height * 3],
+        };
+        for shape in &self.shapes {
+            match shape {
+                Shape::Line {
+                    from,
+                    to,
+                    color,
+                    width,
+                } => pixels.line(*from, *to, *color, *width),
+                Shape::Rect {
+                    corner,
+                    size,
+                    fill,
+                    stroke,
+                } => {
+                    let (left, top) = *corner;
+                    let (right, bottom) = (left + size.0, top + size.1);
+                    if let Some(fill) = fill {
+                        pixels.fill(left, top, right, bottom, *fill);
+                    }
+                    if let Some(stroke) = stroke {
+                        for (from, to) in [
+                            ((left, top), (right, top)),
+                            ((right, top), (right, bottom)),
+                            ((right, bottom), (left, bottom)),
+                            ((left, bottom), (left, top)),
+                        ] {
+                            pixels.line(from, to, *stroke, 1.0);
+                        }
+                    }
+                }
+                Shape::Circle {
+                    center,
+                    radius,
+                    fill,
+                } => pixels.circle(*center, *radius, *fill),
+                Shape::Text {
+                    at,
+                    text,
+                    scale,
+                    anchor,
+                    color,
+                    vertical,
+                } => pixels.text(*at, text, *scale, *anchor, *color, *vertical),
+            }
+        }
+        pixels.encode_png()
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// An RGB raster, row by row from the top.
+struct Pixels {
+    width: usize,
+    height: usize,
+    data: Vec<u8>,
+}
+
+impl Pixels {
+    fn set(&mut self, x: i64, y: i64, color: Color) {
+        if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+            let index = (y as usize * self.width + x as usize) * 3;
+            self.data[index..index + 3].copy_from_slice(&[color.0, color.1, color.2]);
+        }
+    }
+
+    /// Every pixel whose center lies within the rectangle.
+    fn fill(&mut self, left: f64, top: f64, right: f64, bottom: f64, color: Color) {
+        for y in (top.min(bottom) - 0.5).ceil() as i64..(top.max(bottom) - 0.5).ceil() as i64 {
+            for x in (left.min(right) - 0.5).ceil() as i64..(left.max(right) - 0.5).ceil() as i64 {
+                self.set(x, y, color);
+            }
+        }
+    }
+
+    /// Stamps a `width`-wide square every half pixel along the line.
+    fn line(&mut self, from: (f64, f64), to: (f64, f64), color: Color, width: f64) {
+        let half = width.max(1.0) / 2.0;
+        let length = ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt();
+        let steps = (length * 2.0).ceil().max(1.0) as usize;
+        for step in 0..=steps {
+            let t = step as f64 / steps as f64;
+            let x = from.0 + t * (to.0 - from.0);
+            let y = from.1 + t * (to.1 - from.1);
+            self.fill(x - half, y - half, x + half, y + half, color);
+        }
+    }
+
+    fn circle(&mut self, center: (f64, f64), radius: f64, color: Color) {
+        let (cx, cy) = center;
+        for y in (cy - radius).floor() as i64..=(cy + radius).ceil() as i64 {
+            for x in (cx - radius).floor() as i64..=(cx + radius).ceil() as i64 {
+                if (x as f64 + 0.5 - cx).powi(2) + (y as f64 + 0.5 - cy).powi(2) <= radius.powi(2) {
+                    self.set(x, y, color);
+                }
+            }
+        }
+    }
+
+    fn text(
+        &mut self,
+        at: (f64, f64),
+        text: &str,
+        scale: usize,
+        anchor: Anchor,
+        color: Color,
+        vertical: bool,
+    ) {
+        let scale = scale.max(1);
+        let width = Canvas::text_width(text, scale);
+        let start = match anchor {
+            Anchor::Middle => -width / 2.0,
+            Anchor::End => -width,
+        };
+        let top = -((GLYPH_HEIGHT * scale) as f64) / 2.0;
+        for (index, character) in text.chars().enumerate() {
+            let glyph = glyph(character);
+            for (row, bits) in glyph.iter().enumerate() {
+                for column in 0..GLYPH_WIDTH {
+                    if bits & (1 << (GLYPH_WIDTH - 1 - column)) == 0 {
+                        continue;
+                    }
+                    for dy in 0..scale {
+                        for dx in 0..scale {
+                            // offsets along and across the line of text
+                            let along = start + ((index * CELL_WIDTH + column) * scale + dx) as f64;
+                            let across = top + (row * scale + dy) as f64;
+                            let (x, y) = if vertical {
+                                (at.0 + across, at.1 - along)
+                            } else {
+                                (at.0 + along, at.1 + across)
+                            };
+                            self.set(x.round() as i64, y.round() as i64, color);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn encode_png(&self) -> Vec<u8> {
+        // each row starts with filter type 0 (none)
+        let mut scanlines = Vec::with_capacity((self.width * 3 + 1) * self.height);
+        for row in self.data.chunks(self.width * 3) {
+            scanlines.push(0);
+            scanlines.extend_from_slice(row);
+        }
+
+        let mut header = vec![];
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        // 8 bit RGB, default compression and filtering, no interlace
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+        let mut png = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+        write_chunk(&mut png, b"IHDR", &header);
+        write_chunk(
+            &mut png,
+            b"IDAT",
+            &zlib_compress(&scanlines, self.width * 3 + 1),
+        );
+        write_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    png.extend_from_slice(&crc32(&[&kind[..], data].concat()).to_be_bytes());
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffff_u32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1_u32, 0_u32);
+    for byte in bytes {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+const LENGTH_BASES: [usize; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA_BITS: [u32; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASES: [usize; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA_BITS: [u32; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+const MAX_MATCH: usize = 258;
+
+/// A zlib stream of one fixed-Huffman deflate block. Charts are mostly runs of one
+/// color, so matching only the previous pixel and the row above compresses them well.
+fn zlib_compress(data: &[u8], row_length: usize) -> Vec<u8> {
+    let mut bits = BitWriter::default();
+    bits.write(1, 1); // final block
+    bits.write(1, 2); // fixed Huffman codes
+
+    let mut index = 0;
+    while index < data.len() {
+        let (length, distance) = [3, row_length]
+            .into_iter()
+            .filter(|distance| *distance <= index && *distance <= 32768)
+            .map(|distance| {
+                let length = (0..MAX_MATCH.min(data.len() - index))
+                    .take_while(|offset| data[index + offset] == data[index + offset - distance])
+                    .count();
+                (length, distance)
+            })
+            .max()
+            .unwrap_or((0, 0));
+        if length >= 3 {
+            let code = LENGTH_BASES.partition_point(|base| *base <= length) - 1;
+            bits.write_symbol(257 + code);
+            bits.write(
+                (length - LENGTH_BASES[code]) as u32,
+                LENGTH_EXTRA_BITS[code],
+            );
+            let code = DISTANCE_BASES.partition_point(|base| *base <= distance) - 1;
+            bits.write_reversed(code as u32, 5);
+            bits.write(
+                (distance - DISTANCE_BASES[code]) as u32,
+                DISTANCE_EXTRA_BITS[code],
+            );
+            index += length;
+        } else {
+            bits.write_symbol(data[index] as usize);
+            index += 1;
+        }
+    }
+    bits.write_symbol(256); // end of block
+
+    let mut zlib = vec![0x78, 0x01];
+    zlib.extend(bits.finish());
+    zlib.extend_from_slice(&adler32(data).to_be_bytes());
+    zlib
+}
+
+/// Packs bits least significant first, as deflate expects.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    current: u32,
+    count: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, count: u32) {
+        for bit in 0..count {
+            self.current |= ((value >> bit) & 1) << self.count;
+            self.count += 1;
+            if self.count == 8 {
+                self.bytes.push(self.current as u8);
+                self.current = 0;
+                self.count = 0;
+            }
+        }
+    }
+
+    /// Huffman codes are stored most significant bit first.
+    fn write_reversed(&mut self, code: u32, count: u32) {
+        for bit in (0..count).rev() {
+            self.write((code >> bit) & 1, 1);
+        }
+    }
+
+    /// A literal/length symbol in the fixed Huffman code.
+    fn write_symbol(&mut self, symbol: usize) {
+        let symbol = symbol as u32;
+        match symbol {
+            0..=143 => self.write_reversed(0x30 + symbol, 8),
+            144..=255 => self.write_reversed(0x190 + symbol - 144, 9),
+            256..=279 => self.write_reversed(symbol - 256, 7),
+            _ => self.write_reversed(0xc0 + symbol - 280, 8),
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push(self.current as u8);
+        }
+        self.bytes
+    }
+}
+
+/// The rows of `character` in the font, high bit on the left; '?' for anything
+/// outside printable ASCII.
+fn glyph(character: char) -> &'static [u8; GLYPH_HEIGHT] {
+    match character {
+        ' '..='~' => &FONT[character as usize - ' ' as usize],
+        _ => &FONT['?' as usize - ' ' as usize],
+    }
+}
+
+const FONT: [[u8; GLYPH_HEIGHT]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // space
+    [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04], // !
+    [0x0a, 0x0a, 0x0a, 0x00, 0x00, 0x00, 0x00], // "
+    [0x0a, 0x0a, 0x1f, 0x0a, 0x1f, 0x0a, 0x0a], // #
+    [0x04, 0x0f, 0x14, 0x0e, 0x05, 0x1e, 0x04], // $
+    [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03], // %
+    [0x0c, 0x12, 0x14, 0x08, 0x15, 0x12, 0x0d], // &
+    [0x04, 0x04, 0x08, 0x00, 0x00, 0x00, 0x00], // '
+    [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02], // (
+    [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08], // )
+    [0x00, 0x04, 0x15, 0x0e, 0x15, 0x04, 0x00], // *
+    [0x00, 0x04, 0x04, 0x1f, 0x04, 0x04, 0x00], // +
+    [0x00, 0x00, 0x00, 0x00, 0x0c, 0x04, 0x08], // ,
+    [0x00, 0x00, 0x00, 0x1f, 0x00, 0x00, 0x00], // -
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x0c], // .
+    [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00], // /
+    [0x0e, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0e], // 0
+    [0x04, 0x0c, 0x04, 0x04, 0x04, 0x04, 0x0e], // 1
+    [0x0e, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1f], // 2
+    [0x1f, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0e], // 3
+    [0x02, 0x06, 0x0a, 0x12, 0x1f, 0x02, 0x02], // 4
+    [0x1f, 0x10, 0x1e, 0x01, 0x01, 0x11, 0x0e], // 5
+    [0x06, 0x08, 0x10, 0x1e, 0x11, 0x11, 0x0e], // 6
+    [0x1f, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08], // 7
+    [0x0e, 0x11, 0x11, 0x0e, 0x11, 0x11, 0x0e], // 8
+    [0x0e, 0x11, 0x11, 0x0f, 0x01, 0x02, 0x0c], // 9
+    [0x00, 0x0c, 0x0c, 0x00, 0x0c, 0x0c, 0x00], // :
+    [0x00, 0x0c, 0x0c, 0x00, 0x0c, 0x04, 0x08], // ;
+    [0x02, 0x04, 0x08, 0x10, 0x08, 0x04, 0x02], // <
+    [0x00, 0x00, 0x1f, 0x00, 0x1f, 0x00, 0x00], // =
+    [0x08, 0x04, 0x02, 0x01, 0x02, 0x04, 0x08], // >
+    [0x0e, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04], // ?
+    [0x0e, 0x11, 0x01, 0x0d, 0x15, 0x15, 0x0e], // @
+    [0x0e, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11], // A
+    [0x1e, 0x11, 0x11, 0x1e, 0x11, 0x11, 0x1e], // B
+    [0x0e, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0e], // C
+    [0x1c, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1c], // D
+    [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x1f], // E
+    [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x10], // F
+    [0x0e, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0f], // G
+    [0x11, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11], // H
+    [0x0e, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0e], // I
+    [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0c], // J
+    [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11], // K
+    [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1f], // L
+    [0x11, 0x1b, 0x15, 0x15, 0x11, 0x11, 0x11], // M
+    [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11], // N
+    [0x0e, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e], // O
+    [0x1e, 0x11, 0x11, 0x1e, 0x10, 0x10, 0x10], // P
+    [0x0e, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0d], // Q
+    [0x1e, 0x11, 0x11, 0x1e, 0x14, 0x12, 0x11], // R
+    [0x0f, 0x10, 0x10, 0x0e, 0x01, 0x01, 0x1e], // S
+    [0x1f, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04], // T
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e], // U
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x0a, 0x04], // V
+    [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0a], // W
+    [0x11, 0x11, 0x0a, 0x04, 0x0a, 0x11, 0x11], // X
+    [0x11, 0x11, 0x11, 0x0a, 0x04, 0x04, 0x04], // Y
+    [0x1f, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1f], // Z
+    [0x0e, 0x08, 0x08, 0x08, 0x08, 0x08, 0x0e], // [
+    [0x00, 0x10, 0x08, 0x04, 0x02, 0x01, 0x00], // \
+    [0x0e, 0x02, 0x02, 0x02, 0x02, 0x02, 0x0e], // ]
+    [0x04, 0x0a, 0x11, 0x00, 0x00, 0x00, 0x00], // ^
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1f], // _
+    [0x08, 0x04, 0x02, 0x00, 0x00, 0x00, 0x00], // `
+    [0x00, 0x00, 0x0e, 0x01, 0x0f, 0x11, 0x0f], // a
+    [0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x1e], // b
+    [0x00, 0x00, 0x0e, 0x10, 0x10, 0x11, 0x0e], // c
+    [0x01, 0x01, 0x0d, 0x13, 0x11, 0x11, 0x0f], // d
+    [0x00, 0x00, 0x0e, 0x11, 0x1f, 0x10, 0x0e], // e
+    [0x06, 0x09, 0x08, 0x1c, 0x08, 0x08, 0x08], // f
+    [0x00, 0x0f, 0x11, 0x11, 0x0f, 0x01, 0x0e], // g
+    [0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x11], // h
+    [0x04, 0x00, 0x0c, 0x04, 0x04, 0x04, 0x0e], // i
+    [0x02, 0x00, 0x06, 0x02, 0x02, 0x12, 0x0c], // j
+    [0x10, 0x10, 0x12, 0x14, 0x18, 0x14, 0x12], // k
+    [0x0c, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0e], // l
+    [0x00, 0x00, 0x1a, 0x15, 0x15, 0x11, 0x11], // m
+    [0x00, 0x00, 0x16, 0x19, 0x11, 0x11, 0x11], // n
+    [0x00, 0x00, 0x0e, 0x11, 0x11, 0x11, 0x0e], // o
+    [0x00, 0x00, 0x1e, 0x11, 0x1e, 0x10, 0x10], // p
+    [0x00, 0x00, 0x0d, 0x13, 0x0f, 0x01, 0x01], // q
+    [0x00, 0x00, 0x16, 0x19, 0x10, 0x10, 0x10], // r
+    [0x00, 0x00, 0x0e, 0x10, 0x0e, 0x01, 0x1e], // s
+    [0x08, 0x08, 0x1c, 0x08, 0x08, 0x09, 0x06], // t
+    [0x00, 0x00, 0x11, 0x11, 0x11, 0x13, 0x0d], // u
+    [0x00, 0x00, 0x11, 0x11, 0x11, 0x0a, 0x04], // v
+    [0x00, 0x00, 0x11, 0x11, 0x15, 0x15, 0x0a], // w
+    [0x00, 0x00, 0x11, 0x0a, 0x04, 0x0a, 0x11], // x
+    [0x00, 0x00, 0x11, 0x11, 0x0f, 0x01, 0x0e], // y
+    [0x00, 0x00, 0x1f, 0x02, 0x04, 0x08, 0x1f], // z
+    [0x02, 0x04, 0x04, 0x08, 0x04, 0x04, 0x02], // {
+    [0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04], // |
+    [0x08, 0x04, 0x04, 0x02, 0x04, 0x04, 0x08], // }
+    [0x00, 0x00, 0x08, 0x15, 0x02, 0x00, 0x00], // ~
+];
@@ -1,53 +1,459 @@
 use crate::error_types::{CSVError, CSVErrorKind};
-use anyhow::{Error, Result};
-use log::info;
+use crate::functions::sampling::reservoir_sample;
+use crate::functions::stats_math::{mean, median};
+use crate::reporter::{report, report_warning};
+use anyhow::{anyhow, Error, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use clap::ValueEnum;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
 use std::fmt::Debug;
-use std::path::Path;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::{Arc, RwLock};
 
 pub fn import_csv_data(
     file_path: &Path,
     has_headers: Option<bool>,
     delimiter: Option<u8>,
 ) -> Result<CSVData, Error> {
+    let mut reader = csv_reader(file_path, has_headers, delimiter)?;
+    let mut decoder = RecordDecoder::new(file_path);
+
+    let mut sample_data: CSVData = Default::default();
+    sample_data.headers = decoder
+        .decode(reader.byte_headers()?)
+        .iter()
+        .map(String::from)
+        .collect();
+    let mut column_count: usize = 0;
+
+    for result in reader.byte_records() {
+        let string_record = decoder.decode(&result?);
+        sample_data.push_record(&string_record);
+        column_count += 1;
+    }
+    sample_data.column_count = column_count;
+    sample_data.infer_column_types();
+    Ok(sample_data)
+}
+
+/// Like `import_csv_data`, but keeps only a uniform random sample of `size` rows, drawn
+/// while reading so a file of any length is held `size` rows at a time. Returns the
+/// sample and the number of rows in the file.
+pub fn import_csv_sample(
+    file_path: &Path,
+    has_headers: Option<bool>,
+    delimiter: Option<u8>,
+    size: usize,
+    seed: u64,
+) -> Result<(CSVData, usize), Error> {
+    let mut reader = csv_reader(file_path, has_headers, delimiter)?;
+    let mut decoder = RecordDecoder::new(file_path);
+
+    let mut sample_data = CSVData {
+        headers: decoder
+            .decode(reader.byte_headers()?)
+            .iter()
+            .map(String::from)
+            .collect(),
+        ..Default::default()
+    };
+    let (records, total_rows) = reservoir_sample(
+        reader
+            .byte_records()
+            .map(|result| Ok(decoder.decode(&result?))),
+        size,
+        seed,
+    )?;
+    for string_record in records.iter() {
+        sample_data.push_record(string_record);
+    }
+    sample_data.column_count = records.len();
+    sample_data.infer_column_types();
+    Ok((sample_data, total_rows))
+}
+
+/// Reads a file one record at a time into a single reused buffer, for files too large
+/// to import: memory stays at one row however long the file is.
+pub struct CSVStream {
+    reader: csv::Reader<File>,
+    decoder: RecordDecoder,
+    bytes: csv::ByteRecord,
+    record: csv::StringRecord,
+    pub headers: Vec<String>,
+    pub rows_read: usize,
+}
+
+impl CSVStream {
+    pub fn open(
+        file_path: &Path,
+        has_headers: Option<bool>,
+        delimiter: Option<u8>,
+    ) -> Result<CSVStream, Error> {
+        let mut reader = csv_reader(file_path, has_headers, delimiter)?;
+        let mut decoder = RecordDecoder::new(file_path);
+        let headers = decoder
+            .decode(reader.byte_headers()?)
+            .iter()
+            .map(String::from)
+            .collect();
+        Ok(CSVStream {
+            reader,
+            decoder,
+            bytes: csv::ByteRecord::new(),
+            record: csv::StringRecord::new(),
+            headers,
+            rows_read: 0,
+        })
+    }
+
+    /// The next row, valid until the following call, or `None` at the end of the file.
+    pub fn next_row(&mut self) -> Result<Option<&csv::StringRecord>, Error> {
+        if self.reader.read_byte_record(&mut self.bytes)? {
+            self.decoder.decode_into(&self.bytes, &mut self.record);
+            self.rows_read += 1;
+            Ok(Some(&self.record))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Turns the raw fields of a file into text: as UTF-8 when they are, else as Latin-1
+/// (ISO 8859-1), which gives every byte a character, so a file exported in a legacy
+/// encoding still reads. Warns the first time a file falls back.
+struct RecordDecoder {
+    file_path: PathBuf,
+    warned: bool,
+}
+
+impl RecordDecoder {
+    fn new(file_path: &Path) -> RecordDecoder {
+        RecordDecoder {
+            file_path: file_path.to_path_buf(),
+            warned: false,
+        }
+    }
+
+    fn decode(&mut self, bytes: &csv::ByteRecord) -> csv::StringRecord {
+        let mut record = csv::StringRecord::with_capacity(bytes.as_slice().len(), bytes.len());
+        self.decode_into(bytes, &mut record);
+        record
+    }
+
+    /// Overwrites `record` with the decoded fields, reusing its buffer.
+    fn decode_into(&mut self, bytes: &csv::ByteRecord, record: &mut csv::StringRecord) {
+        record.clear();
+        for field in bytes.iter() {
+            match std::str::from_utf8(field) {
+                Ok(text) => record.push_field(text),
+                Err(_) => {
+                    if !self.warned {
+                        report_latin1_fallback(&self.file_path);
+                        self.warned = true;
+                    }
+                    record.push_field(&decode_latin1(field));
+                }
+            }
+        }
+    }
+}
+
+pub fn report_latin1_fallback(file_path: &Path) {
+    report_warning!(
+        "'{}' is not valid UTF-8; reading it as Latin-1, so characters in any other encoding may be misread",
+        file_path.display()
+    );
+}
+
+/// Latin-1 (ISO 8859-1) text, whose 256 characters are the first 256 of Unicode.
+pub fn decode_latin1(bytes: &[u8]) -> String {
+    bytes.iter().map(|&byte| char::from(byte)).collect()
+}
+
+/// Reads `file_path` with the given delimiter, or the one `detect_delimiter` finds.
+/// Fields may be quoted ("a, b") to hold the delimiter, quotes ("" inside quotes), or
+/// line breaks. A leading UTF-8 byte order mark, as spreadsheets often write, is not
+/// part of the first header.
+fn csv_reader(
+    file_path: &Path,
+    has_headers: Option<bool>,
+    delimiter: Option<u8>,
+) -> Result<csv::Reader<File>, Error> {
     let mut reader_builder = csv::ReaderBuilder::new();
 
     match has_headers {
         Some(has_headers) => reader_builder.has_headers(has_headers),
         _ => reader_builder.has_headers(true),
     };
-    match delimiter {
-        Some(delimiter) => reader_builder.delimiter(delimiter),
-        _ => reader_builder.delimiter(b','),
+    let delimiter = match delimiter {
+        Some(delimiter) => delimiter,
+        None => {
+            let detected = detect_delimiter(file_path)?;
+            if detected != Delimiter::Comma {
+                report!(
+                    "Reading '{}' as {}-delimited",
+                    file_path.display(),
+                    detected.label()
+                );
+            }
+            detected.byte()
+        }
     };
+    reader_builder.delimiter(delimiter);
 
-    let mut reader = reader_builder.from_path(file_path)?;
+    Ok(reader_builder.from_path(file_path)?)
+}
 
-    let mut sample_data: CSVData = Default::default();
-    sample_data.headers = reader.headers()?.clone().iter().map(String::from).collect();
-    let mut column_count: usize = 0;
+/// How much of a file `detect_delimiter` reads, and how many of its rows it compares.
+const DETECTION_BYTES: u64 = 64 * 1024;
+const DETECTION_ROWS: usize = 20;
 
-    for result in reader.records() {
-        let string_record = result?;
-        sample_data.row_length = string_record.len();
-        column_count += 1;
-        for string in string_record.iter() {
-            sample_data.data.push(string.to_string().trim().to_string()); // trim in case of whitespace
+/// The field separators a file can use.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delimiter {
+    Comma,
+    Tab,
+    Semicolon,
+    Pipe,
+}
+
+impl Delimiter {
+    pub fn byte(&self) -> u8 {
+        match self {
+            Delimiter::Comma => b',',
+            Delimiter::Tab => b'\t',
+            Delimiter::Semicolon => b';',
+            Delimiter::Pipe => b'|',
         }
     }
-    sample_data.column_count = column_count;
-    Ok(sample_data)
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Delimiter::Comma => "comma",
+            Delimiter::Tab => "tab",
+            Delimiter::Semicolon => "semicolon",
+            Delimiter::Pipe => "pipe",
+        }
+    }
+}
+
+/// Guesses the delimiter from the first rows of a file: the one that splits every row
+/// into the same number of fields (ignoring delimiters inside quotes), preferring the
+/// one that gives the most fields. Falls back to the delimiter that splits the first row
+/// most, then to a comma.
+pub fn detect_delimiter(file_path: &Path) -> Result<Delimiter, Error> {
+    let mut head = vec![];
+    File::open(file_path)?
+        .take(DETECTION_BYTES)
+        .read_to_end(&mut head)?;
+    let truncated = head.len() as u64 == DETECTION_BYTES;
+    let head = String::from_utf8_lossy(&head);
+
+    let candidates = Delimiter::value_variants();
+    // delimiter counts per row, one entry per candidate
+    let mut rows: Vec<Vec<usize>> = vec![];
+    let mut counts = vec![0; candidates.len()];
+    let mut in_quotes = false;
+    for c in head.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '\n' if !in_quotes => {
+                rows.push(std::mem::replace(&mut counts, vec![0; candidates.len()]));
+                if rows.len() == DETECTION_ROWS {
+                    break;
+                }
+            }
+            _ if !in_quotes => {
+                if let Some(index) = candidates
+                    .iter()
+                    .position(|candidate| candidate.byte() as char == c)
+                {
+                    counts[index] += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    // a last row cut off by the read limit would undercount
+    if !truncated && counts.iter().any(|count| *count > 0) {
+        rows.push(counts);
+    }
+    let Some(first) = rows.first() else {
+        return Ok(Delimiter::Comma);
+    };
+
+    let consistent = (0..candidates.len())
+        .filter(|index| first[*index] > 0 && rows.iter().all(|row| row[*index] == first[*index]))
+        .max_by_key(|index| (first[*index], std::cmp::Reverse(*index)));
+    let most_in_first = (0..candidates.len())
+        .filter(|index| first[*index] > 0)
+        .max_by_key(|index| (first[*index], std::cmp::Reverse(*index)));
+    Ok(consistent
+        .or(most_in_first)
+        .map(|index| candidates[index])
+        .unwrap_or(Delimiter::Comma))
+}
+
+/// Cell contents treated as missing, compared case-insensitively after trimming.
+const MISSING_VALUES: [&str; 5] = ["", "na", "n/a", "nan", "null"];
+
+pub fn is_missing(value: &str) -> bool {
+    MISSING_VALUES.contains(&value.to_lowercase().as_str())
+}
+
+/// What to do with missing cells in the columns a statistic reads.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MissingPolicy {
+    /// Stop with an error naming the missing cells
+    #[default]
+    Error,
+    /// Keep only rows complete in every column of the file, so all analyses of it share rows
+    Listwise,
+    /// Drop rows missing a value in any column a statistic reads, separately for each
+    /// statistic, so each one of a chain keeps its own complete cases
+    Analysis,
+    /// Replace missing continuous values with the column mean (rows missing a category are dropped)
+    Mean,
+    /// Replace missing continuous values with the column median (rows missing a category are dropped)
+    Median,
 }
 
-#[derive(Default, Debug)]
+/// Which rows of the main file a join keeps.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JoinKind {
+    /// Only rows whose key matches a row of the other file
+    #[default]
+    Inner,
+    /// Every row, with missing cells where the other file has no match
+    Left,
+}
+
+/// What a column holds, inferred from its present cells when the data are read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    Continuous,  // every present cell is a number
+    Boolean,     // true/false, yes/no, t/f, or y/n, in any case
+    Date,        // ISO 8601 dates (2024-03-01) or date-times
+    Categorical, // anything else, including a column with no present cells
+}
+
+impl ColumnType {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ColumnType::Continuous => "continuous",
+            ColumnType::Boolean => "boolean",
+            ColumnType::Date => "date",
+            ColumnType::Categorical => "categorical",
+        }
+    }
+}
+
+/// The narrowest type that fits every present value: a number, then a boolean, then a
+/// date, else a category.
+pub fn infer_column_type<'a>(values: impl Iterator<Item = &'a str>) -> ColumnType {
+    let present = values
+        .filter(|value| !is_missing(value))
+        .collect::<Vec<&str>>();
+    if present.is_empty() {
+        ColumnType::Categorical
+    } else if present.iter().all(|value| value.parse::<f64>().is_ok()) {
+        ColumnType::Continuous
+    } else if present.iter().all(|value| is_boolean(value)) {
+        ColumnType::Boolean
+    } else if present.iter().all(|value| is_date(value)) {
+        ColumnType::Date
+    } else {
+        ColumnType::Categorical
+    }
+}
+
+fn is_boolean(value: &str) -> bool {
+    matches!(
+        value.to_lowercase().as_str(),
+        "true" | "false" | "yes" | "no" | "t" | "f" | "y" | "n"
+    )
+}
+
+fn is_date(value: &str) -> bool {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok()
+        || NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S").is_ok()
+        || NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").is_ok()
+        || DateTime::parse_from_rfc3339(value).is_ok()
+}
+
+#[derive(Clone, Default, Debug)]
 pub struct CSVData {
     pub data: Vec<String>,
     pub headers: Vec<String>,
     pub row_length: usize,
     pub column_count: usize,
+    pub column_types: Vec<ColumnType>, // one per column, as read
+    pub reference_levels: HashMap<usize, String>, // by column, the level dummy coding leaves out
+    column_cache: ColumnCache,
+}
+
+/// Columns already parsed by `get_column_cached`, and values derived from them by
+/// `get_derived_cached`, keyed by column index, type, and the options they were built
+/// with. Clones share one cache, so what one analysis of a copy builds is there for the
+/// next; changing a cell gives the changed copy a cache of its own first.
+#[derive(Default, Clone)]
+struct ColumnCache(Arc<RwLock<HashMap<CacheKey, Arc<dyn Any + Send + Sync>>>>);
+
+type CacheKey = (usize, TypeId, String);
+
+impl ColumnCache {
+    fn get<T: Send + Sync + 'static>(&self, key: &CacheKey) -> Option<Arc<T>> {
+        let cached = Arc::clone(self.0.read().unwrap().get(key)?);
+        cached.downcast::<T>().ok()
+    }
+
+    fn insert<T: Send + Sync + 'static>(&self, key: CacheKey, value: &Arc<T>) {
+        self.0
+            .write()
+            .unwrap()
+            .insert(key, Arc::clone(value) as Arc<dyn Any + Send + Sync>);
+    }
+
+    /// A separate cache holding everything but `column`'s entries.
+    fn without_column(&self, column: usize) -> ColumnCache {
+        let mut entries = self.0.read().unwrap().clone();
+        entries.retain(|(cached_column, _, _), _| *cached_column != column);
+        ColumnCache(Arc::new(RwLock::new(entries)))
+    }
+}
+
+impl Debug for ColumnCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ColumnCache({} columns)", self.0.read().unwrap().len())
+    }
 }
 
 impl CSVData {
+    /// Classifies every column from its cells; the importers call this once the rows are
+    /// read.
+    pub fn infer_column_types(&mut self) {
+        self.column_types = (0..self.row_length)
+            .map(|column| {
+                infer_column_type(
+                    (0..self.column_count)
+                        .map(|row| self.data[self.row_length * row + column].as_str()),
+                )
+            })
+            .collect();
+    }
+
+    /// The inferred type of a 0-based column.
+    pub fn column_type(&self, column: usize) -> ColumnType {
+        self.column_types
+            .get(column)
+            .copied()
+            .unwrap_or(ColumnType::Categorical)
+    }
+
     /// Retrieves a single datum from CSVData's data vector as if it were a 2D array.
     /// To imitate CSV row and column indexing, this function allows an option of
     /// indexing at 1 (it indexes from 0 as default).
@@ -89,7 +495,7 @@ impl CSVData {
     where
         T: FromStr + Clone + Debug,
     {
-        info!(
+        report!(
             "Retrieving column {} from CSV using {}-based indexing",
             column,
             if one_based_index.unwrap_or_default() {
@@ -111,4 +517,459 @@ impl CSVData {
         }
         Ok(col)
     }
+
+    /// Like `get_column` (0-based), but parses each column as a given type only once;
+    /// later calls for the same column and type share the same vector.
+    pub fn get_column_cached<T>(&self, column: usize) -> Result<Arc<Vec<T>>, CSVError<T>>
+    where
+        T: FromStr + Clone + Debug + Send + Sync + 'static,
+    {
+        let key = (column, TypeId::of::<Vec<T>>(), String::new());
+        if let Some(cached) = self.column_cache.get::<Vec<T>>(&key) {
+            return Ok(cached);
+        }
+
+        let parsed = Arc::new(self.get_column::<T>(column, None)?);
+        self.column_cache.insert(key, &parsed);
+        Ok(parsed)
+    }
+
+    /// A value derived from `column` (such as its data array), built by `build` the first
+    /// time it's asked for with these `options` and shared after that, until the column
+    /// changes.
+    pub fn get_derived_cached<T, F>(
+        &self,
+        column: usize,
+        options: &str,
+        build: F,
+    ) -> Result<Arc<T>, Error>
+    where
+        T: Send + Sync + 'static,
+        F: FnOnce() -> Result<T, Error>,
+    {
+        let key = (column, TypeId::of::<T>(), options.to_string());
+        if let Some(cached) = self.column_cache.get::<T>(&key) {
+            return Ok(cached);
+        }
+
+        let derived = Arc::new(build()?);
+        self.column_cache.insert(key, &derived);
+        Ok(derived)
+    }
+
+    /// Returns the 0-based indices of rows whose cell in `column` is missing.
+    pub fn get_missing_rows(&self, column: usize) -> Vec<usize> {
+        (0..self.column_count)
+            .filter(|row| is_missing(&self.data[self.row_length * row + column]))
+            .collect()
+    }
+
+    /// Resolves the missing cells in the given 0-based columns according to `policy`,
+    /// reporting each column affected. Imputed values are computed from the cells
+    /// that are present (and parse as numbers).
+    pub fn apply_missing_policy(
+        self,
+        policy: MissingPolicy,
+        continuous_columns: &[usize],
+        categorical_columns: &[usize],
+    ) -> Result<CSVData, Error> {
+        let columns = categorical_columns
+            .iter()
+            .chain(continuous_columns.iter())
+            .copied()
+            .collect::<Vec<usize>>();
+        let missing = columns
+            .iter()
+            .map(|column| (*column, self.get_missing_rows(*column)))
+            .filter(|(_, rows)| !rows.is_empty())
+            .collect::<Vec<(usize, Vec<usize>)>>();
+        if missing.is_empty() && policy != MissingPolicy::Listwise {
+            return Ok(self);
+        }
+        for (column, rows) in missing.iter() {
+            report!(
+                "'{}' has {} missing value(s) at row(s) {:?}",
+                self.headers[*column],
+                rows.len(),
+                rows
+            );
+        }
+
+        match policy {
+            MissingPolicy::Error => Err(anyhow!(
+                "Missing values in {} column(s); choose how to handle them with --missing \
+                 (listwise, analysis, mean, or median)",
+                missing.len()
+            )),
+            MissingPolicy::Listwise => {
+                let rows = (0..self.row_length)
+                    .flat_map(|column| self.get_missing_rows(column))
+                    .collect::<Vec<usize>>();
+                Ok(self.without_missing_rows(rows))
+            }
+            MissingPolicy::Analysis => {
+                let rows = missing.into_iter().flat_map(|(_, rows)| rows).collect();
+                Ok(self.without_missing_rows(rows))
+            }
+            MissingPolicy::Mean | MissingPolicy::Median => {
+                let mut imputed = self;
+                for (column, rows) in missing.iter() {
+                    if !continuous_columns.contains(column) {
+                        continue;
+                    }
+                    let mut is_missing_row = vec![false; imputed.column_count];
+                    rows.iter().for_each(|row| is_missing_row[*row] = true);
+                    let present = (0..imputed.column_count)
+                        .filter(|row| !is_missing_row[*row])
+                        .filter_map(|row| imputed.get_datum::<f64>(row, *column, None).ok())
+                        .collect::<Vec<f64>>();
+                    let (statistic, value) = if policy == MissingPolicy::Mean {
+                        ("mean", mean(&present)?)
+                    } else {
+                        ("median", median(&present)?)
+                    };
+                    report!(
+                        "Imputing {} missing value(s) in '{}' with the {} ({})",
+                        rows.len(),
+                        imputed.headers[*column],
+                        statistic,
+                        value
+                    );
+                    for row in rows {
+                        imputed.set_datum(*row, *column, value.to_string());
+                    }
+                }
+                // imputing a category is not meaningful, so those rows are dropped
+                let rows = missing
+                    .into_iter()
+                    .filter(|(column, _)| !continuous_columns.contains(column))
+                    .flat_map(|(_, rows)| rows)
+                    .collect();
+                Ok(imputed.without_missing_rows(rows))
+            }
+        }
+    }
+
+    fn without_missing_rows(self, mut rows: Vec<usize>) -> CSVData {
+        rows.sort();
+        rows.dedup();
+        if rows.is_empty() {
+            return self;
+        }
+        report!(
+            "Dropping {} row(s) with missing values: {:?}",
+            rows.len(),
+            rows
+        );
+        self.without_rows(&rows)
+    }
+
+    /// Overwrites a single datum (0-based row and column).
+    pub fn set_datum(&mut self, row: usize, column: usize, value: String) {
+        self.data[self.row_length * row + column] = value;
+        self.column_cache = self.column_cache.without_column(column);
+    }
+
+    fn push_record(&mut self, string_record: &csv::StringRecord) {
+        self.row_length = string_record.len();
+        for string in string_record.iter() {
+            self.data.push(string.to_string().trim().to_string()); // trim in case of whitespace
+        }
+    }
+
+    /// Returns a uniform random sample of `size` rows, in their original order, and the
+    /// number of rows sampled from.
+    pub fn sample_rows(&self, size: usize, seed: u64) -> Result<(CSVData, usize), Error> {
+        let (rows, total_rows) = reservoir_sample((0..self.column_count).map(Ok), size, seed)?;
+        let mut sampled: CSVData = CSVData {
+            data: Vec::with_capacity(rows.len() * self.row_length),
+            headers: self.headers.clone(),
+            row_length: self.row_length,
+            column_count: rows.len(),
+            column_types: self.column_types.clone(),
+            reference_levels: self.reference_levels.clone(),
+            column_cache: Default::default(),
+        };
+        for row in rows {
+            sampled
+                .data
+                .extend_from_slice(&self.data[self.row_length * row..self.row_length * (row + 1)]);
+        }
+        Ok((sampled, total_rows))
+    }
+
+    /// Writes every row to a CSV file at `path`, followed by the given derived columns
+    /// (name, one value per row).
+    pub fn export_with_columns(
+        &self,
+        path: &Path,
+        columns: &[(String, Vec<f64>)],
+    ) -> Result<(), Error> {
+        if let Some((name, values)) = columns
+            .iter()
+            .find(|(_, values)| values.len() != self.column_count)
+        {
+            return Err(anyhow!(
+                "Derived column '{}' has {} values for {} rows",
+                name,
+                values.len(),
+                self.column_count
+            ));
+        }
+
+        let mut writer = csv::Writer::from_path(path)?;
+        writer.write_record(
+            self.headers
+                .iter()
+                .map(String::as_str)
+                .chain(columns.iter().map(|(name, _)| name.as_str())),
+        )?;
+        for row in 0..self.column_count {
+            writer.write_record(
+                self.data[self.row_length * row..self.row_length * (row + 1)]
+                    .iter()
+                    .cloned()
+                    .chain(columns.iter().map(|(_, values)| values[row].to_string())),
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Returns a copy of the data with the given 0-based rows removed.
+    pub fn without_rows(&self, rows: &[usize]) -> CSVData {
+        let mut removed = vec![false; self.column_count];
+        for row in rows {
+            removed[*row] = true;
+        }
+        let kept = (0..self.column_count)
+            .filter(|row| !removed[*row])
+            .collect::<Vec<usize>>();
+        self.with_rows(&kept)
+    }
+
+    /// Returns a copy of the data with only the given 0-based rows, in that order.
+    pub fn with_rows(&self, rows: &[usize]) -> CSVData {
+        let mut filtered: CSVData = CSVData {
+            data: Vec::with_capacity(self.row_length * rows.len()),
+            headers: self.headers.clone(),
+            row_length: self.row_length,
+            column_count: 0,
+            column_types: self.column_types.clone(),
+            reference_levels: self.reference_levels.clone(),
+            column_cache: Default::default(),
+        };
+        for row in rows {
+            filtered
+                .data
+                .extend_from_slice(&self.data[self.row_length * row..self.row_length * (row + 1)]);
+            filtered.column_count += 1;
+        }
+        filtered
+    }
+
+    /// Joins the rows of `other` whose `other_key` equals this data's `key`, appending
+    /// `other`'s columns except its key. A row matching several rows of `other` appears
+    /// once per match, and a missing key matches nothing. Headers `other` shares with this
+    /// data get `label` appended in parentheses.
+    pub fn join(
+        &self,
+        other: &CSVData,
+        key: usize,
+        other_key: usize,
+        kind: JoinKind,
+        label: &str,
+    ) -> Result<CSVData, Error> {
+        if key >= self.row_length || other_key >= other.row_length {
+            return Err(anyhow!("The join key column is out of range"));
+        }
+        let mut matches: HashMap<&str, Vec<usize>> = HashMap::new();
+        for row in 0..other.column_count {
+            let value = other.data[other.row_length * row + other_key].as_str();
+            if !is_missing(value) {
+                matches.entry(value).or_default().push(row);
+            }
+        }
+
+        let other_columns = (0..other.row_length)
+            .filter(|column| *column != other_key)
+            .collect::<Vec<usize>>();
+        let mut joined = CSVData {
+            headers: self.headers.clone(),
+            ..Default::default()
+        };
+        for column in other_columns.iter() {
+            let header = &other.headers[*column];
+            joined.headers.push(if self.headers.contains(header) {
+                format!("{} ({})", header, label)
+            } else {
+                header.clone()
+            });
+        }
+        joined.row_length = joined.headers.len();
+
+        for row in 0..self.column_count {
+            let cells = &self.data[self.row_length * row..self.row_length * (row + 1)];
+            match matches.get(cells[key].as_str()) {
+                Some(other_rows) => {
+                    for other_row in other_rows {
+                        joined.data.extend_from_slice(cells);
+                        joined.data.extend(other_columns.iter().map(|column| {
+                            other.data[other.row_length * other_row + column].clone()
+                        }));
+                        joined.column_count += 1;
+                    }
+                }
+                None if kind == JoinKind::Left => {
+                    joined.data.extend_from_slice(cells);
+                    joined
+                        .data
+                        .extend(other_columns.iter().map(|_| String::new()));
+                    joined.column_count += 1;
+                }
+                None => {}
+            }
+        }
+        joined.infer_column_types();
+        Ok(joined)
+    }
+
+    /// Reshapes wide to long: each row becomes one row per `columns` entry, holding the
+    /// other columns, the entry's header under `key_name`, and its cell under
+    /// `value_name`.
+    pub fn melt(
+        &self,
+        columns: &[usize],
+        key_name: &str,
+        value_name: &str,
+    ) -> Result<CSVData, Error> {
+        if columns.is_empty() {
+            return Err(anyhow!("Name at least one column to melt"));
+        }
+        if let Some(column) = columns.iter().find(|column| **column >= self.row_length) {
+            return Err(anyhow!(
+                "Column {} is out of range ({} columns)",
+                column,
+                self.row_length
+            ));
+        }
+        let kept = (0..self.row_length)
+            .filter(|column| !columns.contains(column))
+            .collect::<Vec<usize>>();
+        let mut melted = CSVData {
+            headers: kept
+                .iter()
+                .map(|column| self.headers[*column].clone())
+                .collect(),
+            ..Default::default()
+        };
+        for name in [key_name, value_name] {
+            if melted.headers.iter().any(|header| header == name) {
+                return Err(anyhow!(
+                    "'{}' is already a column header; choose another name",
+                    name
+                ));
+            }
+            melted.headers.push(name.to_string());
+        }
+        melted.row_length = melted.headers.len();
+
+        for row in 0..self.column_count {
+            let cells = &self.data[self.row_length * row..self.row_length * (row + 1)];
+            for column in columns {
+                melted
+                    .data
+                    .extend(kept.iter().map(|kept| cells[*kept].clone()));
+                melted.data.push(self.headers[*column].clone());
+                melted.data.push(cells[*column].clone());
+                melted.column_count += 1;
+            }
+        }
+        melted.infer_column_types();
+        Ok(melted)
+    }
+
+    /// Reshapes long to wide: one row per value of `id`, with a column for each value of
+    /// `key` (in order of first appearance) holding the `value` cell of that pair, missing
+    /// where an ID lacks it. Other columns are taken from each ID's first row.
+    pub fn pivot(&self, id: usize, key: usize, value: usize) -> Result<CSVData, Error> {
+        if let Some(column) = [id, key, value]
+            .iter()
+            .find(|column| **column >= self.row_length)
+        {
+            return Err(anyhow!(
+                "Column {} is out of range ({} columns)",
+                column,
+                self.row_length
+            ));
+        }
+        let kept = (0..self.row_length)
+            .filter(|column| *column != key && *column != value)
+            .collect::<Vec<usize>>();
+        let cell = |row: usize, column: usize| self.data[self.row_length * row + column].as_str();
+
+        let (mut ids, mut keys): (Vec<&str>, Vec<&str>) = (vec![], vec![]);
+        let mut first_rows: HashMap<&str, usize> = HashMap::new();
+        let mut values: HashMap<(&str, &str), &str> = HashMap::new();
+        for row in 0..self.column_count {
+            let (row_id, row_key) = (cell(row, id), cell(row, key));
+            if is_missing(row_id) || is_missing(row_key) {
+                return Err(anyhow!(
+                    "Row {} is missing its '{}' or '{}' and can't be placed",
+                    row,
+                    self.headers[id],
+                    self.headers[key]
+                ));
+            }
+            if !first_rows.contains_key(row_id) {
+                first_rows.insert(row_id, row);
+                ids.push(row_id);
+            }
+            if !keys.contains(&row_key) {
+                keys.push(row_key);
+            }
+            if values.insert((row_id, row_key), cell(row, value)).is_some() {
+                return Err(anyhow!(
+                    "'{}' {} has more than one row with '{}' {}",
+                    self.headers[id],
+                    row_id,
+                    self.headers[key],
+                    row_key
+                ));
+            }
+        }
+
+        let mut pivoted = CSVData {
+            headers: kept
+                .iter()
+                .map(|column| self.headers[*column].clone())
+                .collect(),
+            ..Default::default()
+        };
+        for key in keys.iter() {
+            if pivoted.headers.iter().any(|header| header == key) {
+                return Err(anyhow!("'{}' is already a column header", key));
+            }
+            pivoted.headers.push(key.to_string());
+        }
+        pivoted.row_length = pivoted.headers.len();
+
+        for row_id in ids {
+            let first_row = first_rows[row_id];
+            pivoted.data.extend(
+                kept.iter()
+                    .map(|column| cell(first_row, *column).to_string()),
+            );
+            pivoted.data.extend(keys.iter().map(|key| {
+                values
+                    .get(&(row_id, *key))
+                    .copied()
+                    .unwrap_or_default()
+                    .to_string()
+            }));
+            pivoted.column_count += 1;
+        }
+        pivoted.infer_column_types();
+        Ok(pivoted)
+    }
 }
@@ -0,0 +1,444 @@
+use anyhow::{anyhow, Error, Result};
+
+/// Complementary error function, erfc(x) = 1 - erf(x).
+/// Chebyshev fit with fractional error everywhere less than 1.2e-7
+/// (Numerical Recipes, 2nd ed., section 6.2).
+pub fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let ans = t * f64::exp(
+        -z * z - 1.26551223
+            + t * (1.00002368
+                + t * (0.37409196
+                    + t * (0.09678418
+                        + t * (-0.18628806
+                            + t * (0.27886807
+                                + t * (-1.13520398
+                                    + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277)))))))),
+    );
+    if x >= 0.0 {
+        ans
+    } else {
+        2.0 - ans
+    }
+}
+
+/// P(Z <= z) for the standard normal distribution.
+pub fn standard_normal_cdf(z: f64) -> Result<f64, Error> {
+    // Phi(z) = erfc(-z / sqrt(2)) / 2
+    Ok(0.5 * erfc(-z / std::f64::consts::SQRT_2))
+}
+
+/// Two-tailed p-value for an observed z: 2 * (1 - Phi(|z|)).
+pub fn two_tailed_p_from_z(z: f64) -> Result<f64, Error> {
+    Ok(f64::min(1.0, 2.0 * (1.0 - standard_normal_cdf(z.abs())?)))
+}
+
+/// Inverse of the standard normal CDF: the z with Phi(z) = p, for 0 < p < 1
+/// (Wichura's AS 241, PPND16, accurate to about 1e-16).
+pub fn standard_normal_quantile(p: f64) -> Result<f64, Error> {
+    if !(p > 0.0 && p < 1.0) {
+        return Err(anyhow!("Normal quantiles are only defined for 0 < p < 1"));
+    }
+    let polynomial = |coefficients: &[f64], x: f64| {
+        coefficients
+            .iter()
+            .rev()
+            .fold(0.0, |sum, coefficient| sum * x + coefficient)
+    };
+
+    let q = p - 0.5;
+    if q.abs() <= 0.425 {
+        let r = 0.180625 - q * q;
+        return Ok(q * polynomial(
+            &[
+                3.387_132_872_796_366_5,
+                133.141_667_891_784_38,
+                1_971.590_950_306_551_3,
+                13_731.693_765_509_46,
+                45_921.953_931_549_87,
+                67_265.770_927_008_7,
+                33_430.575_583_588_13,
+                2_509.080_928_730_122_7,
+            ],
+            r,
+        ) / polynomial(
+            &[
+                1.0,
+                42.313_330_701_600_91,
+                687.187_007_492_057_9,
+                5_394.196_021_424_751,
+                21_213.794_301_586_597,
+                39_307.895_800_092_71,
+                28_729.085_735_721_943,
+                5_226.495_278_852_545,
+            ],
+            r,
+        ));
+    }
+
+    let r = f64::sqrt(-f64::ln(if q < 0.0 { p } else { 1.0 - p }));
+    let z = if r <= 5.0 {
+        let r = r - 1.6;
+        polynomial(
+            &[
+                1.423_437_110_749_683_5,
+                4.630_337_846_156_546,
+                5.769_497_221_460_691,
+                3.647_848_324_763_204_5,
+                1.270_458_252_452_368_4,
+                0.241_780_725_177_450_6,
+                0.022_723_844_989_269_184,
+                7.745_450_142_783_414e-4,
+            ],
+            r,
+        ) / polynomial(
+            &[
+                1.0,
+                2.053_191_626_637_759,
+                1.676_384_830_183_803_8,
+                0.689_767_334_985_1,
+                0.148_103_976_427_480_08,
+                0.015_198_666_563_616_457,
+                5.475_938_084_995_345e-4,
+                1.050_750_071_644_416_9e-9,
+            ],
+            r,
+        )
+    } else {
+        let r = r - 5.0;
+        polynomial(
+            &[
+                6.657_904_643_501_103,
+                5.463_784_911_164_114,
+                1.784_826_539_917_291_3,
+                0.296_560_571_828_504_9,
+                0.026_532_189_526_576_124,
+                0.001_242_660_947_388_078_4,
+                2.711_555_568_743_487_6e-5,
+                2.010_334_399_292_288_1e-7,
+            ],
+            r,
+        ) / polynomial(
+            &[
+                1.0,
+                0.599_832_206_555_888,
+                0.136_929_880_922_735_8,
+                0.014_875_361_290_850_615,
+                7.868_691_311_456_133e-4,
+                1.846_318_317_510_054_8e-5,
+                1.421_511_758_316_446e-7,
+                2.044_263_103_389_939_7e-15,
+            ],
+            r,
+        )
+    };
+    Ok(if q < 0.0 { -z } else { z })
+}
+
+/// Natural log of the gamma function for x > 0 (Lanczos approximation, g = 7, n = 9).
+pub fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // reflection: Gamma(x) Gamma(1 - x) = pi / sin(pi x)
+        f64::ln(std::f64::consts::PI / f64::sin(std::f64::consts::PI * x)) - ln_gamma(1.0 - x)
+    } else {
+        let x = x - 1.0;
+        let t = x + 7.5;
+        let series = COEFFICIENTS
+            .iter()
+            .enumerate()
+            .skip(1)
+            .fold(COEFFICIENTS[0], |sum, (i, c)| sum + c / (x + i as f64));
+        0.5 * f64::ln(2.0 * std::f64::consts::PI) + (x + 0.5) * f64::ln(t) - t + f64::ln(series)
+    }
+}
+
+/// Regularized lower incomplete gamma function P(a, x), using the series expansion
+/// for x < a + 1 and the continued fraction (modified Lentz) otherwise.
+pub fn regularized_gamma_p(a: f64, x: f64) -> Result<f64, Error> {
+    const MAX_ITERATIONS: usize = 500;
+    const EPSILON: f64 = 1e-14;
+    const TINY: f64 = 1e-300;
+
+    if a <= 0.0 || x < 0.0 {
+        return Err(anyhow!(
+            "Incomplete gamma requires a > 0 and x >= 0 (a = {}, x = {})",
+            a,
+            x
+        ));
+    }
+    if x == 0.0 {
+        return Ok(0.0);
+    }
+
+    let log_prefix = -x + a * f64::ln(x) - ln_gamma(a);
+
+    if x < a + 1.0 {
+        let mut term = 1.0 / a;
+        let mut sum = term;
+        let mut ap = a;
+        for _ in 0..MAX_ITERATIONS {
+            ap += 1.0;
+            term *= x / ap;
+            sum += term;
+            if term.abs() < sum.abs() * EPSILON {
+                break;
+            }
+        }
+        Ok(sum * f64::exp(log_prefix))
+    } else {
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / TINY;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..=MAX_ITERATIONS {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = b + an / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < EPSILON {
+                break;
+            }
+        }
+        Ok(1.0 - f64::exp(log_prefix) * h)
+    }
+}
+
+/// P(X <= x) for the chi-square distribution with `df` degrees of freedom.
+pub fn chi_square_cdf(x: f64, df: f64) -> Result<f64, Error> {
+    if x <= 0.0 {
+        return Ok(0.0);
+    }
+    regularized_gamma_p(df / 2.0, x / 2.0)
+}
+
+/// Right-tailed p-value for an observed chi-square statistic.
+pub fn chi_square_p(x: f64, df: f64) -> Result<f64, Error> {
+    Ok(1.0 - chi_square_cdf(x, df)?)
+}
+
+/// Regularized incomplete beta function I_x(a, b), evaluated by continued fraction
+/// (Numerical Recipes, 2nd ed., section 6.4).
+pub fn regularized_incomplete_beta(a: f64, b: f64, x: f64) -> Result<f64, Error> {
+    if !(0.0..=1.0).contains(&x) {
+        return Err(anyhow!("Incomplete beta requires 0 <= x <= 1 (x = {})", x));
+    }
+    if x == 0.0 || x == 1.0 {
+        return Ok(x);
+    }
+
+    let log_front =
+        ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * f64::ln(x) + b * f64::ln(1.0 - x);
+
+    // the continued fraction converges quickly for x < (a + 1) / (a + b + 2)
+    if x < (a + 1.0) / (a + b + 2.0) {
+        Ok(f64::exp(log_front) * beta_continued_fraction(a, b, x) / a)
+    } else {
+        Ok(1.0 - f64::exp(log_front) * beta_continued_fraction(b, a, 1.0 - x) / b)
+    }
+}
+
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const MAX_ITERATIONS: usize = 500;
+    const EPSILON: f64 = 1e-14;
+    const TINY: f64 = 1e-300;
+
+    let qab = a + b;
+    let qap = a + 1.0;
+    let qam = a - 1.0;
+    let mut c = 1.0;
+    let mut d = 1.0 - qab * x / qap;
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+
+    for m in 1..=MAX_ITERATIONS {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+
+        // even step
+        let aa = m * (b - m) * x / ((qam + m2) * (a + m2));
+        d = 1.0 + aa * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1.0 + aa / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        h *= d * c;
+
+        // odd step
+        let aa = -(a + m) * (qab + m) * x / ((a + m2) * (qap + m2));
+        d = 1.0 + aa * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1.0 + aa / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    h
+}
+
+/// P(T <= t) for Student's t distribution with `df` degrees of freedom.
+pub fn t_cdf(t: f64, df: f64) -> Result<f64, Error> {
+    // the tail area beyond |t| is I_{df / (df + t^2)}(df / 2, 1 / 2) / 2
+    let tail = 0.5 * regularized_incomplete_beta(df / 2.0, 0.5, df / (df + t * t))?;
+    if t >= 0.0 {
+        Ok(1.0 - tail)
+    } else {
+        Ok(tail)
+    }
+}
+
+/// Two-tailed p-value for an observed t with `df` degrees of freedom.
+pub fn two_tailed_p_from_t(t: f64, df: f64) -> Result<f64, Error> {
+    Ok(f64::min(1.0, 2.0 * (1.0 - t_cdf(t.abs(), df)?)))
+}
+
+/// P(F <= f) for the F distribution with `df_1` and `df_2` degrees of freedom.
+pub fn f_cdf(f: f64, df_1: f64, df_2: f64) -> Result<f64, Error> {
+    if f <= 0.0 {
+        return Ok(0.0);
+    }
+    regularized_incomplete_beta(df_1 / 2.0, df_2 / 2.0, df_1 * f / (df_1 * f + df_2))
+}
+
+/// Right-tailed p-value for an observed F.
+pub fn f_p(f: f64, df_1: f64, df_2: f64) -> Result<f64, Error> {
+    Ok(1.0 - f_cdf(f, df_1, df_2)?)
+}
+
+/// Which tail(s) of a distribution a test rejects in.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tails {
+    Left,
+    Right,
+    Two,
+}
+
+/// Critical values for a test at `alpha`, as (lower, upper), given the distribution's
+/// quantile function Q:
+///
+/// - left-tailed: reject in (-inf, Q(alpha)]
+/// - right-tailed: reject in [Q(1 - alpha), inf)
+/// - two-tailed: reject in (-inf, Q(alpha / 2)] and [Q(1 - alpha / 2), inf)
+pub fn critical_values(
+    quantile: impl Fn(f64) -> Result<f64, Error>,
+    alpha: f64,
+    tails: Tails,
+) -> Result<(Option<f64>, Option<f64>), Error> {
+    if !(alpha > 0.0 && alpha < 1.0) {
+        return Err(anyhow!("alpha must be between 0 and 1 (alpha = {})", alpha));
+    }
+    Ok(match tails {
+        Tails::Left => (Some(quantile(alpha)?), None),
+        Tails::Right => (None, Some(quantile(1.0 - alpha)?)),
+        Tails::Two => (
+            Some(quantile(alpha / 2.0)?),
+            Some(quantile(1.0 - alpha / 2.0)?),
+        ),
+    })
+}
+
+/// The t with P(T <= t) = p for Student's t distribution with `df` degrees of freedom.
+pub fn t_quantile(p: f64, df: f64) -> Result<f64, Error> {
+    check_quantile_arguments(p, &[df])?;
+    // symmetric about 0, so search the upper half only
+    if p < 0.5 {
+        Ok(-t_quantile(1.0 - p, df)?)
+    } else {
+        invert_cdf(|t| t_cdf(t, df), p)
+    }
+}
+
+/// The x with P(X <= x) = p for the chi-square distribution with `df` degrees of freedom.
+pub fn chi_square_quantile(p: f64, df: f64) -> Result<f64, Error> {
+    check_quantile_arguments(p, &[df])?;
+    invert_cdf(|x| chi_square_cdf(x, df), p)
+}
+
+/// The f with P(F <= f) = p for the F distribution with `df_1` and `df_2` degrees of freedom.
+pub fn f_quantile(p: f64, df_1: f64, df_2: f64) -> Result<f64, Error> {
+    check_quantile_arguments(p, &[df_1, df_2])?;
+    invert_cdf(|f| f_cdf(f, df_1, df_2), p)
+}
+
+fn check_quantile_arguments(p: f64, degrees_of_freedom: &[f64]) -> Result<(), Error> {
+    if !(p > 0.0 && p < 1.0) {
+        return Err(anyhow!(
+            "Quantiles are only defined for 0 < p < 1 (p = {})",
+            p
+        ));
+    }
+    if degrees_of_freedom
+        .iter()
+        .any(|df| df.is_nan() || *df <= 0.0)
+    {
+        return Err(anyhow!("Degrees of freedom must be positive"));
+    }
+    Ok(())
+}
+
+/// Solves cdf(x) = p for x >= 0 by bisection, doubling the upper bracket until it
+/// holds p. The CDFs here are continuous and increasing, so the root is unique.
+fn invert_cdf(cdf: impl Fn(f64) -> Result<f64, Error>, p: f64) -> Result<f64, Error> {
+    const MAX_UPPER: f64 = 1e12;
+    const MAX_ITERATIONS: usize = 200;
+    const TOLERANCE: f64 = 1e-12;
+
+    let mut lower = 0.0;
+    let mut upper = 1.0;
+    while cdf(upper)? < p {
+        lower = upper;
+        upper *= 2.0;
+        if upper > MAX_UPPER {
+            return Err(anyhow!("Quantile for p = {} is out of range", p));
+        }
+    }
+
+    for _ in 0..MAX_ITERATIONS {
+        let middle = (lower + upper) / 2.0;
+        if cdf(middle)? < p {
+            lower = middle;
+        } else {
+            upper = middle;
+        }
+        if upper - lower <= TOLERANCE * f64::max(1.0, upper) {
+            break;
+        }
+    }
+    Ok((lower + upper) / 2.0)
+}
@@ -0,0 +1,319 @@
+use crate::functions::csv::{is_missing, CSVData};
+use anyhow::{anyhow, Error, Result};
+use std::collections::HashMap;
+
+/// A row predicate such as `GPA > 3 && (School == 'CSU' || !(Netflix <= 5))`.
+///
+/// Comparisons (`== != < <= > >=`) take a column header or a literal on either side and
+/// combine with `&&`, `||`, `!`, and parentheses. Values compare as numbers when both
+/// sides are numbers and as text otherwise. Quote text in '...' or "...", and a header
+/// containing spaces or operators in backticks. A comparison with a missing cell is
+/// false, so `x <= 3` leaves out rows missing x but `!(x > 3)` keeps them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    expression: Expression,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expression {
+    And(Box<Expression>, Box<Expression>),
+    Or(Box<Expression>, Box<Expression>),
+    Not(Box<Expression>),
+    Compare(Operand, Comparison, Operand),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Column(String),
+    Literal(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Column(String),  // a bare word or `quoted header`
+    Literal(String), // a number or 'quoted text'
+    Comparison(Comparison),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+impl Filter {
+    pub fn parse(filter: &str) -> Result<Filter, Error> {
+        let tokens = tokenize(filter)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            position: 0,
+        };
+        let expression = parser.or()?;
+        if let Some(token) = parser.peek() {
+            return Err(anyhow!(
+                "Unexpected {} in filter '{}'",
+                describe(token),
+                filter
+            ));
+        }
+        Ok(Filter { expression })
+    }
+
+    /// 0-based rows the filter holds for.
+    pub fn matching_rows(&self, csv_data: &CSVData) -> Result<Vec<usize>, Error> {
+        let mut columns = HashMap::new();
+        self.expression.resolve(csv_data, &mut columns)?;
+        Ok((0..csv_data.column_count)
+            .filter(|row| self.expression.holds(csv_data, *row, &columns))
+            .collect())
+    }
+}
+
+impl Expression {
+    /// Finds the column of every header the expression names.
+    fn resolve<'a>(
+        &'a self,
+        csv_data: &CSVData,
+        columns: &mut HashMap<&'a str, usize>,
+    ) -> Result<(), Error> {
+        match self {
+            Expression::And(left, right) | Expression::Or(left, right) => {
+                left.resolve(csv_data, columns)?;
+                right.resolve(csv_data, columns)
+            }
+            Expression::Not(inner) => inner.resolve(csv_data, columns),
+            Expression::Compare(left, _, right) => {
+                for operand in [left, right] {
+                    if let Operand::Column(name) = operand {
+                        let column = csv_data
+                            .headers
+                            .iter()
+                            .position(|header| header == name)
+                            .ok_or_else(|| {
+                                anyhow!(
+                                    "'{}' in the filter is not a column header (headers are {:?}); quote text values",
+                                    name,
+                                    csv_data.headers
+                                )
+                            })?;
+                        columns.insert(name.as_str(), column);
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn holds(&self, csv_data: &CSVData, row: usize, columns: &HashMap<&str, usize>) -> bool {
+        match self {
+            Expression::And(left, right) => {
+                left.holds(csv_data, row, columns) && right.holds(csv_data, row, columns)
+            }
+            Expression::Or(left, right) => {
+                left.holds(csv_data, row, columns) || right.holds(csv_data, row, columns)
+            }
+            Expression::Not(inner) => !inner.holds(csv_data, row, columns),
+            Expression::Compare(left, comparison, right) => {
+                let value = |operand: &Operand| match operand {
+                    Operand::Column(name) => {
+                        let cell = csv_data.data
+                            [csv_data.row_length * row + columns[name.as_str()]]
+                        .as_str();
+                        (!is_missing(cell)).then_some(cell.to_string())
+                    }
+                    Operand::Literal(literal) => Some(literal.clone()),
+                };
+                match (value(left), value(right)) {
+                    (Some(left), Some(right)) => comparison.holds(&left, &right),
+                    _ => false,
+                }
+            }
+        }
+    }
+}
+
+impl Comparison {
+    fn holds(&self, left: &str, right: &str) -> bool {
+        let ordering = match (left.parse::<f64>(), right.parse::<f64>()) {
+            (Ok(left), Ok(right)) => left.total_cmp(&right),
+            _ => left.cmp(right),
+        };
+        match self {
+            Comparison::Equal => ordering.is_eq(),
+            Comparison::NotEqual => ordering.is_ne(),
+            Comparison::Less => ordering.is_lt(),
+            Comparison::LessOrEqual => ordering.is_le(),
+            Comparison::Greater => ordering.is_gt(),
+            Comparison::GreaterOrEqual => ordering.is_ge(),
+        }
+    }
+}
+
+/// Recursive descent over the tokens: `||` binds loosest, then `&&`, then `!`.
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        self.position += 1;
+        self.tokens.get(self.position - 1)
+    }
+
+    fn or(&mut self) -> Result<Expression, Error> {
+        let mut expression = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.position += 1;
+            expression = Expression::Or(Box::new(expression), Box::new(self.and()?));
+        }
+        Ok(expression)
+    }
+
+    fn and(&mut self) -> Result<Expression, Error> {
+        let mut expression = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.position += 1;
+            expression = Expression::And(Box::new(expression), Box::new(self.unary()?));
+        }
+        Ok(expression)
+    }
+
+    fn unary(&mut self) -> Result<Expression, Error> {
+        match self.peek() {
+            Some(Token::Not) => {
+                self.position += 1;
+                Ok(Expression::Not(Box::new(self.unary()?)))
+            }
+            Some(Token::Open) => {
+                self.position += 1;
+                let expression = self.or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(expression),
+                    _ => Err(anyhow!("Missing ')' in filter")),
+                }
+            }
+            _ => {
+                let left = self.operand()?;
+                let comparison = match self.next() {
+                    Some(Token::Comparison(comparison)) => *comparison,
+                    Some(token) => {
+                        return Err(anyhow!(
+                            "Expected a comparison (== != < <= > >=), found {}",
+                            describe(token)
+                        ))
+                    }
+                    None => {
+                        return Err(anyhow!(
+                            "Expected a comparison (== != < <= > >=) at the end of the filter"
+                        ))
+                    }
+                };
+                Ok(Expression::Compare(left, comparison, self.operand()?))
+            }
+        }
+    }
+
+    fn operand(&mut self) -> Result<Operand, Error> {
+        match self.next() {
+            Some(Token::Column(name)) => Ok(Operand::Column(name.clone())),
+            Some(Token::Literal(literal)) => Ok(Operand::Literal(literal.clone())),
+            Some(token) => Err(anyhow!(
+                "Expected a column or a value, found {}",
+                describe(token)
+            )),
+            None => Err(anyhow!(
+                "Expected a column or a value at the end of the filter"
+            )),
+        }
+    }
+}
+
+fn tokenize(filter: &str) -> Result<Vec<Token>, Error> {
+    let chars = filter.chars().collect::<Vec<char>>();
+    let mut tokens = vec![];
+    let mut index = 0;
+    while index < chars.len() {
+        let c = chars[index];
+        let next = chars.get(index + 1).copied();
+        let (token, length) = match (c, next) {
+            (c, _) if c.is_whitespace() => {
+                index += 1;
+                continue;
+            }
+            ('&', Some('&')) => (Token::And, 2),
+            ('|', Some('|')) => (Token::Or, 2),
+            ('=', Some('=')) => (Token::Comparison(Comparison::Equal), 2),
+            ('!', Some('=')) => (Token::Comparison(Comparison::NotEqual), 2),
+            ('<', Some('=')) => (Token::Comparison(Comparison::LessOrEqual), 2),
+            ('>', Some('=')) => (Token::Comparison(Comparison::GreaterOrEqual), 2),
+            ('<', _) => (Token::Comparison(Comparison::Less), 1),
+            ('>', _) => (Token::Comparison(Comparison::Greater), 1),
+            ('!', _) => (Token::Not, 1),
+            ('(', _) => (Token::Open, 1),
+            (')', _) => (Token::Close, 1),
+            ('\'' | '"' | '`', _) => {
+                let end = chars[index + 1..]
+                    .iter()
+                    .position(|&closing| closing == c)
+                    .ok_or_else(|| anyhow!("Unclosed {} in filter '{}'", c, filter))?;
+                let text = chars[index + 1..index + 1 + end].iter().collect::<String>();
+                let token = match c {
+                    '`' => Token::Column(text),
+                    _ => Token::Literal(text),
+                };
+                (token, end + 2)
+            }
+            (c, _) if is_word_char(c) || c == '-' => {
+                let length = chars[index + 1..]
+                    .iter()
+                    .take_while(|&&c| is_word_char(c))
+                    .count()
+                    + 1;
+                let word = chars[index..index + length].iter().collect::<String>();
+                let token = match word.parse::<f64>() {
+                    Ok(_) => Token::Literal(word),
+                    Err(_) => Token::Column(word),
+                };
+                (token, length)
+            }
+            (c, _) => return Err(anyhow!("Unexpected '{}' in filter '{}'", c, filter)),
+        };
+        tokens.push(token);
+        index += length;
+    }
+    if tokens.is_empty() {
+        return Err(anyhow!("The filter is empty"));
+    }
+    Ok(tokens)
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '.'
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Column(name) => format!("'{}'", name),
+        Token::Literal(literal) => format!("'{}'", literal),
+        Token::Comparison(_) => String::from("a comparison"),
+        Token::And => String::from("'&&'"),
+        Token::Or => String::from("'||'"),
+        Token::Not => String::from("'!'"),
+        Token::Open => String::from("'('"),
+        Token::Close => String::from("')'"),
+    }
+}
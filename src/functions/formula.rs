@@ -0,0 +1,591 @@
+use crate::functions::csv::{is_missing, CSVData};
+use crate::reporter::report;
+use anyhow::{anyhow, Error, Result};
+use std::ops::Range;
+
+/// An R-style model formula such as `GPA ~ School + Netflix + School:Netflix`.
+///
+/// `a:b` is the interaction of `a` and `b`, `a*b` expands to `a + b + a:b`, and `a^2`
+/// is the square of a continuous `a` (any whole power of two or more). Wrap a numeric
+/// column in `factor(...)` to treat it as categorical, and a header containing
+/// `~ + : * ^ ( )` in backticks. The intercept is always included.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Formula {
+    pub response: Variable,
+    pub terms: Vec<Term>, // main effects first, then interactions by order
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variable {
+    pub name: String,
+    pub factor: bool, // written as factor(name)
+    pub power: u32,   // written as name^power, 1 otherwise
+}
+
+/// The variables multiplied together in one term; a main effect has just one.
+pub type Term = Vec<Variable>;
+
+/// The response and design matrix built from a formula. Categorical variables are
+/// dummy coded against their first level; interaction columns are the products of
+/// their variables' columns.
+#[derive(Debug, Clone, Default)]
+pub struct Design {
+    pub response_name: String,
+    pub response: Vec<f64>,
+    pub columns: Vec<DesignColumn>,
+    pub terms: Vec<DesignTerm>,
+    pub dropped_rows: Vec<usize>, // 0-based rows left out for missing values
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DesignColumn {
+    pub name: String,
+    pub values: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DesignTerm {
+    pub name: String,
+    pub columns: Range<usize>, // indices into Design::columns
+}
+
+/// How a single variable resolves against the data.
+#[derive(Debug, Clone)]
+struct ResolvedVariable {
+    column: usize,
+    levels: Option<Vec<String>>, // None for continuous variables
+}
+
+impl Formula {
+    pub fn parse(formula: &str) -> Result<Formula, Error> {
+        let sides = split_top_level(formula, '~')?;
+        if sides.len() != 2 {
+            return Err(anyhow!(
+                "A formula needs exactly one '~' between the response and the predictors: '{}'",
+                formula
+            ));
+        }
+        let response = parse_variable(sides[0])?;
+        if response.factor {
+            return Err(anyhow!(
+                "The response '{}' must be continuous, not a factor",
+                response.name
+            ));
+        }
+        if response.power != 1 {
+            return Err(anyhow!(
+                "The response '{}' cannot be raised to a power",
+                response.name
+            ));
+        }
+
+        let mut terms: Vec<Term> = vec![];
+        for summand in split_top_level(sides[1], '+')? {
+            let crossed = split_top_level(summand, '*')?
+                .into_iter()
+                .map(|piece| {
+                    split_top_level(piece, ':')?
+                        .into_iter()
+                        .map(parse_variable)
+                        .collect::<Result<Term, Error>>()
+                })
+                .collect::<Result<Vec<Term>, Error>>()?;
+
+            // a*b*c is every non-empty combination of its pieces
+            for mask in 1..(1usize << crossed.len()) {
+                let mut term: Term = vec![];
+                for (index, piece) in crossed.iter().enumerate() {
+                    if mask & (1 << index) != 0 {
+                        for variable in piece {
+                            if !term.contains(variable) {
+                                term.push(variable.clone());
+                            }
+                        }
+                    }
+                }
+                if !terms.iter().any(|existing| same_term(existing, &term)) {
+                    terms.push(term);
+                }
+            }
+        }
+
+        if terms
+            .iter()
+            .flatten()
+            .any(|variable| variable.name == response.name)
+        {
+            return Err(anyhow!(
+                "The response '{}' cannot also be a predictor",
+                response.name
+            ));
+        }
+        terms.sort_by_key(|term| term.len());
+
+        Ok(Formula { response, terms })
+    }
+
+    /// The formula with every term written out, e.g. `y ~ a + b + a:b`.
+    pub fn expanded(&self) -> String {
+        format!(
+            "{} ~ {}",
+            self.response.name,
+            self.terms
+                .iter()
+                .map(term_name)
+                .collect::<Vec<String>>()
+                .join(" + ")
+        )
+    }
+
+    /// When the formula is a single categorical predictor, its 0-based column index and
+    /// level count, which is the one way ANOVA layout.
+    pub fn one_way_factor(&self, csv_data: &CSVData) -> Result<Option<(usize, usize)>, Error> {
+        if self.terms.len() != 1 || self.terms[0].len() != 1 {
+            return Ok(None);
+        }
+        let resolved = resolve_variable(csv_data, &self.terms[0][0], &[])?;
+        Ok(resolved
+            .levels
+            .map(|levels| (resolved.column, levels.len())))
+    }
+
+    /// 0-based indices of the (continuous, categorical) columns the formula reads, the
+    /// response first. Names that are not headers are skipped; `design` reports them.
+    pub fn columns(&self, csv_data: &CSVData) -> (Vec<usize>, Vec<usize>) {
+        let (mut continuous, mut categorical) = (vec![], vec![]);
+        for variable in std::iter::once(&self.response).chain(self.terms.iter().flatten()) {
+            if let Ok(resolved) = resolve_variable(csv_data, variable, &[]) {
+                let columns = match resolved.levels {
+                    Some(_) => &mut categorical,
+                    None => &mut continuous,
+                };
+                if !columns.contains(&resolved.column) {
+                    columns.push(resolved.column);
+                }
+            }
+        }
+        (continuous, categorical)
+    }
+
+    /// 0-based column index of the response.
+    pub fn response_column(&self, csv_data: &CSVData) -> Result<usize, Error> {
+        column_index(csv_data, &self.response.name)
+    }
+
+    /// 0-based rows with a missing cell in any column the formula reads.
+    pub fn missing_rows(&self, csv_data: &CSVData) -> Result<Vec<usize>, Error> {
+        let mut columns = vec![self.response_column(csv_data)?];
+        for variable in self.terms.iter().flatten() {
+            columns.push(column_index(csv_data, &variable.name)?);
+        }
+        Ok((0..csv_data.column_count)
+            .filter(|row| {
+                columns
+                    .iter()
+                    .any(|column| is_missing(&csv_data.data[csv_data.row_length * row + column]))
+            })
+            .collect())
+    }
+
+    /// Builds the design matrix, leaving out rows with a missing value in any variable.
+    pub fn design(&self, csv_data: &CSVData) -> Result<Design, Error> {
+        let (dropped_rows, rows) = self.complete_rows(csv_data)?;
+
+        let response_column = self.response_column(csv_data)?;
+        let response = rows
+            .iter()
+            .map(|row| {
+                csv_data
+                    .get_datum::<f64>(*row, response_column, None)
+                    .map_err(|_| {
+                        anyhow!(
+                            "The response '{}' must be continuous, but row {} is '{}'",
+                            self.response.name,
+                            row,
+                            csv_data.data[csv_data.row_length * row + response_column]
+                        )
+                    })
+            })
+            .collect::<Result<Vec<f64>, Error>>()?;
+
+        let mut design = Design {
+            response_name: self.response.name.clone(),
+            response,
+            dropped_rows,
+            ..Default::default()
+        };
+        self.add_terms(csv_data, &rows, &mut design)?;
+        Ok(design)
+    }
+
+    /// Builds the design matrix for an ordinal response, whose levels run from lowest to
+    /// highest in `order`, or in numeric order when `order` is empty and every level is a
+    /// number. The response holds each row's 0-based position among the returned levels.
+    pub fn ordinal_design(
+        &self,
+        csv_data: &CSVData,
+        order: &[String],
+    ) -> Result<(Design, Vec<String>), Error> {
+        let (dropped_rows, rows) = self.complete_rows(csv_data)?;
+
+        let response_column = self.response_column(csv_data)?;
+        let values = rows
+            .iter()
+            .map(|row| csv_data.data[csv_data.row_length * row + response_column].as_str())
+            .collect::<Vec<&str>>();
+        let levels = if order.is_empty() {
+            let mut levels = values
+                .iter()
+                .map(|value| {
+                    value.parse::<f64>().map_err(|_| {
+                        anyhow!(
+                            "'{}' is not a number, so give the order of the levels of '{}' from lowest to highest (e.g. --levels \"Disagree,Neutral,Agree\")",
+                            value,
+                            self.response.name
+                        )
+                    })
+                })
+                .collect::<Result<Vec<f64>, Error>>()?;
+            levels.sort_by(f64::total_cmp);
+            levels.dedup();
+            levels
+                .into_iter()
+                .map(|level| {
+                    values
+                        .iter()
+                        .find(|value| value.parse::<f64>().ok() == Some(level))
+                        .unwrap()
+                        .to_string()
+                })
+                .collect::<Vec<String>>()
+        } else {
+            order.to_vec()
+        };
+
+        let response = values
+            .iter()
+            .zip(rows.iter())
+            .map(|(value, row)| {
+                levels
+                    .iter()
+                    .position(|level| level == value || numerically_equal(level, value))
+                    .map(|position| position as f64)
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "Row {} of '{}' is '{}', which is not one of the levels {:?}",
+                            row,
+                            self.response.name,
+                            value,
+                            levels
+                        )
+                    })
+            })
+            .collect::<Result<Vec<f64>, Error>>()?;
+        if let Some(level) = levels
+            .iter()
+            .enumerate()
+            .find(|(position, _)| !response.contains(&(*position as f64)))
+            .map(|(_, level)| level)
+        {
+            return Err(anyhow!(
+                "No row of '{}' is '{}'; every level needs at least one row",
+                self.response.name,
+                level
+            ));
+        }
+
+        let mut design = Design {
+            response_name: self.response.name.clone(),
+            response,
+            dropped_rows,
+            ..Default::default()
+        };
+        self.add_terms(csv_data, &rows, &mut design)?;
+        Ok((design, levels))
+    }
+
+    /// The 0-based rows with a missing value in any variable, reported, and the rest.
+    fn complete_rows(&self, csv_data: &CSVData) -> Result<(Vec<usize>, Vec<usize>), Error> {
+        let dropped_rows = self.missing_rows(csv_data)?;
+        if !dropped_rows.is_empty() {
+            report!(
+                "Leaving out {} row(s) with missing values: {:?}",
+                dropped_rows.len(),
+                dropped_rows
+            );
+        }
+        // missing_rows is ascending
+        let rows = (0..csv_data.column_count)
+            .filter(|row| dropped_rows.binary_search(row).is_err())
+            .collect::<Vec<usize>>();
+        Ok((dropped_rows, rows))
+    }
+
+    /// Appends the columns of every term, over `rows`, to the design.
+    fn add_terms(
+        &self,
+        csv_data: &CSVData,
+        rows: &[usize],
+        design: &mut Design,
+    ) -> Result<(), Error> {
+        for term in &self.terms {
+            // start from a single column of 1s and multiply in each variable's columns
+            let mut term_columns = vec![DesignColumn {
+                name: String::new(),
+                values: vec![1.0; rows.len()],
+            }];
+            for variable in term {
+                let resolved = resolve_variable(csv_data, variable, rows)?;
+                let variable_columns = variable_columns(csv_data, variable, &resolved, rows)?;
+                term_columns = term_columns
+                    .iter()
+                    .flat_map(|left| {
+                        variable_columns.iter().map(move |right| DesignColumn {
+                            name: if left.name.is_empty() {
+                                right.name.clone()
+                            } else {
+                                format!("{}:{}", left.name, right.name)
+                            },
+                            values: left
+                                .values
+                                .iter()
+                                .zip(right.values.iter())
+                                .map(|(l, r)| l * r)
+                                .collect(),
+                        })
+                    })
+                    .collect();
+            }
+
+            let start = design.columns.len();
+            design.columns.extend(term_columns);
+            design.terms.push(DesignTerm {
+                name: term_name(term),
+                columns: start..design.columns.len(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Whether two cells hold the same number, such as "1" and "1.0".
+fn numerically_equal(a: &str, b: &str) -> bool {
+    matches!((a.parse::<f64>(), b.parse::<f64>()), (Ok(a), Ok(b)) if a == b)
+}
+
+fn term_name(term: &Term) -> String {
+    term.iter()
+        .map(variable_name)
+        .collect::<Vec<String>>()
+        .join(":")
+}
+
+/// The variable as written in a formula, less any factor(): `a` or `a^2`.
+fn variable_name(variable: &Variable) -> String {
+    match variable.power {
+        1 => variable.name.clone(),
+        power => format!("{}^{}", variable.name, power),
+    }
+}
+
+fn same_term(a: &Term, b: &Term) -> bool {
+    a.len() == b.len() && a.iter().all(|variable| b.contains(variable))
+}
+
+/// Splits on `separator` outside of backticks and parentheses, trimming each piece.
+fn split_top_level(input: &str, separator: char) -> Result<Vec<&str>, Error> {
+    let mut pieces = vec![];
+    let mut depth = 0usize;
+    let mut quoted = false;
+    let mut start = 0;
+    for (index, character) in input.char_indices() {
+        match character {
+            '`' => quoted = !quoted,
+            '(' if !quoted => depth += 1,
+            ')' if !quoted => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| anyhow!("Unbalanced ')' in formula '{}'", input))?
+            }
+            _ if character == separator && !quoted && depth == 0 => {
+                pieces.push(input[start..index].trim());
+                start = index + character.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    if quoted || depth != 0 {
+        return Err(anyhow!("Unclosed '`' or '(' in formula '{}'", input));
+    }
+    pieces.push(input[start..].trim());
+
+    if pieces.iter().any(|piece| piece.is_empty()) {
+        return Err(anyhow!(
+            "Missing a variable around '{}' in formula '{}'",
+            separator,
+            input
+        ));
+    }
+    Ok(pieces)
+}
+
+fn parse_variable(input: &str) -> Result<Variable, Error> {
+    let input = input.trim();
+    // a trailing ^n outside of backticks is a power
+    let (input, power) = match input.rsplit_once('^') {
+        Some((base, exponent)) if base.matches('`').count() % 2 == 0 => {
+            match exponent.trim().parse::<u32>() {
+                Ok(power) if power >= 2 => (base.trim(), power),
+                _ => {
+                    return Err(anyhow!(
+                    "'{}' is not a power; write a whole number of two or more after '^', e.g. x^2",
+                    input
+                ))
+                }
+            }
+        }
+        _ => (input, 1),
+    };
+    let (inner, factor) = match input
+        .strip_prefix("factor(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        Some(inner) => (inner.trim(), true),
+        None => (input, false),
+    };
+    let name = match inner
+        .strip_prefix('`')
+        .and_then(|rest| rest.strip_suffix('`'))
+    {
+        Some(name) => name,
+        None if inner.contains(['`', '(', ')']) => {
+            return Err(anyhow!("Could not read '{}' as a variable name", input))
+        }
+        None => inner,
+    };
+    if name.is_empty() {
+        return Err(anyhow!("Empty variable name in '{}'", input));
+    }
+    if factor && power != 1 {
+        return Err(anyhow!(
+            "'{}' is a factor, so it cannot be raised to a power",
+            name
+        ));
+    }
+    Ok(Variable {
+        name: name.to_string(),
+        factor,
+        power,
+    })
+}
+
+fn column_index(csv_data: &CSVData, name: &str) -> Result<usize, Error> {
+    csv_data
+        .headers
+        .iter()
+        .position(|header| header == name)
+        .ok_or_else(|| {
+            anyhow!(
+                "'{}' is not a column header (headers are {:?})",
+                name,
+                csv_data.headers
+            )
+        })
+}
+
+/// Finds the variable's column and, when it is categorical (written as a factor, or
+/// holding any value that is not a number), its sorted levels over `rows` (all rows
+/// when empty), led by the column's reference level when one is set.
+fn resolve_variable(
+    csv_data: &CSVData,
+    variable: &Variable,
+    rows: &[usize],
+) -> Result<ResolvedVariable, Error> {
+    let column = column_index(csv_data, &variable.name)?;
+    let all_rows = (0..csv_data.column_count).collect::<Vec<usize>>();
+    let rows = if rows.is_empty() { &all_rows } else { rows };
+    let values = rows
+        .iter()
+        .map(|row| csv_data.data[csv_data.row_length * row + column].clone())
+        .filter(|value| !is_missing(value))
+        .collect::<Vec<String>>();
+
+    let numeric = values.iter().all(|value| value.parse::<f64>().is_ok());
+    if numeric && !variable.factor {
+        return Ok(ResolvedVariable {
+            column,
+            levels: None,
+        });
+    }
+
+    if variable.power != 1 {
+        return Err(anyhow!(
+            "'{}' is categorical, so it cannot be raised to a power",
+            variable.name
+        ));
+    }
+    let mut levels = values;
+    if numeric {
+        levels.sort_by(|a, b| {
+            a.parse::<f64>()
+                .unwrap()
+                .total_cmp(&b.parse::<f64>().unwrap())
+        });
+    } else {
+        levels.sort();
+    }
+    levels.dedup();
+    if let Some(reference) = csv_data.reference_levels.get(&column) {
+        if let Some(position) = levels.iter().position(|level| level == reference) {
+            let reference = levels.remove(position);
+            levels.insert(0, reference);
+        }
+    }
+    Ok(ResolvedVariable {
+        column,
+        levels: Some(levels),
+    })
+}
+
+/// One column for a continuous variable (raised to its power), or one dummy column per
+/// non-reference level.
+fn variable_columns(
+    csv_data: &CSVData,
+    variable: &Variable,
+    resolved: &ResolvedVariable,
+    rows: &[usize],
+) -> Result<Vec<DesignColumn>, Error> {
+    match &resolved.levels {
+        None => Ok(vec![DesignColumn {
+            name: variable_name(variable),
+            values: rows
+                .iter()
+                .map(|row| {
+                    csv_data
+                        .get_datum::<f64>(*row, resolved.column, None)
+                        .map(|value| value.powi(variable.power as i32))
+                })
+                .collect::<Result<Vec<f64>, _>>()?,
+        }]),
+        Some(levels) if levels.len() < 2 => Err(anyhow!(
+            "'{}' needs at least two levels to be a predictor",
+            variable.name
+        )),
+        Some(levels) => Ok(levels[1..]
+            .iter()
+            .map(|level| DesignColumn {
+                name: format!("{}[{}]", variable.name, level),
+                values: rows
+                    .iter()
+                    .map(|row| {
+                        if csv_data.data[csv_data.row_length * row + resolved.column] == *level {
+                            1.0
+                        } else {
+                            0.0
+                        }
+                    })
+                    .collect(),
+            })
+            .collect()),
+    }
+}
@@ -1,3 +1,950 @@
+use crate::data_types::data_array::{CategoricalDataArray, ContinuousDataArray};
+use crate::functions::canvas::{Anchor, Canvas, Color};
+use crate::functions::distributions::standard_normal_quantile;
+use crate::functions::stats_math::quantile;
+use crate::logging;
+use crate::reporter::report;
+use anyhow::{anyhow, Error, Result};
+use charming::component::{Axis, Title};
+use charming::datatype::{Dataset, Transform};
+use charming::element::{AxisType, Tooltip, Trigger};
+use charming::series::{Bar, Boxplot, Line, Scatter};
+use charming::{Chart, HtmlRenderer};
+use clap::ValueEnum;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+const PLOT_WIDTH: u64 = 1000;
+const PLOT_HEIGHT: u64 = 800;
+
+// the plotting area of a terminal chart, in characters
+const TERMINAL_WIDTH: usize = 60;
+const TERMINAL_HEIGHT: usize = 20;
+const BAR_WIDTH: usize = 40;
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlotBackend {
+    /// Interactive charts written as HTML files
+    Html,
+    /// Text charts printed with the rest of the output, for terminals without a browser
+    Terminal,
+}
+
+static PLOT_BACKEND: RwLock<PlotBackend> = RwLock::new(PlotBackend::Html);
+
+pub fn set_plot_backend(backend: PlotBackend) {
+    *PLOT_BACKEND.write().unwrap() = backend;
+}
+
+pub fn plot_backend() -> PlotBackend {
+    *PLOT_BACKEND.read().unwrap()
+}
+
+/// The `Graph` of the selected backend. HTML charts are written to `directory`.
+pub fn graph(directory: &Path) -> Box<dyn Graph> {
+    match plot_backend() {
+        PlotBackend::Html => Box::new(HtmlGraph {
+            directory: directory.to_path_buf(),
+        }),
+        PlotBackend::Terminal => Box::new(TerminalGraph),
+    }
+}
+
+/// Draws the charts built in this module. `stem` names the chart in file-name form, and
+/// each method returns the file it wrote, if it wrote one.
+pub trait Graph {
+    /// One bar of height `values[i]` per label, side by side.
+    fn bar(
+        &self,
+        stem: &str,
+        title: &str,
+        x_name: &str,
+        y_name: &str,
+        labels: &[String],
+        values: &[usize],
+    ) -> Result<Option<PathBuf>, Error>;
+
+    /// `points` with a straight reference line between the two points of `reference`.
+    fn scatter(
+        &self,
+        stem: &str,
+        title: &str,
+        x_name: &str,
+        y_name: &str,
+        points: &[(f64, f64)],
+        reference: Option<[(f64, f64); 2]>,
+    ) -> Result<Option<PathBuf>, Error>;
+
+    /// One box of quartiles, 1.5 IQR whiskers, and outliers per group of values.
+    fn boxplot(
+        &self,
+        stem: &str,
+        title: &str,
+        y_name: &str,
+        names: &[String],
+        groups: &[Vec<f64>],
+    ) -> Result<Option<PathBuf>, Error>;
+}
+
+/// Charts rendered with charming as HTML files in `directory`.
+pub struct HtmlGraph {
+    pub directory: PathBuf,
+}
+
+impl HtmlGraph {
+    fn save(&self, stem: &str, title: &str, chart: Chart) -> Result<Option<PathBuf>, Error> {
+        fs::create_dir_all(&self.directory)?;
+        let path = self.directory.join(format!("{}.html", stem));
+        HtmlRenderer::new(title, PLOT_WIDTH, PLOT_HEIGHT).save(&chart, &path)?;
+        Ok(Some(path))
+    }
+}
+
+impl Graph for HtmlGraph {
+    fn bar(
+        &self,
+        stem: &str,
+        title: &str,
+        x_name: &str,
+        y_name: &str,
+        labels: &[String],
+        values: &[usize],
+    ) -> Result<Option<PathBuf>, Error> {
+        let chart = Chart::new()
+            .title(Title::new().text(title))
+            .tooltip(Tooltip::new().trigger(Trigger::Item))
+            .x_axis(
+                Axis::new()
+                    .type_(AxisType::Category)
+                    .name(x_name)
+                    .data(labels.to_vec()),
+            )
+            .y_axis(Axis::new().type_(AxisType::Value).name(y_name))
+            .series(Bar::new().data(values.iter().map(|value| *value as i64).collect()));
+        self.save(stem, title, chart)
+    }
+
+    fn scatter(
+        &self,
+        stem: &str,
+        title: &str,
+        x_name: &str,
+        y_name: &str,
+        points: &[(f64, f64)],
+        reference: Option<[(f64, f64); 2]>,
+    ) -> Result<Option<PathBuf>, Error> {
+        let mut chart = Chart::new()
+            .title(Title::new().text(title))
+            .tooltip(Tooltip::new().trigger(Trigger::Item))
+            .x_axis(Axis::new().type_(AxisType::Value).name(x_name).scale(true))
+            .y_axis(Axis::new().type_(AxisType::Value).name(y_name).scale(true))
+            .series(
+                Scatter::new()
+                    .symbol_size(8)
+                    .data(points.iter().map(|(x, y)| vec![*x, *y]).collect()),
+            );
+        if let Some(reference) = reference {
+            chart = chart.series(
+                Line::new()
+                    .show_symbol(false)
+                    .data(reference.iter().map(|(x, y)| vec![*x, *y]).collect()),
+            );
+        }
+        self.save(stem, title, chart)
+    }
+
+    fn boxplot(
+        &self,
+        stem: &str,
+        title: &str,
+        y_name: &str,
+        names: &[String],
+        groups: &[Vec<f64>],
+    ) -> Result<Option<PathBuf>, Error> {
+        // the transform labels each box by its row index, so map the index back to its name
+        let summary = format!(
+            "{{type: 'boxplot', config: {{itemNameFormatter: function (params) {{ return {}[params.value]; }}}}}}",
+            serde_json::to_string(names)?
+        );
+        let chart = Chart::new()
+            .title(Title::new().text(title))
+            .tooltip(Tooltip::new().trigger(Trigger::Item))
+            .dataset(
+                Dataset::new()
+                    .source(groups.to_vec())
+                    .transform(summary.as_str())
+                    .transform(
+                        Transform::new()
+                            .from_dataset_index(1)
+                            .from_transform_result(1),
+                    ),
+            )
+            .x_axis(Axis::new().type_(AxisType::Category))
+            .y_axis(Axis::new().type_(AxisType::Value).name(y_name).scale(true))
+            .series(Boxplot::new().name("boxplot").dataset_index(1))
+            .series(Scatter::new().name("outlier").dataset_index(2));
+        self.save(stem, title, chart)
+    }
+}
+
+/// Charts drawn in text and reported line by line with the rest of the output.
+pub struct TerminalGraph;
+
+impl Graph for TerminalGraph {
+    fn bar(
+        &self,
+        _stem: &str,
+        title: &str,
+        x_name: &str,
+        y_name: &str,
+        labels: &[String],
+        values: &[usize],
+    ) -> Result<Option<PathBuf>, Error> {
+        let most = values.iter().copied().max().unwrap_or_default().max(1);
+        let sparkline = values
+            .iter()
+            .map(|value| match value {
+                0 => ' ',
+                _ => SPARKS[(value * (SPARKS.len() - 1) + most / 2) / most],
+            })
+            .collect::<String>();
+        let label_width = labels
+            .iter()
+            .map(|label| label.chars().count())
+            .max()
+            .unwrap_or_default()
+            .max(x_name.chars().count());
+
+        report!("{}", logging::format_title(title));
+        report!("{:<label_width$}  {}", x_name, sparkline);
+        for (label, value) in labels.iter().zip(values) {
+            report!(
+                "{:<label_width$}  {:<BAR_WIDTH$}  {}",
+                label,
+                "█".repeat((value * BAR_WIDTH + most / 2) / most),
+                value
+            );
+        }
+        report!("{:<label_width$}  {}", "", y_name);
+        Ok(None)
+    }
+
+    fn scatter(
+        &self,
+        _stem: &str,
+        title: &str,
+        x_name: &str,
+        y_name: &str,
+        points: &[(f64, f64)],
+        reference: Option<[(f64, f64); 2]>,
+    ) -> Result<Option<PathBuf>, Error> {
+        let x_values = points.iter().map(|(x, _)| *x).collect::<Vec<f64>>();
+        let y_values = points.iter().map(|(_, y)| *y).collect::<Vec<f64>>();
+        let (x_low, x_high) = padded_range(&x_values);
+        let (y_low, y_high) = padded_range(&y_values);
+        let column =
+            |x: f64| ((x - x_low) / (x_high - x_low) * (TERMINAL_WIDTH - 1) as f64).round();
+        let row = |y: f64| ((y_high - y) / (y_high - y_low) * (TERMINAL_HEIGHT - 1) as f64).round();
+
+        let mut cells = vec![vec![' '; TERMINAL_WIDTH]; TERMINAL_HEIGHT];
+        if let Some([(x_1, y_1), (x_2, y_2)]) = reference {
+            if x_1 != x_2 {
+                // one step per column, so the line has no gaps
+                for step in 0..TERMINAL_WIDTH {
+                    let x = x_low + step as f64 / (TERMINAL_WIDTH - 1) as f64 * (x_high - x_low);
+                    let cell_row = row(y_1 + (x - x_1) * (y_2 - y_1) / (x_2 - x_1));
+                    if (0.0..TERMINAL_HEIGHT as f64).contains(&cell_row) {
+                        cells[cell_row as usize][column(x) as usize] = '.';
+                    }
+                }
+            }
+        }
+        for (x, y) in points {
+            let cell = &mut cells[row(*y) as usize][column(*x) as usize];
+            *cell = if *cell == '*' || *cell == '#' {
+                '#'
+            } else {
+                '*'
+            };
+        }
+
+        let y_labels = [format!("{:.2}", y_high), format!("{:.2}", y_low)];
+        let margin = y_labels.iter().map(String::len).max().unwrap_or_default();
+        report!("{}", logging::format_title(title));
+        report!("{:>margin$}  {}", "", y_name);
+        for (index, cells) in cells.iter().enumerate() {
+            let label = match index {
+                0 => &y_labels[0],
+                _ if index == TERMINAL_HEIGHT - 1 => &y_labels[1],
+                _ => "",
+            };
+            report!(
+                "{:>margin$} |{}",
+                label,
+                cells.iter().collect::<String>().trim_end()
+            );
+        }
+        report!("{:>margin$} +{}", "", "-".repeat(TERMINAL_WIDTH));
+        let x_low_label = format!("{:.2}", x_low);
+        report!(
+            "{:>margin$}  {}{:>width$}",
+            "",
+            x_low_label,
+            format!("{:.2}", x_high),
+            width = TERMINAL_WIDTH.saturating_sub(x_low_label.len())
+        );
+        report!(
+            "{:>margin$}  {}  (* one point, # several{})",
+            "",
+            x_name,
+            if reference.is_some() {
+                ", . reference line"
+            } else {
+                ""
+            }
+        );
+        Ok(None)
+    }
+
+    fn boxplot(
+        &self,
+        _stem: &str,
+        title: &str,
+        y_name: &str,
+        names: &[String],
+        groups: &[Vec<f64>],
+    ) -> Result<Option<PathBuf>, Error> {
+        let (low, high) = padded_range(&groups.concat());
+        let column = |value: f64| {
+            ((value - low) / (high - low) * (TERMINAL_WIDTH - 1) as f64).round() as usize
+        };
+        let name_width = names
+            .iter()
+            .map(|name| name.chars().count())
+            .max()
+            .unwrap_or_default();
+
+        report!("{}", logging::format_title(title));
+        for (name, group) in names.iter().zip(groups) {
+            if group.is_empty() {
+                report!("{:<name_width$}  (no values)", name);
+                continue;
+            }
+            let summary = box_summary(group)?;
+            let mut cells = vec![' '; TERMINAL_WIDTH];
+            for cell in &mut cells[column(summary.lower_whisker)..=column(summary.upper_whisker)] {
+                *cell = '-';
+            }
+            for cell in &mut cells[column(summary.first_quartile)..=column(summary.third_quartile)]
+            {
+                *cell = '=';
+            }
+            cells[column(summary.lower_whisker)] = '+';
+            cells[column(summary.upper_whisker)] = '+';
+            cells[column(summary.first_quartile)] = '[';
+            cells[column(summary.third_quartile)] = ']';
+            cells[column(summary.median)] = '|';
+            for outlier in &summary.outliers {
+                cells[column(*outlier)] = 'o';
+            }
+            report!(
+                "{:<name_width$}  {}  median {:.2}",
+                name,
+                cells.iter().collect::<String>(),
+                summary.median
+            );
+        }
+        let low_label = format!("{:.2}", low);
+        report!(
+            "{:<name_width$}  {}{:>width$}",
+            "",
+            low_label,
+            format!("{:.2}", high),
+            width = TERMINAL_WIDTH.saturating_sub(low_label.len())
+        );
+        report!(
+            "{:<name_width$}  {}  ([ ] quartiles, | median, + whiskers at 1.5 IQR, o outliers)",
+            "",
+            y_name
+        );
+        Ok(None)
+    }
+}
+
+/// Charts drawn on a `Canvas` and written as SVG or PNG files, by the extension of
+/// `path`. With `several`, each chart goes beside `path` with its name appended to the
+/// file stem, e.g. plots-GPA-normal-q-q.png for plots.png.
+pub struct ImageGraph {
+    path: PathBuf,
+    format: ImageFormat,
+    several: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ImageFormat {
+    Svg,
+    Png,
+}
+
+/// An `ImageGraph` writing to `path`, which must end in .svg or .png.
+pub fn image_graph(path: &Path, several: bool) -> Result<Box<dyn Graph>, Error> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase);
+    let format = match extension.as_deref() {
+        Some("svg") => ImageFormat::Svg,
+        Some("png") => ImageFormat::Png,
+        _ => {
+            return Err(anyhow!(
+                "Charts can be exported as .svg or .png files, not '{}'",
+                path.display()
+            ))
+        }
+    };
+    Ok(Box::new(ImageGraph {
+        path: path.to_path_buf(),
+        format,
+        several,
+    }))
+}
+
+impl ImageGraph {
+    fn save(&self, stem: &str, canvas: Canvas) -> Result<Option<PathBuf>, Error> {
+        let path = if self.several {
+            let (base, extension) = (
+                self.path.file_stem().unwrap_or_default().to_string_lossy(),
+                self.path.extension().unwrap_or_default().to_string_lossy(),
+            );
+            self.path
+                .with_file_name(format!("{}-{}.{}", base, stem, extension))
+        } else {
+            self.path.clone()
+        };
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+        match self.format {
+            ImageFormat::Svg => fs::write(&path, canvas.to_svg())?,
+            ImageFormat::Png => fs::write(&path, canvas.to_png())?,
+        }
+        Ok(Some(path))
+    }
+}
+
+impl Graph for ImageGraph {
+    fn bar(
+        &self,
+        stem: &str,
+        title: &str,
+        x_name: &str,
+        y_name: &str,
+        labels: &[String],
+        values: &[usize],
+    ) -> Result<Option<PathBuf>, Error> {
+        let most = values.iter().copied().max().unwrap_or_default().max(1);
+        let y_ticks = ticks(0.0, most as f64);
+        let mut canvas = Canvas::new(PLOT_WIDTH as usize, PLOT_HEIGHT as usize);
+        let frame = Frame::new((0.0, 1.0), &y_ticks);
+        frame.draw(&mut canvas, title, x_name, y_name, None, &y_ticks);
+
+        let slot = (frame.right - frame.left) / values.len().max(1) as f64;
+        for (index, value) in values.iter().enumerate() {
+            let left = frame.left + (index as f64 + 0.1) * slot;
+            let top = frame.y(*value as f64);
+            canvas.rect(
+                (left, top),
+                (slot * 0.8, frame.bottom - top),
+                Some(Color::BLUE),
+                None,
+            );
+        }
+        frame.category_labels(&mut canvas, labels);
+        self.save(stem, canvas)
+    }
+
+    fn scatter(
+        &self,
+        stem: &str,
+        title: &str,
+        x_name: &str,
+        y_name: &str,
+        points: &[(f64, f64)],
+        reference: Option<[(f64, f64); 2]>,
+    ) -> Result<Option<PathBuf>, Error> {
+        let (x_low, x_high) = range(&points.iter().map(|(x, _)| *x).collect::<Vec<f64>>());
+        let (y_low, y_high) = range(&points.iter().map(|(_, y)| *y).collect::<Vec<f64>>());
+        let x_ticks = ticks(x_low, x_high);
+        let y_ticks = ticks(y_low, y_high);
+        let mut canvas = Canvas::new(PLOT_WIDTH as usize, PLOT_HEIGHT as usize);
+        let frame = Frame::new((x_ticks[0], x_ticks[x_ticks.len() - 1]), &y_ticks);
+        frame.draw(&mut canvas, title, x_name, y_name, Some(&x_ticks), &y_ticks);
+
+        if let Some([from, to]) = reference {
+            canvas.line(
+                (frame.x(from.0), frame.y(from.1)),
+                (frame.x(to.0), frame.y(to.1)),
+                Color::RED,
+                2.0,
+            );
+        }
+        for (x, y) in points {
+            canvas.circle((frame.x(*x), frame.y(*y)), 4.0, Color::BLUE);
+        }
+        self.save(stem, canvas)
+    }
+
+    fn boxplot(
+        &self,
+        stem: &str,
+        title: &str,
+        y_name: &str,
+        names: &[String],
+        groups: &[Vec<f64>],
+    ) -> Result<Option<PathBuf>, Error> {
+        let (low, high) = range(&groups.concat());
+        let y_ticks = ticks(low, high);
+        let mut canvas = Canvas::new(PLOT_WIDTH as usize, PLOT_HEIGHT as usize);
+        let frame = Frame::new((0.0, 1.0), &y_ticks);
+        frame.draw(&mut canvas, title, "", y_name, None, &y_ticks);
+
+        let slot = (frame.right - frame.left) / groups.len().max(1) as f64;
+        let half = (slot * 0.25).min(60.0);
+        for (index, group) in groups.iter().enumerate() {
+            if group.is_empty() {
+                continue;
+            }
+            let summary = box_summary(group)?;
+            let center = frame.left + (index as f64 + 0.5) * slot;
+            let (first, third) = (
+                frame.y(summary.first_quartile),
+                frame.y(summary.third_quartile),
+            );
+            for (from, to) in [
+                (summary.lower_whisker, summary.first_quartile),
+                (summary.third_quartile, summary.upper_whisker),
+            ] {
+                canvas.line(
+                    (center, frame.y(from)),
+                    (center, frame.y(to)),
+                    Color::BLUE,
+                    2.0,
+                );
+            }
+            for whisker in [summary.lower_whisker, summary.upper_whisker] {
+                let y = frame.y(whisker);
+                canvas.line(
+                    (center - half / 2.0, y),
+                    (center + half / 2.0, y),
+                    Color::BLUE,
+                    2.0,
+                );
+            }
+            canvas.rect(
+                (center - half, third),
+                (2.0 * half, first - third),
+                Some(Color::WHITE),
+                Some(Color::BLUE),
+            );
+            let median = frame.y(summary.median);
+            canvas.line(
+                (center - half, median),
+                (center + half, median),
+                Color::BLUE,
+                3.0,
+            );
+            for outlier in &summary.outliers {
+                canvas.circle((center, frame.y(*outlier)), 4.0, Color::RED);
+            }
+        }
+        frame.category_labels(&mut canvas, names);
+        self.save(stem, canvas)
+    }
+}
+
+// margins around the plotting area of an exported chart, in pixels
+const MARGIN_LEFT: f64 = 110.0;
+const MARGIN_RIGHT: f64 = 40.0;
+const MARGIN_TOP: f64 = 80.0;
+const MARGIN_BOTTOM: f64 = 100.0;
+const LABEL_SCALE: usize = 2;
+const TITLE_SCALE: usize = 3;
+
+/// The plotting area of an exported chart and the values its edges stand for.
+struct Frame {
+    left: f64,
+    top: f64,
+    right: f64,
+    bottom: f64,
+    x_span: (f64, f64),
+    y_span: (f64, f64),
+}
+
+impl Frame {
+    fn new(x_span: (f64, f64), y_ticks: &[f64]) -> Frame {
+        Frame {
+            left: MARGIN_LEFT,
+            top: MARGIN_TOP,
+            right: PLOT_WIDTH as f64 - MARGIN_RIGHT,
+            bottom: PLOT_HEIGHT as f64 - MARGIN_BOTTOM,
+            x_span,
+            y_span: (y_ticks[0], y_ticks[y_ticks.len() - 1]),
+        }
+    }
+
+    fn x(&self, value: f64) -> f64 {
+        self.left
+            + (value - self.x_span.0) / (self.x_span.1 - self.x_span.0) * (self.right - self.left)
+    }
+
+    fn y(&self, value: f64) -> f64 {
+        self.bottom
+            - (value - self.y_span.0) / (self.y_span.1 - self.y_span.0) * (self.bottom - self.top)
+    }
+
+    /// The title, gridlines, tick labels, axes, and axis names. `x_ticks` is `None` for
+    /// a category axis, which `category_labels` labels.
+    fn draw(
+        &self,
+        canvas: &mut Canvas,
+        title: &str,
+        x_name: &str,
+        y_name: &str,
+        x_ticks: Option<&[f64]>,
+        y_ticks: &[f64],
+    ) {
+        canvas.text(
+            (PLOT_WIDTH as f64 / 2.0, MARGIN_TOP / 2.0),
+            title,
+            TITLE_SCALE,
+            Anchor::Middle,
+        );
+        for tick in y_ticks {
+            let y = self.y(*tick);
+            canvas.line((self.left, y), (self.right, y), Color::GRID, 1.0);
+            canvas.text(
+                (self.left - 10.0, y),
+                &tick_label(*tick, y_ticks),
+                LABEL_SCALE,
+                Anchor::End,
+            );
+        }
+        if let Some(x_ticks) = x_ticks {
+            for tick in x_ticks {
+                let x = self.x(*tick);
+                canvas.line((x, self.bottom), (x, self.bottom + 6.0), Color::BLACK, 1.0);
+                canvas.text(
+                    (x, self.bottom + 22.0),
+                    &tick_label(*tick, x_ticks),
+                    LABEL_SCALE,
+                    Anchor::Middle,
+                );
+            }
+        }
+        canvas.line(
+            (self.left, self.top),
+            (self.left, self.bottom),
+            Color::BLACK,
+            1.0,
+        );
+        canvas.line(
+            (self.left, self.bottom),
+            (self.right, self.bottom),
+            Color::BLACK,
+            1.0,
+        );
+        if !x_name.is_empty() {
+            canvas.text(
+                ((self.left + self.right) / 2.0, self.bottom + 60.0),
+                x_name,
+                LABEL_SCALE,
+                Anchor::Middle,
+            );
+        }
+        canvas.vertical_text((25.0, (self.top + self.bottom) / 2.0), y_name, LABEL_SCALE);
+    }
+
+    /// One label under the middle of each equal slot along the x axis, skipping labels
+    /// evenly when they would overlap.
+    fn category_labels(&self, canvas: &mut Canvas, labels: &[String]) {
+        let slot = (self.right - self.left) / labels.len().max(1) as f64;
+        let widest = labels
+            .iter()
+            .map(|label| Canvas::text_width(label, LABEL_SCALE))
+            .fold(0.0, f64::max);
+        let every = ((widest + 10.0) / slot).ceil().max(1.0) as usize;
+        for (index, label) in labels.iter().enumerate().step_by(every) {
+            canvas.text(
+                (self.left + (index as f64 + 0.5) * slot, self.bottom + 22.0),
+                label,
+                LABEL_SCALE,
+                Anchor::Middle,
+            );
+        }
+    }
+}
+
+/// Evenly spaced round values (steps of 1, 2, or 5 times a power of ten) covering
+/// `low` to `high`, about five intervals apart.
+fn ticks(low: f64, high: f64) -> Vec<f64> {
+    let (low, high) = padded_range(&[low, high]);
+    let rough = (high - low) / 5.0;
+    let magnitude = 10_f64.powf(rough.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|multiple| multiple * magnitude)
+        .find(|step| *step >= rough)
+        .unwrap_or(10.0 * magnitude);
+    let first = (low / step).floor();
+    let last = (high / step).ceil();
+    (0..=(last - first).round() as usize)
+        .map(|index| (first + index as f64) * step)
+        .collect()
+}
+
+/// `tick` with as many decimals as the spacing of `ticks` needs.
+fn tick_label(tick: f64, ticks: &[f64]) -> String {
+    let step = match ticks {
+        [first, second, ..] => second - first,
+        _ => 1.0,
+    };
+    let decimals = (-step.log10().floor()).max(0.0) as usize;
+    // avoid printing -0.00 for a tick that rounds to zero
+    let tick = if tick.abs() < step / 1e6 { 0.0 } else { tick };
+    format!("{:.*}", decimals, tick)
+}
+
+/// Writes residual-vs-fitted, normal Q-Q, and scale-location plots of a regression of
+/// `response` with `graph`, returning the files written. `residuals` are y - y-hat, and
+/// `standardized` are the residuals divided by s sqrt(1 - h).
+pub fn residual_plots(
+    response: &str,
+    graph: &dyn Graph,
+    fitted: &[f64],
+    residuals: &[f64],
+    standardized: &[f64],
+) -> Result<Vec<PathBuf>, Error> {
+    let (low, high) = range(fitted);
+
+    // theoretical quantiles at Blom's positions (i - 3/8) / (n + 1/4)
+    let mut sorted = standardized.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let n = sorted.len() as f64;
+    let theoretical = (1..=sorted.len())
+        .map(|i| standard_normal_quantile((i as f64 - 0.375) / (n + 0.25)))
+        .collect::<Result<Vec<f64>, Error>>()?;
+    let (quantile_low, quantile_high) = range(&theoretical);
+
+    let stem = file_stem(response);
+    let paths = [
+        graph.scatter(
+            &format!("{}-residuals-vs-fitted", stem),
+            &format!("Residuals vs Fitted: {}", response),
+            "Fitted",
+            "Residual",
+            &points(fitted, residuals),
+            Some([(low, 0.0), (high, 0.0)]),
+        )?,
+        graph.scatter(
+            &format!("{}-normal-q-q", stem),
+            &format!("Normal Q-Q: {}", response),
+            "Theoretical Quantile",
+            "Standardized Residual",
+            &points(&theoretical, &sorted),
+            Some([(quantile_low, quantile_low), (quantile_high, quantile_high)]),
+        )?,
+        graph.scatter(
+            &format!("{}-scale-location", stem),
+            &format!("Scale-Location: {}", response),
+            "Fitted",
+            "sqrt(|Standardized Residual|)",
+            &points(
+                fitted,
+                &standardized
+                    .iter()
+                    .map(|residual| residual.abs().sqrt())
+                    .collect::<Vec<f64>>(),
+            ),
+            None,
+        )?,
+    ];
+    Ok(paths.into_iter().flatten().collect())
+}
+
+/// Sturges' rule, ceil(log2 n) + 1, the default number of histogram bins for n values.
+pub fn default_bins(n: usize) -> usize {
+    (n.max(1) as f64).log2().ceil() as usize + 1
+}
+
+/// Draws a histogram of `data` with `bins` equal-width bins with `graph`, returning the
+/// file written, if any. Every bin is half-open except the last, which also holds the
+/// maximum.
+pub fn histogram(
+    data: &ContinuousDataArray,
+    bins: usize,
+    graph: &dyn Graph,
+) -> Result<Option<PathBuf>, Error> {
+    if bins == 0 {
+        return Err(anyhow!("A histogram needs at least one bin"));
+    }
+    if data.data_array.data.is_empty() {
+        return Err(anyhow!("'{}' has no values to plot", data.name));
+    }
+    let values = data
+        .data_array
+        .data
+        .iter()
+        .map(|(_, value)| *value)
+        .collect::<Vec<f64>>();
+    let (low, high) = range(&values);
+    let width = (high - low) / bins as f64;
+
+    // compare against the edges themselves, so a value on an edge lands in the bin its
+    // label says, whatever the rounding of (value - low) / width
+    let edges = (0..=bins)
+        .map(|edge| low + edge as f64 * width)
+        .collect::<Vec<f64>>();
+    let mut counts = vec![0; bins];
+    for value in &values {
+        let bin = edges[1..bins].partition_point(|edge| edge <= value);
+        counts[bin] += 1;
+    }
+    let labels = (0..bins)
+        .map(|bin| {
+            let closing = if bin == bins - 1 { ']' } else { ')' };
+            format!("[{:.2}, {:.2}{}", edges[bin], edges[bin + 1], closing)
+        })
+        .collect::<Vec<String>>();
+
+    graph.bar(
+        &format!("{}-histogram", file_stem(&data.name)),
+        &format!("Histogram: {}", data.name),
+        &data.name,
+        "Frequency",
+        &labels,
+        &counts,
+    )
+}
+
+/// Draws a boxplot of `continuous` with `graph`, with one box per level of `categorical`
+/// when given, returning the file written, if any.
+pub fn boxplot(
+    continuous: &ContinuousDataArray,
+    categorical: Option<&CategoricalDataArray>,
+    graph: &dyn Graph,
+) -> Result<Option<PathBuf>, Error> {
+    let (names, groups, title, stem) = match categorical {
+        Some(categorical) => {
+            let mut names = vec![];
+            let mut groups = vec![];
+            for level_name in categorical.levels.keys() {
+                names.push((*level_name).clone());
+                groups.push(
+                    categorical
+                        .get_level_data(level_name, continuous)?
+                        .into_iter()
+                        .copied()
+                        .collect::<Vec<f64>>(),
+                );
+            }
+            (
+                names,
+                groups,
+                format!("Boxplot: {} by {}", continuous.name, categorical.name),
+                format!(
+                    "{}-by-{}",
+                    file_stem(&continuous.name),
+                    file_stem(&categorical.name)
+                ),
+            )
+        }
+        None => (
+            vec![continuous.name.clone()],
+            vec![continuous
+                .data_array
+                .data
+                .iter()
+                .map(|(_, value)| *value)
+                .collect::<Vec<f64>>()],
+            format!("Boxplot: {}", continuous.name),
+            file_stem(&continuous.name),
+        ),
+    };
+    if groups.iter().all(|group| group.is_empty()) {
+        return Err(anyhow!("'{}' has no values to plot", continuous.name));
+    }
+
+    graph.boxplot(
+        &format!("{}-boxplot", stem),
+        &title,
+        &continuous.name,
+        &names,
+        &groups,
+    )
+}
+
+fn points(x: &[f64], y: &[f64]) -> Vec<(f64, f64)> {
+    x.iter().zip(y).map(|(x, y)| (*x, *y)).collect()
+}
+
+fn range(values: &[f64]) -> (f64, f64) {
+    values
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), value| {
+            (low.min(*value), high.max(*value))
+        })
+}
+
+/// The five numbers a boxplot draws, with the whiskers at the most extreme values within
+/// 1.5 IQR of the quartiles and anything beyond them an outlier.
+struct BoxSummary {
+    first_quartile: f64,
+    median: f64,
+    third_quartile: f64,
+    lower_whisker: f64,
+    upper_whisker: f64,
+    outliers: Vec<f64>,
+}
+
+fn box_summary(values: &[f64]) -> Result<BoxSummary, Error> {
+    let first_quartile = quantile(values, 0.25)?;
+    let third_quartile = quantile(values, 0.75)?;
+    let fence = 1.5 * (third_quartile - first_quartile);
+    let (inside, outliers): (Vec<f64>, Vec<f64>) = values
+        .iter()
+        .partition(|value| (first_quartile - fence..=third_quartile + fence).contains(*value));
+    let (lower_whisker, upper_whisker) = range(&inside);
+    Ok(BoxSummary {
+        first_quartile,
+        median: quantile(values, 0.5)?,
+        third_quartile,
+        lower_whisker,
+        upper_whisker,
+        outliers,
+    })
+}
+
+/// `range`, widened by 0.5 either side when every value is the same so it can be scaled.
+fn padded_range(values: &[f64]) -> (f64, f64) {
+    match range(values) {
+        (low, high) if low < high => (low, high),
+        (low, _) if low.is_finite() => (low - 0.5, low + 0.5),
+        _ => (0.0, 1.0),
+    }
+}
+
+/// `name` with every run of characters other than letters and digits replaced by '-'.
+fn file_stem(name: &str) -> String {
+    name.split(|character: char| !character.is_alphanumeric())
+        .filter(|piece| !piece.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-")
+}
 // use std::any::Any;
 // use crate::data_types::data_relationship::DataRelationship;
 // use anyhow::{Error, Result};
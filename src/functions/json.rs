@@ -0,0 +1,96 @@
+use crate::functions::csv::{decode_latin1, report_latin1_fallback, CSVData};
+use anyhow::{anyhow, Error, Result};
+use clap::ValueEnum;
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::Path;
+
+/// File extensions read as JSON (an array of objects) or JSON lines instead of CSV.
+pub const JSON_EXTENSIONS: [&str; 3] = ["json", "jsonl", "ndjson"];
+
+/// How to read an input file, when its extension doesn't say.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// Delimited text
+    Csv,
+    /// An array of flat JSON objects, or one object per line (JSON lines)
+    Json,
+}
+
+pub fn is_json_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| JSON_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+/// Reads a JSON array of flat objects, or JSON lines (one object per line), as rows. The
+/// headers are every key in order of first appearance; a key a record lacks, or a null,
+/// is a missing cell. Strings are kept as they are and numbers and booleans written as
+/// text, so the result reads like the same data exported to CSV. As with CSV, a leading
+/// byte order mark is skipped, and a file that is not UTF-8 is read as Latin-1.
+pub fn import_json_data(file_path: &Path) -> Result<CSVData, Error> {
+    let bytes = fs::read(file_path)?;
+    let contents = match String::from_utf8(bytes) {
+        Ok(contents) => contents,
+        Err(error) => {
+            report_latin1_fallback(file_path);
+            decode_latin1(error.as_bytes())
+        }
+    };
+    let contents = contents.strip_prefix('\u{feff}').unwrap_or(&contents);
+    let records = if contents.trim_start().starts_with('[') {
+        match serde_json::from_str::<Value>(contents)? {
+            Value::Array(records) => records,
+            _ => return Err(anyhow!("'{}' is not a JSON array", file_path.display())),
+        }
+    } else {
+        contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str::<Value>(line)
+                    .map_err(|error| anyhow!("Line {} is not valid JSON: {}", index + 1, error))
+            })
+            .collect::<Result<Vec<Value>, Error>>()?
+    };
+
+    let objects = records
+        .iter()
+        .enumerate()
+        .map(|(index, record)| match record {
+            Value::Object(object) => Ok(object),
+            _ => Err(anyhow!("Record {} is not a JSON object", index + 1)),
+        })
+        .collect::<Result<Vec<&Map<String, Value>>, Error>>()?;
+
+    let mut csv_data = CSVData::default();
+    for key in objects.iter().flat_map(|object| object.keys()) {
+        if !csv_data.headers.contains(key) {
+            csv_data.headers.push(key.clone());
+        }
+    }
+    csv_data.row_length = csv_data.headers.len();
+
+    for (index, object) in objects.iter().enumerate() {
+        for header in csv_data.headers.iter() {
+            let cell = match object.get(header) {
+                None | Some(Value::Null) => String::new(),
+                Some(Value::String(text)) => text.trim().to_string(),
+                Some(Value::Number(number)) => number.to_string(),
+                Some(Value::Bool(switch)) => switch.to_string(),
+                Some(Value::Array(_)) | Some(Value::Object(_)) => {
+                    return Err(anyhow!(
+                        "'{}' in record {} is nested; only flat objects can be read",
+                        header,
+                        index + 1
+                    ))
+                }
+            };
+            csv_data.data.push(cell);
+        }
+        csv_data.column_count += 1;
+    }
+    csv_data.infer_column_types();
+    Ok(csv_data)
+}
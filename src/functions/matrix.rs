@@ -0,0 +1,90 @@
+use anyhow::{anyhow, Error, Result};
+
+/// Row-major dense matrix.
+pub type Matrix = Vec<Vec<f64>>;
+
+pub fn transpose(matrix: &Matrix) -> Matrix {
+    if matrix.is_empty() {
+        return vec![];
+    }
+    (0..matrix[0].len())
+        .map(|column| matrix.iter().map(|row| row[column]).collect())
+        .collect()
+}
+
+pub fn multiply(a: &Matrix, b: &Matrix) -> Result<Matrix, Error> {
+    let inner = b.len();
+    if a.iter().any(|row| row.len() != inner) {
+        return Err(anyhow!("Matrix dimensions do not agree for multiplication"));
+    }
+    let columns = b.first().map(|row| row.len()).unwrap_or(0);
+    Ok(a.iter()
+        .map(|row| {
+            (0..columns)
+                .map(|column| (0..inner).map(|k| row[k] * b[k][column]).sum())
+                .collect()
+        })
+        .collect())
+}
+
+pub fn multiply_vector(a: &Matrix, v: &[f64]) -> Result<Vec<f64>, Error> {
+    if a.iter().any(|row| row.len() != v.len()) {
+        return Err(anyhow!("Matrix and vector dimensions do not agree"));
+    }
+    Ok(a.iter()
+        .map(|row| row.iter().zip(v.iter()).map(|(x, y)| x * y).sum())
+        .collect())
+}
+
+/// Inverts a square matrix by Gauss-Jordan elimination with partial pivoting.
+pub fn invert(matrix: &Matrix) -> Result<Matrix, Error> {
+    const SINGULAR_TOLERANCE: f64 = 1e-12;
+
+    let n = matrix.len();
+    if matrix.iter().any(|row| row.len() != n) {
+        return Err(anyhow!("Only square matrices can be inverted"));
+    }
+
+    // augment [A | I]
+    let mut augmented: Matrix = matrix
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut augmented_row = row.clone();
+            augmented_row.extend((0..n).map(|j| if i == j { 1.0 } else { 0.0 }));
+            augmented_row
+        })
+        .collect();
+
+    for column in 0..n {
+        let pivot_row = (column..n)
+            .max_by(|a, b| {
+                augmented[*a][column]
+                    .abs()
+                    .total_cmp(&augmented[*b][column].abs())
+            })
+            .unwrap_or(column);
+        if augmented[pivot_row][column].abs() < SINGULAR_TOLERANCE {
+            return Err(anyhow!(
+                "Matrix is singular (are two predictors perfectly collinear?)"
+            ));
+        }
+        augmented.swap(column, pivot_row);
+
+        let pivot = augmented[column][column];
+        augmented[column].iter_mut().for_each(|x| *x /= pivot);
+
+        let pivot_values = augmented[column].clone();
+        for (row, values) in augmented.iter_mut().enumerate() {
+            let factor = values[column];
+            if row != column && factor != 0.0 {
+                values
+                    .iter_mut()
+                    .zip(pivot_values.iter())
+                    .for_each(|(value, pivot_value)| *value -= factor * pivot_value);
+            }
+        }
+    }
+
+    Ok(augmented.into_iter().map(|row| row[n..].to_vec()).collect())
+}
@@ -1,4 +1,17 @@
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod apa;
+pub mod canvas;
 pub mod convert;
 pub mod stats_math;
 pub mod csv;
 pub mod graph;
+pub mod distributions;
+pub mod filter;
+pub mod formula;
+pub mod json;
+pub mod matrix;
+pub mod optimize;
+pub mod recode;
+pub mod sampling;
+pub mod transform;
@@ -0,0 +1,52 @@
+use crate::functions::matrix::{invert, multiply_vector, Matrix};
+use anyhow::{anyhow, Error, Result};
+
+/// Most Newton steps a fit takes, and the largest change in any parameter at which it
+/// has converged.
+const MAX_ITERATIONS: usize = 100;
+const TOLERANCE: f64 = 1e-8;
+
+/// Newton's method with step halving, from `start`, on a concave log-likelihood giving
+/// its value, gradient, and Hessian (or None outside its domain). Returns the maximizing
+/// parameters, the maximum, and the Hessian there.
+pub fn maximize<F>(start: Vec<f64>, likelihood: F) -> Result<(Vec<f64>, f64, Matrix), Error>
+where
+    F: Fn(&[f64]) -> Option<(f64, Vec<f64>, Matrix)>,
+{
+    let mut parameters = start;
+    let (mut log_likelihood, mut gradient, mut hessian) = likelihood(&parameters)
+        .ok_or_else(|| anyhow!("could not be started from its initial estimates"))?;
+    for _ in 0..MAX_ITERATIONS {
+        // step = -H^-1 g
+        let step = multiply_vector(&invert(&hessian)?, &gradient)?
+            .iter()
+            .map(|x| -x)
+            .collect::<Vec<f64>>();
+        let mut scale = 1.0;
+        let (candidate, next) = loop {
+            let candidate = parameters
+                .iter()
+                .zip(step.iter())
+                .map(|(parameter, step)| parameter + scale * step)
+                .collect::<Vec<f64>>();
+            match likelihood(&candidate) {
+                Some(next) if next.0 >= log_likelihood - 1e-10 => break (candidate, next),
+                _ if scale < 1e-10 => return Err(anyhow!("stopped improving before it converged")),
+                _ => scale /= 2.0,
+            }
+        };
+        let change = step
+            .iter()
+            .map(|step| (scale * step).abs())
+            .fold(0.0, f64::max);
+        parameters = candidate;
+        (log_likelihood, gradient, hessian) = next;
+        if change < TOLERANCE {
+            return Ok((parameters, log_likelihood, hessian));
+        }
+    }
+    Err(anyhow!(
+        "did not converge in {} iterations; a predictor may separate the levels perfectly",
+        MAX_ITERATIONS
+    ))
+}
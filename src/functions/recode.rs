@@ -0,0 +1,137 @@
+use crate::data_types::data_array::CategoricalDataArray;
+use crate::functions::csv::CSVData;
+use crate::reporter::report;
+use anyhow::{anyhow, Error, Result};
+
+/// The level `collapse_sparse_levels` merges sparse levels into.
+pub const OTHER_LEVEL: &str = "Other";
+
+/// A rule merging levels of a categorical column into one, written `FROM,FROM=>TO` (e.g.
+/// `Yes,Y,yes=>Yes`). Levels match exactly; TO may be one of them, another level, a new
+/// level, or empty to make the levels missing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recode {
+    pub from: Vec<String>,
+    pub to: String,
+}
+
+impl Recode {
+    pub fn parse(recode: &str) -> Result<Recode, Error> {
+        let (from, to) = recode.split_once("=>").ok_or_else(|| {
+            anyhow!(
+                "'{}' is not a recoding; write FROM,FROM=>TO, e.g. 'Yes,Y=>Yes'",
+                recode
+            )
+        })?;
+        let from = from
+            .split(',')
+            .map(|level| level.trim().to_string())
+            .filter(|level| !level.is_empty())
+            .collect::<Vec<String>>();
+        if from.is_empty() {
+            return Err(anyhow!(
+                "The recoding '{}' names no levels to recode",
+                recode
+            ));
+        }
+        Ok(Recode {
+            from,
+            to: to.trim().to_string(),
+        })
+    }
+}
+
+/// Replaces the levels of a 0-based column that `recode` names.
+pub fn recode_column(csv_data: &mut CSVData, column: usize, recode: &Recode) -> Result<(), Error> {
+    let from = recode
+        .from
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<&str>>();
+    let cells = replace_levels(csv_data, column, &from, &recode.to)?;
+    if cells > 0 {
+        report!(
+            "Recoding {} as {} in '{}' ({} cells)",
+            recode.from.join(", "),
+            match recode.to.is_empty() {
+                true => String::from("missing"),
+                false => format!("'{}'", recode.to),
+            },
+            csv_data.headers[column],
+            cells
+        );
+    }
+    Ok(())
+}
+
+/// Merges the levels of a 0-based column held by fewer than `min_count` rows into
+/// `OTHER_LEVEL`. Missing cells are left as they are.
+pub fn collapse_sparse_levels(
+    csv_data: &mut CSVData,
+    column: usize,
+    min_count: usize,
+) -> Result<(), Error> {
+    let data = csv_data.get_column_cached::<String>(column)?;
+    let sparse = categorical_data_array(csv_data, &data, column)?
+        .sparse_levels(min_count)
+        .into_iter()
+        .cloned()
+        .collect::<Vec<String>>();
+    if sparse.is_empty() {
+        return Ok(());
+    }
+    let from = sparse.iter().map(String::as_str).collect::<Vec<&str>>();
+    replace_levels(csv_data, column, &from, OTHER_LEVEL)?;
+    report!(
+        "Merging the levels of '{}' held by fewer than {} rows into '{}': {}",
+        csv_data.headers[column],
+        min_count,
+        OTHER_LEVEL,
+        sparse.join(", ")
+    );
+    Ok(())
+}
+
+/// Makes `level` the reference of a 0-based column, the level its other levels are
+/// compared to. Returns false, changing nothing, when no row holds the level.
+pub fn set_reference_level(csv_data: &mut CSVData, column: usize, level: &str) -> Result<bool> {
+    let data = csv_data.get_column_cached::<String>(column)?;
+    if !categorical_data_array(csv_data, &data, column)?.has_level(level) {
+        return Ok(false);
+    }
+    report!(
+        "Comparing the levels of '{}' to '{}'",
+        csv_data.headers[column],
+        level
+    );
+    csv_data.reference_levels.insert(column, level.to_string());
+    Ok(true)
+}
+
+/// Writes `to` over every cell of the column holding one of `from`, returning how many
+/// cells changed.
+fn replace_levels(
+    csv_data: &mut CSVData,
+    column: usize,
+    from: &[&str],
+    to: &str,
+) -> Result<usize, Error> {
+    let data = csv_data.get_column_cached::<String>(column)?;
+    let recoded = categorical_data_array(csv_data, &data, column)?.recoded(from, to);
+    let mut cells = 0;
+    for (row, value) in recoded.into_iter().enumerate() {
+        if value != data[row] {
+            csv_data.set_datum(row, column, value);
+            cells += 1;
+        }
+    }
+    Ok(cells)
+}
+
+fn categorical_data_array<'a>(
+    csv_data: &CSVData,
+    data: &'a Vec<String>,
+    column: usize,
+) -> Result<CategoricalDataArray<'a>, Error> {
+    CategoricalDataArray::new(csv_data.headers[column].clone(), data, column, Some(false))
+}
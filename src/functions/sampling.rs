@@ -0,0 +1,40 @@
+use anyhow::{Error, Result};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Draws a uniform random sample of up to `size` items in a single pass over `items`,
+/// holding no more than `size` of them at once (Algorithm R). The sample keeps the order
+/// the items arrived in. Returns the sample and the number of items seen.
+pub fn reservoir_sample<T>(
+    items: impl IntoIterator<Item = Result<T, Error>>,
+    size: usize,
+    seed: u64,
+) -> Result<(Vec<T>, usize), Error> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut reservoir: Vec<(usize, T)> = Vec::with_capacity(size);
+    let mut seen = 0;
+    for item in items {
+        let item = item?;
+        if reservoir.len() < size {
+            reservoir.push((seen, item));
+        } else {
+            // the item replaces a random slot with probability size / (seen + 1)
+            let slot = rng.random_range(0..=seen);
+            if slot < size {
+                reservoir[slot] = (seen, item);
+            }
+        }
+        seen += 1;
+    }
+
+    reservoir.sort_by_key(|(index, _)| *index);
+    Ok((reservoir.into_iter().map(|(_, item)| item).collect(), seen))
+}
+
+/// A seed for when none was given, taken from the clock.
+pub fn clock_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos() as u64)
+}
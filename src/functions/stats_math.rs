@@ -1,8 +1,9 @@
 use crate::error_types::{CSVError, CSVErrorKind};
 use crate::functions::convert::Convert;
+use crate::functions::distributions::{standard_normal_cdf, standard_normal_quantile};
 use crate::functions::{convert, stats_math};
+use crate::reporter::report;
 use anyhow::{anyhow, Error, Result};
-use log::info;
 
 const MODULE_NAME: &str = "STATS_MATH";
 
@@ -92,27 +93,31 @@ where
             Err(anyhow!("Missing data for calculating z-scores"))
         }
         (Some(datum), _, Some(data), _, _, _) => {
-            info!(
+            report!(
                 "{}: Calculating z-score from provided datum ({}) and data",
-                MODULE_NAME, datum
+                MODULE_NAME,
+                datum
             );
             Ok((f64::convert(datum) - mean(data)?) / standard_deviation(Some(data), None, pop)?)
         }
         (Some(datum), _, _, Some(data_mean), Some(sd), _) => {
-            info!("{}: Calculating z-score from provided datum ({}) and mean ({}) and standard deviation ({})", MODULE_NAME, datum, data_mean, sd);
+            report!("{}: Calculating z-score from provided datum ({}) and mean ({}) and standard deviation ({})", MODULE_NAME, datum, data_mean, sd);
             Ok((f64::convert(datum) - data_mean) / sd)
         }
         (_, Some(deviation), Some(data), _, _, _) => {
-            info!(
+            report!(
                 "{}: Calculating z-score from provided deviation ({}) and data",
-                MODULE_NAME, deviation
+                MODULE_NAME,
+                deviation
             );
             Ok(deviation / standard_deviation(Some(data), None, pop)?)
         }
         (_, Some(deviation), _, _, Some(sd), _) => {
-            info!(
+            report!(
                 "{}: Calculating z-score from provided deviation ({}) and standard deviation ({})",
-                MODULE_NAME, deviation, sd
+                MODULE_NAME,
+                deviation,
+                sd
             );
             Ok(deviation / sd)
         }
@@ -313,3 +318,461 @@ where
             })
         / (n_x + n_y - 2.0))
 }
+
+/// Ranks the data from 1..=N, assigning tied values the average of the ranks they span.
+pub fn ranks(data: &[f64]) -> Result<Vec<f64>, Error> {
+    let mut sorted_indices: Vec<usize> = (0..data.len()).collect();
+    sorted_indices.sort_by(|a, b| data[*a].total_cmp(&data[*b]));
+
+    let mut ranks = vec![0.0; data.len()];
+    let mut i = 0;
+    while i < sorted_indices.len() {
+        let mut j = i;
+        while j + 1 < sorted_indices.len() && data[sorted_indices[j + 1]] == data[sorted_indices[i]]
+        {
+            j += 1;
+        }
+        // positions i..=j share the average of ranks (i + 1)..=(j + 1)
+        let average_rank = (i + j) as f64 / 2.0 + 1.0;
+        for index in &sorted_indices[i..=j] {
+            ranks[*index] = average_rank;
+        }
+        i = j + 1;
+    }
+    Ok(ranks)
+}
+
+/// Whether every value equals the first, so the data have no spread at all.
+pub fn has_no_spread(data: &[f64]) -> bool {
+    data.iter().all(|x| *x == data[0])
+}
+
+/// Tie correction term sum(t^3 - t), where t is the size of each group of tied values.
+pub fn tie_correction(data: &[f64]) -> Result<f64, Error> {
+    Ok(tie_group_sizes(data)?
+        .iter()
+        .map(|t| f64::powi(*t, 3) - t)
+        .sum::<f64>())
+}
+
+/// Sizes of each group of equal values (groups of one included).
+pub fn tie_group_sizes(data: &[f64]) -> Result<Vec<f64>, Error> {
+    let mut sorted = data.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+
+    let mut sizes = vec![];
+    let mut i = 0;
+    while i < sorted.len() {
+        let mut j = i;
+        while j + 1 < sorted.len() && sorted[j + 1] == sorted[i] {
+            j += 1;
+        }
+        sizes.push((j - i + 1) as f64);
+        i = j + 1;
+    }
+    Ok(sizes)
+}
+
+/// Spearman's rho: Pearson r computed on the (tie-averaged) ranks of each variable.
+pub fn spearman_rho(data_x: &[f64], data_y: &[f64]) -> Result<f64, Error> {
+    pearson_r_method_1(&ranks(data_x)?, &ranks(data_y)?, None)
+}
+
+/// Kendall's tau-b and its normal-approximation z, with the tie-adjusted variance of S.
+pub fn kendall_tau_b(data_x: &[f64], data_y: &[f64]) -> Result<(f64, f64), Error> {
+    if data_x.len() != data_y.len() {
+        return Err(anyhow!("Kendall's tau requires data of the same length"));
+    }
+    let n = data_x.len() as f64;
+
+    // S = concordant pairs - discordant pairs (pairs tied in either variable count as neither)
+    let sign = |difference: f64| {
+        if difference > 0.0 {
+            1.0
+        } else if difference < 0.0 {
+            -1.0
+        } else {
+            0.0
+        }
+    };
+    let mut s = 0.0;
+    for i in 0..data_x.len() {
+        for j in (i + 1)..data_x.len() {
+            s += sign(data_x[i] - data_x[j]) * sign(data_y[i] - data_y[j]);
+        }
+    }
+
+    let ties_x = tie_group_sizes(data_x)?;
+    let ties_y = tie_group_sizes(data_y)?;
+
+    // tau-b = S / sqrt((n0 - n1)(n0 - n2))
+    let n_0 = n * (n - 1.0) / 2.0;
+    let n_1 = ties_x.iter().map(|t| t * (t - 1.0) / 2.0).sum::<f64>();
+    let n_2 = ties_y.iter().map(|u| u * (u - 1.0) / 2.0).sum::<f64>();
+    let tau_b = s / f64::sqrt((n_0 - n_1) * (n_0 - n_2));
+
+    // var(S) = (v0 - vt - vu) / 18 + v1 / (2n(n - 1)) + v2 / (9n(n - 1)(n - 2))
+    let v_0 = n * (n - 1.0) * (2.0 * n + 5.0);
+    let v_t = ties_x
+        .iter()
+        .map(|t| t * (t - 1.0) * (2.0 * t + 5.0))
+        .sum::<f64>();
+    let v_u = ties_y
+        .iter()
+        .map(|u| u * (u - 1.0) * (2.0 * u + 5.0))
+        .sum::<f64>();
+    let v_1 = ties_x.iter().map(|t| t * (t - 1.0)).sum::<f64>()
+        * ties_y.iter().map(|u| u * (u - 1.0)).sum::<f64>();
+    let v_2 = ties_x
+        .iter()
+        .map(|t| t * (t - 1.0) * (t - 2.0))
+        .sum::<f64>()
+        * ties_y
+            .iter()
+            .map(|u| u * (u - 1.0) * (u - 2.0))
+            .sum::<f64>();
+    let variance_s = (v_0 - v_t - v_u) / 18.0
+        + v_1 / (2.0 * n * (n - 1.0))
+        + v_2 / (9.0 * n * (n - 1.0) * (n - 2.0));
+
+    Ok((tau_b, s / f64::sqrt(variance_s)))
+}
+
+/// Cramér's V = sqrt(chi^2 / (n (min(r, c) - 1))) from a Pearson chi-square over an
+/// r x c table of n observations; 0 is independence and 1 a perfect association.
+pub fn cramers_v(chi_square: f64, n: f64, rows: usize, columns: usize) -> Result<f64, Error> {
+    if rows < 2 || columns < 2 || n <= 0.0 {
+        return Err(anyhow!(
+            "Cramér's V needs at least a 2 x 2 table with observations in it"
+        ));
+    }
+    Ok(f64::sqrt(chi_square / (n * (rows.min(columns) - 1) as f64)))
+}
+
+/// Phi for the 2 x 2 table [[a, b], [c, d]]:
+/// (ad - bc) / sqrt((a + b)(c + d)(a + c)(b + d)), positive when a and d go together. Its
+/// size equals Cramér's V for the table.
+pub fn phi(table: [[f64; 2]; 2]) -> Result<f64, Error> {
+    let [[a, b], [c, d]] = table;
+    let margins = (a + b) * (c + d) * (a + c) * (b + d);
+    if margins == 0.0 {
+        return Err(anyhow!(
+            "Phi is undefined when a row or column of the table is empty"
+        ));
+    }
+    Ok((a * d - b * c) / margins.sqrt())
+}
+
+/// Central moments m_2, m_3, m_4 (divided by n) about the mean.
+fn central_moments(data: &[f64]) -> (f64, f64, f64) {
+    let n = data.len() as f64;
+    let mean = data.iter().sum::<f64>() / n;
+    data.iter().fold((0.0, 0.0, 0.0), |(m_2, m_3, m_4), x| {
+        let deviation = x - mean;
+        (
+            m_2 + f64::powi(deviation, 2) / n,
+            m_3 + f64::powi(deviation, 3) / n,
+            m_4 + f64::powi(deviation, 4) / n,
+        )
+    })
+}
+
+/// Sample skewness G1 = sqrt(n(n - 1)) / (n - 2) * m_3 / m_2^(3/2), as reported by SPSS and Excel.
+pub fn skewness(data: &[f64]) -> Result<f64, Error> {
+    if data.len() < 3 {
+        return Err(anyhow!("Skewness requires at least three observations"));
+    }
+    let n = data.len() as f64;
+    let (m_2, m_3, _) = central_moments(data);
+    Ok(f64::sqrt(n * (n - 1.0)) / (n - 2.0) * m_3 / f64::powf(m_2, 1.5))
+}
+
+/// Sample excess kurtosis G2 = ((n + 1) g_2 + 6)(n - 1) / ((n - 2)(n - 3)), where
+/// g_2 = m_4 / m_2^2 - 3. A normal distribution has an excess kurtosis of 0.
+pub fn kurtosis(data: &[f64]) -> Result<f64, Error> {
+    if data.len() < 4 {
+        return Err(anyhow!("Kurtosis requires at least four observations"));
+    }
+    let n = data.len() as f64;
+    let (m_2, _, m_4) = central_moments(data);
+    let g_2 = m_4 / f64::powi(m_2, 2) - 3.0;
+    Ok(((n + 1.0) * g_2 + 6.0) * (n - 1.0) / ((n - 2.0) * (n - 3.0)))
+}
+
+/// Shapiro-Wilk W and its p-value, following Royston's (1995) algorithm AS R94
+/// for 3 <= n <= 5000.
+pub fn shapiro_wilk(data: &[f64]) -> Result<(f64, f64), Error> {
+    let n = data.len();
+    if !(3..=5000).contains(&n) {
+        return Err(anyhow!(
+            "Shapiro-Wilk requires between 3 and 5000 observations"
+        ));
+    }
+    let mut sorted = data.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    if sorted[n - 1] - sorted[0] < 1e-19 {
+        return Err(anyhow!("Shapiro-Wilk is undefined for data with no range"));
+    }
+
+    let polynomial = |coefficients: &[f64], x: f64| {
+        coefficients
+            .iter()
+            .rev()
+            .fold(0.0, |sum, coefficient| sum * x + coefficient)
+    };
+    let n_f = n as f64;
+
+    // coefficients a_i for the largest half of the order statistics
+    let half = n / 2;
+    let a = if n == 3 {
+        vec![std::f64::consts::FRAC_1_SQRT_2]
+    } else {
+        let m = (1..=half)
+            .map(|i| standard_normal_quantile((i as f64 - 0.375) / (n_f + 0.25)))
+            .collect::<Result<Vec<f64>, Error>>()?;
+        let sum_m_squared = 2.0 * m.iter().map(|m_i| m_i * m_i).sum::<f64>();
+        let root_sum_m_squared = f64::sqrt(sum_m_squared);
+        let u = 1.0 / f64::sqrt(n_f);
+
+        let mut a = m.iter().map(|m_i| -m_i).collect::<Vec<f64>>();
+        let a_1 = polynomial(
+            &[0.0, 0.221157, -0.147981, -2.07119, 4.434685, -2.706056],
+            u,
+        ) - m[0] / root_sum_m_squared;
+        let (first_scaled, phi) = if n > 5 {
+            let a_2 = polynomial(
+                &[0.0, 0.042981, -0.293762, -1.752461, 5.682633, -3.582633],
+                u,
+            ) - m[1] / root_sum_m_squared;
+            let phi = (sum_m_squared - 2.0 * m[0] * m[0] - 2.0 * m[1] * m[1])
+                / (1.0 - 2.0 * a_1 * a_1 - 2.0 * a_2 * a_2);
+            a[1] = a_2;
+            (2, phi)
+        } else {
+            let phi = (sum_m_squared - 2.0 * m[0] * m[0]) / (1.0 - 2.0 * a_1 * a_1);
+            (1, phi)
+        };
+        a[0] = a_1;
+        let epsilon = f64::sqrt(phi);
+        a.iter_mut()
+            .skip(first_scaled)
+            .for_each(|a_i| *a_i /= epsilon);
+        a
+    };
+
+    // W = (sum a_i (x_(n+1-i) - x_(i)))^2 / SS
+    let b = a
+        .iter()
+        .enumerate()
+        .map(|(i, a_i)| a_i * (sorted[n - 1 - i] - sorted[i]))
+        .sum::<f64>();
+    let mean = sorted.iter().sum::<f64>() / n_f;
+    let ss = sorted.iter().map(|x| f64::powi(x - mean, 2)).sum::<f64>();
+    let w = f64::min(1.0, b * b / ss);
+
+    if n == 3 {
+        // exact: p = 6 / pi * (asin(sqrt(W)) - asin(sqrt(3 / 4)))
+        let p = 6.0 / std::f64::consts::PI * (f64::asin(f64::sqrt(w)) - f64::asin(f64::sqrt(0.75)));
+        return Ok((w, f64::max(0.0, p)));
+    }
+
+    // normalizing transformation of 1 - W
+    let mut y = f64::ln(1.0 - w);
+    let (mu, sigma) = if n <= 11 {
+        let gamma = polynomial(&[-2.273, 0.459], n_f);
+        if y >= gamma {
+            return Ok((w, 0.0));
+        }
+        y = -f64::ln(gamma - y);
+        (
+            polynomial(&[0.544, -0.39978, 0.025054, -6.714e-4], n_f),
+            f64::exp(polynomial(&[1.3822, -0.77857, 0.062767, -0.0020322], n_f)),
+        )
+    } else {
+        let ln_n = f64::ln(n_f);
+        (
+            polynomial(&[-1.5861, -0.31082, -0.083751, 0.0038915], ln_n),
+            f64::exp(polynomial(&[-0.4803, -0.082676, 0.0030302], ln_n)),
+        )
+    };
+    Ok((w, 1.0 - standard_normal_cdf((y - mu) / sigma)?))
+}
+
+/// Middle value of the sorted data, or the mean of the two middle values for even N.
+pub fn median(data: &[f64]) -> Result<f64, Error> {
+    if data.is_empty() {
+        return Err(anyhow!("The median of an empty data set is undefined"));
+    }
+    let mut sorted = data.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let middle = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        Ok((sorted[middle - 1] + sorted[middle]) / 2.0)
+    } else {
+        Ok(sorted[middle])
+    }
+}
+
+/// The p-th quantile (0 <= p <= 1), interpolating linearly between order statistics
+/// (Hyndman & Fan type 7, the default in R and numpy).
+pub fn quantile(data: &[f64], p: f64) -> Result<f64, Error> {
+    if data.is_empty() {
+        return Err(anyhow!("The quantile of an empty data set is undefined"));
+    }
+    if !(0.0..=1.0).contains(&p) {
+        return Err(anyhow!(
+            "Quantile probability must be between 0 and 1, got {}",
+            p
+        ));
+    }
+    let mut sorted = data.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let position = p * (sorted.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    Ok(sorted[lower] + (position - lower as f64) * (sorted[upper] - sorted[lower]))
+}
+
+/// Count, mean, variance, and range accumulated one value at a time with Welford's
+/// update, so data read in a single pass never has to be held.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunningStats {
+    pub n: usize,
+    mean: f64,
+    m2: f64, // sum of squared deviations from the running mean
+    min: f64,
+    max: f64,
+}
+
+impl RunningStats {
+    pub fn push(&mut self, x: f64) {
+        self.n += 1;
+        if self.n == 1 {
+            self.min = x;
+            self.max = x;
+        } else {
+            self.min = self.min.min(x);
+            self.max = self.max.max(x);
+        }
+        let delta = x - self.mean;
+        self.mean += delta / self.n as f64;
+        self.m2 += delta * (x - self.mean);
+    }
+
+    pub fn mean(&self) -> Result<f64, Error> {
+        match self.n {
+            0 => Err(anyhow!("The mean of an empty data set is undefined")),
+            _ => Ok(self.mean),
+        }
+    }
+
+    /// Sample variance (N - 1).
+    pub fn variance(&self) -> Result<f64, Error> {
+        match self.n {
+            0 | 1 => Err(anyhow!(
+                "The variance of fewer than two values is undefined"
+            )),
+            n => Ok(self.m2 / (n - 1) as f64),
+        }
+    }
+
+    pub fn standard_deviation(&self) -> Result<f64, Error> {
+        Ok(f64::sqrt(self.variance()?))
+    }
+
+    pub fn range(&self) -> Result<(f64, f64), Error> {
+        match self.n {
+            0 => Err(anyhow!("The range of an empty data set is undefined")),
+            _ => Ok((self.min, self.max)),
+        }
+    }
+}
+
+/// A running estimate of the p-th quantile in constant memory, by the P² algorithm (Jain &
+/// Chlamtac, 1985): five markers track the minimum, p/2, p, (1 + p)/2, and maximum
+/// quantiles, their heights adjusted parabolically as values arrive. Exact for up to
+/// five values.
+#[derive(Debug, Clone)]
+pub struct P2Quantile {
+    p: f64,
+    heights: Vec<f64>,    // the values themselves until there are five
+    positions: [f64; 5],  // actual marker positions, 1-based
+    desired: [f64; 5],    // where the markers should be
+    increments: [f64; 5], // how far each desired position moves per value
+}
+
+impl P2Quantile {
+    pub fn new(p: f64) -> Result<P2Quantile, Error> {
+        if !(0.0..=1.0).contains(&p) {
+            return Err(anyhow!(
+                "Quantile probability must be between 0 and 1, got {}",
+                p
+            ));
+        }
+        Ok(P2Quantile {
+            p,
+            heights: Vec::with_capacity(5),
+            positions: [1.0, 2.0, 3.0, 4.0, 5.0],
+            desired: [1.0, 1.0 + 2.0 * p, 1.0 + 4.0 * p, 3.0 + 2.0 * p, 5.0],
+            increments: [0.0, p / 2.0, p, (1.0 + p) / 2.0, 1.0],
+        })
+    }
+
+    pub fn push(&mut self, x: f64) {
+        if self.heights.len() < 5 {
+            self.heights.push(x);
+            if self.heights.len() == 5 {
+                self.heights.sort_by(|a, b| a.total_cmp(b));
+            }
+            return;
+        }
+
+        let heights = &mut self.heights;
+        // the cell x falls in, widening the extremes if it lies outside them
+        let cell = if x < heights[0] {
+            heights[0] = x;
+            0
+        } else if x >= heights[4] {
+            heights[4] = x;
+            3
+        } else {
+            (1..5).find(|&index| x < heights[index]).unwrap_or(4) - 1
+        };
+        for position in self.positions.iter_mut().skip(cell + 1) {
+            *position += 1.0;
+        }
+        for (desired, increment) in self.desired.iter_mut().zip(self.increments) {
+            *desired += increment;
+        }
+
+        for i in 1..4 {
+            let offset = self.desired[i] - self.positions[i];
+            let room_above = self.positions[i + 1] - self.positions[i] > 1.0;
+            let room_below = self.positions[i - 1] - self.positions[i] < -1.0;
+            if (offset >= 1.0 && room_above) || (offset <= -1.0 && room_below) {
+                let d = offset.signum();
+                let (n, q) = (&self.positions, &*heights);
+                let parabolic = q[i]
+                    + d / (n[i + 1] - n[i - 1])
+                        * ((n[i] - n[i - 1] + d) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                            + (n[i + 1] - n[i] - d) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]));
+                heights[i] = if q[i - 1] < parabolic && parabolic < q[i + 1] {
+                    parabolic
+                } else {
+                    let j = if d > 0.0 { i + 1 } else { i - 1 };
+                    q[i] + d * (q[j] - q[i]) / (n[j] - n[i])
+                };
+                self.positions[i] += d;
+            }
+        }
+    }
+
+    pub fn estimate(&self) -> Result<f64, Error> {
+        // the last marker's position is the count once there are more than five values
+        if self.heights.len() < 5 || self.positions[4] == 5.0 {
+            quantile(&self.heights, self.p)
+        } else {
+            Ok(self.heights[2])
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use crate::functions::csv::{is_missing, CSVData};
+use crate::functions::stats_math::{mean, standard_deviation};
+use crate::reporter::report;
+use anyhow::{anyhow, Error, Result};
+use clap::ValueEnum;
+
+/// A re-expression of a continuous column, applied before a statistic runs.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transform {
+    /// Natural log, ln(x); values must be positive
+    Log,
+    /// Square root; values must not be negative
+    Sqrt,
+    /// 1 / x; values must not be zero
+    Reciprocal,
+    /// z-standardization, (x - mean) / s
+    Z,
+    /// Mean-centering, x - mean
+    Center,
+}
+
+impl Transform {
+    pub fn label(self) -> &'static str {
+        match self {
+            Transform::Log => "natural log",
+            Transform::Sqrt => "square root",
+            Transform::Reciprocal => "reciprocal",
+            Transform::Z => "z-standardization",
+            Transform::Center => "mean-centering",
+        }
+    }
+
+    /// Transforms every value, or fails on the first value outside the domain.
+    pub fn apply(self, data: &[f64]) -> Result<Vec<f64>, Error> {
+        let outside_domain = |x: &f64| match self {
+            Transform::Log => *x <= 0.0,
+            Transform::Sqrt => *x < 0.0,
+            Transform::Reciprocal => *x == 0.0,
+            Transform::Z | Transform::Center => false,
+        };
+        if let Some(x) = data.iter().find(|x| outside_domain(x)) {
+            return Err(anyhow!("Cannot apply the {} to {}", self.label(), x));
+        }
+
+        Ok(match self {
+            Transform::Log => data.iter().map(|x| x.ln()).collect(),
+            Transform::Sqrt => data.iter().map(|x| x.sqrt()).collect(),
+            Transform::Reciprocal => data.iter().map(|x| 1.0 / x).collect(),
+            Transform::Z | Transform::Center => {
+                let data_mean = mean(&data.to_vec())?;
+                let divisor = if self == Transform::Z {
+                    standard_deviation(Some(&data.to_vec()), None, None)?
+                } else {
+                    1.0
+                };
+                if divisor == 0.0 {
+                    return Err(anyhow!(
+                        "Cannot apply the {} to constant data",
+                        self.label()
+                    ));
+                }
+                data.iter().map(|x| (x - data_mean) / divisor).collect()
+            }
+        })
+    }
+}
+
+/// Replaces the present values of a 0-based column with their transformed values.
+/// Missing cells are left as they are.
+pub fn transform_column(
+    csv_data: &mut CSVData,
+    column: usize,
+    transform: Transform,
+) -> Result<(), Error> {
+    let rows = (0..csv_data.column_count)
+        .filter(|row| !is_missing(&csv_data.data[csv_data.row_length * row + column]))
+        .collect::<Vec<usize>>();
+    let values = rows
+        .iter()
+        .map(|row| csv_data.get_datum::<f64>(*row, column, None))
+        .collect::<Result<Vec<f64>, _>>()?;
+    let transformed = transform
+        .apply(&values)
+        .map_err(|error| anyhow!("'{}': {}", csv_data.headers[column], error))?;
+
+    report!(
+        "Applying the {} to '{}'",
+        transform.label(),
+        csv_data.headers[column]
+    );
+    for (row, value) in rows.into_iter().zip(transformed) {
+        csv_data.set_datum(row, column, value.to_string());
+    }
+    Ok(())
+}
@@ -4,8 +4,10 @@ const FILE_PATH: &str = "./logs/stisty.log";
 const ARCHIVE_PATTERN: &str = "./logs/archive/stisty.{}.log";
 
 const FORMATTED_LINE_LENGTH: usize = 70;
+const FORMATTED_LINE_FILL: char = '=';
+const LOG_PREFIX_LENGTH: usize = "2024-01-01 00:00:00 INFO: ".len();
 
-use log::{LevelFilter, SetLoggerError};
+use log::{error, LevelFilter, SetLoggerError};
 use log4rs::{
     append::{
         console::{ConsoleAppender, Target},
@@ -17,13 +19,40 @@ use log4rs::{
     encode::pattern::PatternEncoder,
     filter::threshold::ThresholdFilter,
 };
+use std::io::IsTerminal;
+use std::sync::RwLock;
+
+/// How `format_title` draws banners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TitleStyle {
+    pub width: usize, // in characters, title included
+    pub fill: char,
+    pub enabled: bool, // false returns titles undecorated
+}
+
+impl Default for TitleStyle {
+    fn default() -> TitleStyle {
+        DEFAULT_TITLE_STYLE
+    }
+}
+
+const DEFAULT_TITLE_STYLE: TitleStyle = TitleStyle {
+    width: FORMATTED_LINE_LENGTH,
+    fill: FORMATTED_LINE_FILL,
+    enabled: true,
+};
+
+static TITLE_STYLE: RwLock<TitleStyle> = RwLock::new(DEFAULT_TITLE_STYLE);
 
 pub fn setup_logger() -> Result<(), SetLoggerError> {
     let level = log::LevelFilter::Info;
 
     // Build a stderr logger.
-    let stderr = ConsoleAppender::builder().target(Target::Stderr)
-        .encoder(Box::new(PatternEncoder::new("{d(%Y-%m-%d %H:%M:%S)(local)} {l}: {m}{n}")))
+    let stderr = ConsoleAppender::builder()
+        .target(Target::Stderr)
+        .encoder(Box::new(PatternEncoder::new(
+            "{d(%Y-%m-%d %H:%M:%S)(local)} {l}: {m}{n}",
+        )))
         .build();
 
     // Create a policy to use with the file logging
@@ -35,7 +64,9 @@ pub fn setup_logger() -> Result<(), SetLoggerError> {
 
     // Logging to log file. (with rolling)
     let logfile = log4rs::append::rolling_file::RollingFileAppender::builder()
-        .encoder(Box::new(PatternEncoder::new("{d(%Y-%m-%d %H:%M:%S)(local)} {l}: {m}{n}")))
+        .encoder(Box::new(PatternEncoder::new(
+            "{d(%Y-%m-%d %H:%M:%S)(local)} {l}: {m}{n}",
+        )))
         .build(FILE_PATH, Box::new(policy))
         .unwrap();
 
@@ -61,18 +92,77 @@ pub fn setup_logger() -> Result<(), SetLoggerError> {
     Ok(())
 }
 
+pub fn set_title_style(style: TitleStyle) {
+    *TITLE_STYLE.write().unwrap() = style;
+}
+
+pub fn title_style() -> TitleStyle {
+    *TITLE_STYLE.read().unwrap()
+}
+
+/// The default banner width, narrowed to fit the terminal (after the log line prefix)
+/// when stderr is a terminal smaller than that. Redirected output keeps the full width.
+pub fn default_title_width() -> usize {
+    if !std::io::stderr().is_terminal() {
+        return FORMATTED_LINE_LENGTH;
+    }
+    match crossterm::terminal::size() {
+        Ok((columns, _)) => {
+            FORMATTED_LINE_LENGTH.min((columns as usize).saturating_sub(LOG_PREFIX_LENGTH))
+        }
+        Err(_) => FORMATTED_LINE_LENGTH,
+    }
+}
+
+/// Centers `title` in a banner of the configured width and fill. Widths are counted
+/// in characters, so multi-byte titles line up; titles as wide as the banner are
+/// returned as they are.
 pub fn format_title(title: &str) -> String {
-    let mut line_length = FORMATTED_LINE_LENGTH;
-    if title.len() < FORMATTED_LINE_LENGTH {
-        line_length -= title.len();
-    } else {
+    let style = title_style();
+    let title_length = title.chars().count();
+    if !style.enabled || title_length >= style.width {
         return title.to_string();
     }
-    let prefix = "=".repeat(line_length / 2);
-    let suffix = "=".repeat(line_length / 2);
+    let line_length = style.width - title_length;
+    let prefix = style.fill.to_string().repeat(line_length / 2);
+    let suffix = style.fill.to_string().repeat(line_length - line_length / 2);
     let mut formatted_title = String::new();
     formatted_title.push_str(prefix.as_str());
     formatted_title.push_str(title);
     formatted_title.push_str(suffix.as_str());
     formatted_title
-}
\ No newline at end of file
+}
+
+/// The title inside a banner drawn by `format_title` with the configured style, or
+/// `None` if `line` isn't a banner. Untitled banners give an empty title.
+pub fn title_text(line: &str) -> Option<&str> {
+    let style = title_style();
+    (style.enabled
+        && line.chars().count() == style.width
+        && line.starts_with(style.fill)
+        && line.ends_with(style.fill))
+    .then(|| line.trim_matches(style.fill))
+}
+
+/// Replaces the default panic output (a raw message and backtrace hint) with a
+/// readable error routed through the logger, so the crash also lands in the log file.
+/// The process still exits with a nonzero status once the panic unwinds.
+pub fn setup_panic_hook() {
+    std::panic::set_hook(Box::new(|panic_info| {
+        let message = if let Some(message) = panic_info.payload().downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = panic_info.payload().downcast_ref::<String>() {
+            message.clone()
+        } else {
+            String::from("unknown cause")
+        };
+        let location = panic_info
+            .location()
+            .map(|location| format!(" ({}:{})", location.file(), location.line()))
+            .unwrap_or_default();
+
+        error!("Stisty stopped unexpectedly: {}{}", message, location);
+        error!("Details have been written to {}", FILE_PATH);
+        log::logger().flush();
+    }));
+}
@@ -1,14 +1,28 @@
+mod arg_handler;
+mod cache;
+mod config;
 mod data_types;
 mod error_types;
 mod functions;
 mod logging;
+mod menu;
+mod overview;
+mod provenance;
+mod report;
+mod reporter;
+mod scan;
+mod spec;
+mod stream;
 mod tests;
+mod timing;
 
-use crate::logging::{format_title, setup_logger};
-use crate::tests::tests::*;
-use anyhow::Result;
-use log::info;
-use std::{env, io};
+use crate::arg_handler::{handle_args, Cli};
+use crate::functions::graph::set_plot_backend;
+use crate::logging::{format_title, set_title_style, setup_logger, setup_panic_hook};
+use crate::menu::run_menu;
+use anyhow::{Error, Result};
+use log::{error, info};
+use std::process::ExitCode;
 
 // ratatui modules
 // use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
@@ -42,27 +56,27 @@ use std::{env, io};
 //     }
 // }
 
-// #[derive(Debug)]
-// struct Config {
-//     csv_file_path: String,
-//     test_name: String,
-// }
+fn main() -> ExitCode {
+    let cli = Cli::parse_chained();
 
-// fn parse_args(args: &[String]) -> Config {
-//     Config {
-//         csv_file_path: args[0].clone(),
-//         test_name: args[1].clone(),
-//     }
-// }
+    setup_logger().expect("Logging setup failed.");
+    setup_panic_hook();
+    reporter::set_reporter(cli.reporter.reporter());
+    set_title_style(cli.title_style());
+    set_plot_backend(cli.plot_backend);
 
-fn main() -> Result<()> {
-    // let args: Vec<String> = env::args().collect();
-    // let args = parse_args(&args);
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            error!("{:#}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
 
-    setup_logger().expect("Logging setup failed.");
+fn run(cli: Cli) -> Result<(), Error> {
     info!("{}", format_title(&*"Stisty"));
     info!("{}", format_title(&*""));
-    // info!("{:#?}", args);
 
     //////// ratatui ////////
     // let mut terminal = ratatui::init();
@@ -87,7 +101,12 @@ fn main() -> Result<()> {
     // run_gpa_test().expect("GPA test failed");
     // run_glasses_occupation_likes_test().expect("student eyes test failed");
     // run_anova_sample_test().expect("ANOVA sample test failed.");
-    run_exam_3_review_test().expect("Exam 3 review test failed.");
+    // run_exam_3_review_test().expect("Exam 3 review test failed.");
+
+    match cli.command {
+        Some(command) => handle_args(command)?,
+        None => run_menu()?,
+    }
 
     info!("{}", format_title(&*""));
 
@@ -0,0 +1,1082 @@
+use crate::arg_handler::{import_data, run_statistic, DataOptions, Labels, Statistic};
+use crate::config::{push_recent_file, recent_files};
+use crate::data_types::count_regression::CountModel;
+use crate::data_types::outliers::{column_outlier_rows, OutlierMethod};
+use crate::functions::csv::{is_missing, CSVData, ColumnType, MissingPolicy};
+use crate::functions::formula::Formula;
+use crate::functions::graph::default_bins;
+use crate::functions::json::JSON_EXTENSIONS;
+use crate::functions::stats_math::mean;
+use crate::functions::transform::{transform_column, Transform};
+use crate::reporter::report;
+use anyhow::{anyhow, Error, Result};
+use clap::ValueEnum;
+use inquire::autocompletion::{Autocomplete, Replacement};
+use inquire::list_option::ListOption;
+use inquire::validator::Validation;
+use inquire::{Confirm, CustomType, CustomUserError, InquireError, MultiSelect, Select, Text};
+use log::{error, info};
+use std::path::{Path, PathBuf};
+
+/// A statistic the menu offers: its label and the prompts that configure it.
+type MenuEntry = (&'static str, fn(&CSVData) -> Result<Statistic, Error>);
+
+const STATISTICS: [MenuEntry; 16] = [
+    ("Z Test", z_test_menu),
+    ("Single Sample t", single_sample_t_menu),
+    ("Paired Samples t", paired_samples_t_menu),
+    ("Independent Groups t", independent_groups_t_menu),
+    ("One Way ANOVA", anova_menu),
+    ("Mann-Whitney U", mann_whitney_u_menu),
+    ("Wilcoxon Signed-Rank", wilcoxon_signed_rank_menu),
+    ("Kruskal-Wallis H", kruskal_wallis_menu),
+    ("Correlation", correlation_menu),
+    ("Multiple Regression", multiple_regression_menu),
+    ("Linear Model (formula)", formula_menu),
+    ("Ordinal Regression", ordinal_regression_menu),
+    ("Count Regression", count_regression_menu),
+    ("Multiple Response", multiple_response_menu),
+    ("Histogram", histogram_menu),
+    ("Boxplot", boxplot_menu),
+];
+
+const SUGGEST: &str = "Not sure? Help me choose...";
+
+const RUN_ANOTHER: &str = "Run another statistic on this file";
+const OPEN_ANOTHER: &str = "Open another file";
+const QUIT: &str = "Quit";
+const NEXT_STEPS: [&str; 3] = [RUN_ANOTHER, OPEN_ANOTHER, QUIT];
+
+const CSV_EXTENSIONS: [&str; 3] = ["csv", "tsv", "txt"];
+const BROWSE_FOR_FILE: &str = "Browse for another file...";
+
+const NO_TRANSFORM: &str = "None";
+const NO_OUTLIER_CHECK: &str = "None";
+const NO_GROUPS: &str = "None";
+const CHART_FORMATS: [&str; 3] = ["HTML", "SVG", "PNG"];
+const DROP_ROWS: &str = "Drop the affected rows";
+const IMPUTE_MEAN: &str = "Impute with the mean of the remaining values";
+const KEEP_VALUES: &str = "Keep them as they are";
+
+/// Column types offered for each kind of variable a statistic takes.
+const CONTINUOUS: &[ColumnType] = &[ColumnType::Continuous];
+const CATEGORICAL: &[ColumnType] = &[ColumnType::Categorical, ColumnType::Boolean];
+
+/// A session of statistics on one file at a time. Esc at any prompt of a statistic goes
+/// back to the list of statistics, and Esc at the list (or at the first file prompt)
+/// ends the session. A statistic or file that fails is reported without ending it.
+pub fn run_menu() -> Result<(), Error> {
+    let Some(mut csv_data) = open_csv_until_valid()? else {
+        return Ok(());
+    };
+    info!("Press Esc at any prompt to go back");
+
+    // every statistic of the session reads the same data, so the columns parsed and the
+    // data arrays built for one are reused by the next
+    loop {
+        let mut options = STATISTICS
+            .iter()
+            .map(|(label, _)| *label)
+            .collect::<Vec<&str>>();
+        options.push(SUGGEST);
+        let label = match Select::new("Statistic to run:", options)
+            .with_help_message("esc to end the session")
+            .prompt_skippable()?
+        {
+            None => return Ok(()),
+            Some(SUGGEST) => match cancellable(suggest_statistic())? {
+                Some(Some(label)) => label,
+                _ => continue,
+            },
+            Some(label) => label,
+        };
+
+        match cancellable(configure_and_run(label, &csv_data)) {
+            Ok(Some(())) => {}
+            Ok(None) => continue,
+            Err(error) => error!("{:#}", error),
+        }
+
+        match Select::new("Next:", NEXT_STEPS.to_vec()).prompt_skippable()? {
+            Some(RUN_ANOTHER) => {}
+            Some(OPEN_ANOTHER) => {
+                if let Some(other) = open_csv_until_valid()? {
+                    csv_data = other;
+                }
+            }
+            _ => return Ok(()),
+        }
+    }
+}
+
+/// Prompts for a data file and imports it as the CLI would, adding it to the recent files.
+fn open_csv() -> Result<CSVData, Error> {
+    let csv_path = prompt_file_path("Path to CSV file:", &data_extensions())?;
+    let (csv_data, _) = import_data(Path::new(&csv_path), &DataOptions::default())?;
+    push_recent_file(Path::new(&csv_path));
+    Ok(csv_data)
+}
+
+/// Prompts for files until one imports, reporting each that fails; `None` on Esc.
+fn open_csv_until_valid() -> Result<Option<CSVData>, Error> {
+    loop {
+        match cancellable(open_csv()) {
+            Err(error) if error.downcast_ref::<InquireError>().is_none() => {
+                error!("{:#}", error)
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Extensions the file prompts offer: CSV, JSON, and Arrow IPC when built with it.
+fn data_extensions() -> Vec<&'static str> {
+    let extensions = CSV_EXTENSIONS.iter().chain(JSON_EXTENSIONS.iter());
+    #[cfg(feature = "arrow")]
+    let extensions = extensions.chain(crate::functions::arrow::ARROW_EXTENSIONS.iter());
+    extensions.copied().collect()
+}
+
+/// `result`, with Esc at one of its prompts turned into `None`.
+fn cancellable<T>(result: Result<T, Error>) -> Result<Option<T>, Error> {
+    match result {
+        Err(error)
+            if matches!(
+                error.downcast_ref::<InquireError>(),
+                Some(InquireError::OperationCanceled)
+            ) =>
+        {
+            Ok(None)
+        }
+        result => result.map(Some),
+    }
+}
+
+/// The `suggest` command: recommends a statistic and, if asked, runs it against `csv`
+/// (prompting for a file when none was given).
+pub fn run_suggest(csv: Option<PathBuf>) -> Result<(), Error> {
+    let Some(label) = suggest_statistic()? else {
+        return Ok(());
+    };
+    if !Confirm::new(&format!("Run {} now?", label))
+        .with_default(true)
+        .prompt()?
+    {
+        return Ok(());
+    }
+
+    let csv_path = match csv {
+        Some(csv_path) => csv_path,
+        None => PathBuf::from(prompt_file_path("Path to CSV file:", &data_extensions())?),
+    };
+    let (csv_data, _) = import_data(&csv_path, &DataOptions::default())?;
+    push_recent_file(&csv_path);
+
+    configure_and_run(label, &csv_data)
+}
+
+/// Prompts for the columns and arguments of the statistic named `label` (an entry of
+/// STATISTICS), then handles missing values and outliers and runs it.
+fn configure_and_run(label: &str, csv_data: &CSVData) -> Result<(), Error> {
+    let (_, menu) = STATISTICS
+        .iter()
+        .find(|(name, _)| *name == label)
+        .ok_or_else(|| anyhow!("No statistic named '{}'", label))?;
+    let mut statistic = menu(csv_data)?;
+    if let Some(labels) = statistic.labels_mut() {
+        *labels = prompt_labels()?;
+    }
+
+    // a copy shares the session's cache until a cell of it changes
+    let csv_data = handle_missing_and_outliers(csv_data.clone(), &statistic)?;
+
+    run_statistic(&csv_data, &statistic)?;
+    Ok(())
+}
+
+/// Walks the usual intro stats decision tree (the question, the outcome's level of
+/// measurement, the number of groups, and pairing) and logs why one statistic fits.
+/// Returns its STATISTICS entry, or None when none of them fits the design.
+fn suggest_statistic() -> Result<Option<&'static str>, Error> {
+    const ONE_SAMPLE: &str = "Compare one sample's mean with a known or hypothesized value";
+    const GROUPS: &str = "Compare groups or conditions";
+    const RELATIONSHIP: &str = "Measure the relationship between two variables";
+    const PREDICTION: &str = "Predict an outcome from one or more variables";
+
+    const CONTINUOUS: &str = "Continuous (interval or ratio), roughly normal";
+    const NOT_NORMAL: &str = "Continuous, but skewed or with outliers";
+    const ORDINAL: &str = "Ordinal (ranks or ratings)";
+
+    let (label, reason) = match Select::new(
+        "What do you want to find out?",
+        vec![ONE_SAMPLE, GROUPS, RELATIONSHIP, PREDICTION],
+    )
+    .prompt()?
+    {
+        ONE_SAMPLE => {
+            if Confirm::new("Do you know the population standard deviation (sigma)?")
+                .with_default(false)
+                .prompt()?
+            {
+                (
+                    "Z Test",
+                    "sigma is known, so the standard error needs no estimate",
+                )
+            } else {
+                (
+                    "Single Sample t",
+                    "sigma is estimated from the sample, so the t distribution applies",
+                )
+            }
+        }
+        GROUPS => {
+            let outcome = Select::new(
+                "What kind of outcome are you comparing?",
+                vec![CONTINUOUS, NOT_NORMAL, ORDINAL],
+            )
+            .prompt()?;
+            let parametric = outcome == CONTINUOUS;
+            let groups = Select::new(
+                "How many groups or conditions?",
+                vec!["Two", "Three or more"],
+            )
+            .prompt()?;
+            let paired = Confirm::new(
+                "Is each participant measured in every condition (paired or repeated measures)?",
+            )
+            .with_default(false)
+            .prompt()?;
+
+            match (groups == "Two", paired, parametric) {
+                (true, true, true) => (
+                    "Paired Samples t",
+                    "two related measurements of a normal outcome",
+                ),
+                (true, true, false) => (
+                    "Wilcoxon Signed-Rank",
+                    "two related measurements; ranks make no normality assumption",
+                ),
+                (true, false, true) => (
+                    "Independent Groups t",
+                    "two separate groups with a normal outcome",
+                ),
+                (true, false, false) => (
+                    "Mann-Whitney U",
+                    "two separate groups; ranks make no normality assumption",
+                ),
+                (false, false, true) => (
+                    "One Way ANOVA",
+                    "three or more separate groups with a normal outcome",
+                ),
+                (false, false, false) => (
+                    "Kruskal-Wallis H",
+                    "three or more separate groups; ranks make no normality assumption",
+                ),
+                (false, true, _) => {
+                    report!(
+                        "Three or more repeated measurements call for a repeated measures \
+                         ANOVA or Friedman test, which Stisty does not run yet"
+                    );
+                    return Ok(None);
+                }
+            }
+        }
+        RELATIONSHIP => (
+            "Correlation",
+            "Pearson's r for linear relationships, with Spearman's rho and Kendall's tau for \
+             ranked or non-normal data",
+        ),
+        PREDICTION => {
+            if Confirm::new("Is the outcome a set of ordered categories (e.g. a Likert rating)?")
+                .with_default(false)
+                .prompt()?
+            {
+                (
+                    "Ordinal Regression",
+                    "an ordinal outcome, modeled by cumulative logits without assuming equal \
+                     spacing between its levels",
+                )
+            } else if Confirm::new("Is the outcome a count (e.g. visits or errors)?")
+                .with_default(false)
+                .prompt()?
+            {
+                (
+                    "Count Regression",
+                    "a count outcome, modeled as Poisson, or negative binomial when it is \
+                     overdispersed",
+                )
+            } else if Confirm::new("Are any of the predictors categorical (groups or labels)?")
+                .with_default(false)
+                .prompt()?
+            {
+                (
+                    "Linear Model (formula)",
+                    "categorical predictors are dummy coded from a formula",
+                )
+            } else {
+                (
+                    "Multiple Regression",
+                    "a continuous outcome from continuous predictors",
+                )
+            }
+        }
+        _ => unreachable!(),
+    };
+
+    report!("Suggested statistic: {} ({})", label, reason);
+    Ok(Some(label))
+}
+
+/// Asks how to handle missing cells in the columns the statistic reads (when there are
+/// any) and whether to transform each continuous column, then reports outliers by a
+/// method the user picks and asks whether to drop, impute, or keep them before the
+/// statistic runs.
+fn handle_missing_and_outliers(
+    mut csv_data: CSVData,
+    statistic: &Statistic,
+) -> Result<CSVData, Error> {
+    let continuous_columns = statistic.continuous_columns(&csv_data);
+    let categorical_columns = statistic.categorical_columns(&csv_data);
+
+    let missing_count = categorical_columns
+        .iter()
+        .chain(continuous_columns.iter())
+        .map(|column| csv_data.get_missing_rows(*column).len())
+        .sum::<usize>();
+    if missing_count > 0 {
+        report!(
+            "The selected columns have {} missing value(s)",
+            missing_count
+        );
+        let policies = MissingPolicy::value_variants()
+            .iter()
+            .filter(|policy| **policy != MissingPolicy::Error)
+            .collect::<Vec<&MissingPolicy>>();
+        let labels = policies
+            .iter()
+            .map(|policy| {
+                policy
+                    .to_possible_value()
+                    .and_then(|value| value.get_help().map(|help| help.to_string()))
+                    .unwrap_or_else(|| format!("{:?}", policy))
+            })
+            .collect::<Vec<String>>();
+        let index = Select::new("How should missing values be handled?", labels)
+            .raw_prompt()?
+            .index;
+        csv_data = csv_data.apply_missing_policy(
+            *policies[index],
+            &continuous_columns,
+            &categorical_columns,
+        )?;
+    }
+
+    for column in continuous_columns.iter() {
+        let mut options = vec![NO_TRANSFORM];
+        options.extend(
+            Transform::value_variants()
+                .iter()
+                .map(|transform| transform.label()),
+        );
+        let message = format!("Transformation for '{}':", csv_data.headers[*column]);
+        let index = Select::new(&message, options).raw_prompt()?.index;
+        if let Some(transform) = index
+            .checked_sub(1)
+            .map(|index| Transform::value_variants()[index])
+        {
+            transform_column(&mut csv_data, *column, transform)?;
+        }
+    }
+
+    let Some(method) = (if continuous_columns.is_empty() {
+        None
+    } else {
+        select_outlier_method()?
+    }) else {
+        return Ok(csv_data);
+    };
+
+    for column in continuous_columns {
+        let outlier_rows = column_outlier_rows(&csv_data, column, method)?;
+        if !outlier_rows.is_empty() {
+            report!(
+                "'{}' has {} outlier(s) ({}) at row(s) {:?}",
+                csv_data.headers[column],
+                outlier_rows.len(),
+                method.label(),
+                outlier_rows
+            );
+            csv_data = resolve_flagged_rows(csv_data, column, &outlier_rows, "outliers")?;
+        }
+    }
+
+    Ok(csv_data)
+}
+
+fn select_outlier_method() -> Result<Option<OutlierMethod>, Error> {
+    let mut options = OutlierMethod::value_variants()
+        .iter()
+        .map(|method| method.label())
+        .collect::<Vec<String>>();
+    options.push(String::from(NO_OUTLIER_CHECK));
+    let index = Select::new("Outlier check:", options).raw_prompt()?.index;
+    Ok(OutlierMethod::value_variants().get(index).copied())
+}
+
+fn resolve_flagged_rows(
+    mut csv_data: CSVData,
+    column: usize,
+    flagged_rows: &[usize],
+    description: &str,
+) -> Result<CSVData, Error> {
+    let message = format!(
+        "How should {} in '{}' be handled?",
+        description, csv_data.headers[column]
+    );
+    match Select::new(&message, vec![DROP_ROWS, IMPUTE_MEAN, KEEP_VALUES]).prompt()? {
+        DROP_ROWS => Ok(csv_data.without_rows(flagged_rows)),
+        IMPUTE_MEAN => {
+            let remaining = (0..csv_data.column_count)
+                .filter(|row| !flagged_rows.contains(row))
+                .filter(|row| !is_missing(&csv_data.data[csv_data.row_length * row + column]))
+                .filter_map(|row| csv_data.get_datum::<f64>(row, column, None).ok())
+                .collect::<Vec<f64>>();
+            let imputed = mean(&remaining)?;
+            for row in flagged_rows {
+                csv_data.set_datum(*row, column, imputed.to_string());
+            }
+            Ok(csv_data)
+        }
+        _ => Ok(csv_data),
+    }
+}
+
+fn z_test_menu(csv_data: &CSVData) -> Result<Statistic, Error> {
+    let column = select_column(csv_data, "Continuous column:", CONTINUOUS)?;
+    let mu = CustomType::<f64>::new("Population mean (mu):").prompt()?;
+    let sigma = CustomType::<f64>::new("Population standard deviation (sigma):")
+        .with_validator(|sigma: &f64| {
+            if *sigma > 0.0 {
+                Ok(Validation::Valid)
+            } else {
+                Ok(Validation::Invalid("sigma must be positive".into()))
+            }
+        })
+        .prompt()?;
+    Ok(Statistic::ZTest {
+        column: Some(column),
+        column_name: None,
+        mu,
+        sigma,
+        labels: Labels::default(),
+    })
+}
+
+fn single_sample_t_menu(csv_data: &CSVData) -> Result<Statistic, Error> {
+    let column = select_column(csv_data, "Continuous column:", CONTINUOUS)?;
+    let mu = CustomType::<f64>::new("Population mean (mu):").prompt()?;
+    Ok(Statistic::SingleSampleT {
+        column: Some(column),
+        column_name: None,
+        mu,
+        export_derived: None,
+        labels: Labels::default(),
+    })
+}
+
+fn paired_samples_t_menu(csv_data: &CSVData) -> Result<Statistic, Error> {
+    let columns = select_columns(
+        csv_data,
+        "Paired continuous columns:",
+        CONTINUOUS,
+        2,
+        Some(2),
+    )?;
+    Ok(Statistic::PairedSamplesT {
+        column_x: Some(columns[0]),
+        column_x_name: None,
+        column_y: Some(columns[1]),
+        column_y_name: None,
+        export_derived: None,
+        labels: Labels::default(),
+    })
+}
+
+fn independent_groups_t_menu(csv_data: &CSVData) -> Result<Statistic, Error> {
+    let categorical_column =
+        select_column(csv_data, "Categorical column (two levels):", CATEGORICAL)?;
+    let continuous_column = select_column(csv_data, "Continuous column:", CONTINUOUS)?;
+    Ok(Statistic::IndependentGroupsT {
+        categorical_column: Some(categorical_column),
+        categorical_column_name: None,
+        continuous_column: Some(continuous_column),
+        continuous_column_name: None,
+        export_derived: None,
+        labels: Labels::default(),
+    })
+}
+
+fn anova_menu(csv_data: &CSVData) -> Result<Statistic, Error> {
+    let categorical_column = select_column(
+        csv_data,
+        "Categorical column (three or more levels):",
+        CATEGORICAL,
+    )?;
+    let continuous_column = select_column(csv_data, "Continuous column:", CONTINUOUS)?;
+    Ok(Statistic::Anova {
+        categorical_column: Some(categorical_column),
+        categorical_column_name: None,
+        continuous_column: Some(continuous_column),
+        continuous_column_name: None,
+        labels: Labels::default(),
+    })
+}
+
+fn mann_whitney_u_menu(csv_data: &CSVData) -> Result<Statistic, Error> {
+    let categorical_column =
+        select_column(csv_data, "Categorical column (two levels):", CATEGORICAL)?;
+    let continuous_column = select_column(csv_data, "Continuous column:", CONTINUOUS)?;
+    Ok(Statistic::MannWhitneyU {
+        categorical_column: Some(categorical_column),
+        categorical_column_name: None,
+        continuous_column: Some(continuous_column),
+        continuous_column_name: None,
+        labels: Labels::default(),
+    })
+}
+
+fn wilcoxon_signed_rank_menu(csv_data: &CSVData) -> Result<Statistic, Error> {
+    let columns = select_columns(
+        csv_data,
+        "Paired continuous columns:",
+        CONTINUOUS,
+        2,
+        Some(2),
+    )?;
+    Ok(Statistic::WilcoxonSignedRank {
+        column_x: Some(columns[0]),
+        column_x_name: None,
+        column_y: Some(columns[1]),
+        column_y_name: None,
+        labels: Labels::default(),
+    })
+}
+
+fn kruskal_wallis_menu(csv_data: &CSVData) -> Result<Statistic, Error> {
+    let categorical_column = select_column(csv_data, "Categorical column:", CATEGORICAL)?;
+    let continuous_column = select_column(csv_data, "Continuous column:", CONTINUOUS)?;
+    Ok(Statistic::KruskalWallis {
+        categorical_column: Some(categorical_column),
+        categorical_column_name: None,
+        continuous_column: Some(continuous_column),
+        continuous_column_name: None,
+        labels: Labels::default(),
+    })
+}
+
+fn correlation_menu(csv_data: &CSVData) -> Result<Statistic, Error> {
+    let columns = select_columns(
+        csv_data,
+        "Continuous columns to correlate:",
+        CONTINUOUS,
+        2,
+        Some(2),
+    )?;
+    Ok(Statistic::Correlation {
+        column_x: Some(columns[0]),
+        column_x_name: None,
+        column_y: Some(columns[1]),
+        column_y_name: None,
+        cluster: select_cluster_column(csv_data, &columns)?,
+        cluster_name: None,
+        labels: Labels::default(),
+    })
+}
+
+fn multiple_regression_menu(csv_data: &CSVData) -> Result<Statistic, Error> {
+    let dependent_column = select_column(csv_data, "Dependent (outcome) column:", CONTINUOUS)?;
+    let predictor_columns = select_columns(csv_data, "Predictor columns:", CONTINUOUS, 1, None)?;
+    let analyzed = std::iter::once(dependent_column)
+        .chain(predictor_columns.iter().copied())
+        .collect::<Vec<usize>>();
+    Ok(Statistic::MultiRegression {
+        dependent_column: Some(dependent_column),
+        dependent_column_name: None,
+        cluster: select_cluster_column(csv_data, &analyzed)?,
+        cluster_name: None,
+        predictor_columns,
+        predictor_column_names: vec![],
+        formula: None,
+        export_derived: None,
+        plots: None,
+        chart_out: None,
+        labels: Labels::default(),
+    })
+}
+
+/// Prompts for a column marking rows from the same subject (or other cluster), of any
+/// type and other than the `analyzed` columns, or `None` when the rows are independent.
+fn select_cluster_column(csv_data: &CSVData, analyzed: &[usize]) -> Result<Option<usize>, Error> {
+    let columns = (0..csv_data.row_length)
+        .filter(|column| !analyzed.contains(column))
+        .collect::<Vec<usize>>();
+    let mut options = vec![NO_GROUPS.to_string()];
+    options.extend(
+        columns
+            .iter()
+            .map(|column| csv_data.headers[*column].clone()),
+    );
+    Ok(Select::new(
+        "Cluster the rows by a subject ID column (repeated observations)?",
+        options,
+    )
+    .raw_prompt()?
+    .index
+    .checked_sub(1)
+    .map(|index| columns[index]))
+}
+
+fn formula_menu(csv_data: &CSVData) -> Result<Statistic, Error> {
+    let help_message = format!(
+        "e.g. y ~ a + b + a:b, or y ~ a * b; headers: {}",
+        csv_data.headers.join(", ")
+    );
+    let formula = Text::new("Formula:")
+        .with_help_message(&help_message)
+        .with_validator(|formula: &str| match Formula::parse(formula) {
+            Ok(_) => Ok(Validation::Valid),
+            Err(error) => Ok(Validation::Invalid(error.to_string().into())),
+        })
+        .prompt()?;
+    Ok(Statistic::Formula {
+        formula,
+        labels: Labels::default(),
+    })
+}
+
+fn ordinal_regression_menu(csv_data: &CSVData) -> Result<Statistic, Error> {
+    let Statistic::Formula { formula, .. } = formula_menu(csv_data)? else {
+        unreachable!()
+    };
+    let levels = Text::new("Levels of the response from lowest to highest:")
+        .with_help_message(
+            "separated by commas, e.g. Disagree,Neutral,Agree; leave empty for numbers",
+        )
+        .prompt()?;
+    Ok(Statistic::OrdinalRegression {
+        formula,
+        levels: Some(levels).filter(|levels| !levels.trim().is_empty()),
+        labels: Labels::default(),
+    })
+}
+
+fn count_regression_menu(csv_data: &CSVData) -> Result<Statistic, Error> {
+    let Statistic::Formula { formula, .. } = formula_menu(csv_data)? else {
+        unreachable!()
+    };
+    let options = CountModel::value_variants()
+        .iter()
+        .map(|model| model.label())
+        .collect::<Vec<&str>>();
+    let index = Select::new("Model:", options).raw_prompt()?.index;
+    Ok(Statistic::CountRegression {
+        formula,
+        model: CountModel::value_variants()[index],
+        labels: Labels::default(),
+    })
+}
+
+fn multiple_response_menu(csv_data: &CSVData) -> Result<Statistic, Error> {
+    let column = select_column(
+        csv_data,
+        "Multiple response column:",
+        &[ColumnType::Categorical],
+    )?;
+    let delimiter = Text::new("Delimiter between options:")
+        .with_default(";")
+        .with_validator(|delimiter: &str| {
+            if delimiter.is_empty() {
+                Ok(Validation::Invalid("The delimiter cannot be empty".into()))
+            } else {
+                Ok(Validation::Valid)
+            }
+        })
+        .prompt()?;
+    let groups = columns_of_type(csv_data, CATEGORICAL)
+        .into_iter()
+        .filter(|group| *group != column)
+        .collect::<Vec<usize>>();
+    let mut options = vec![NO_GROUPS.to_string()];
+    options.extend(groups.iter().map(|group| csv_data.headers[*group].clone()));
+    let by = Select::new("Compare options across a grouping column?", options)
+        .raw_prompt()?
+        .index
+        .checked_sub(1)
+        .map(|index| groups[index]);
+    Ok(Statistic::MultipleResponse {
+        column: Some(column),
+        column_name: None,
+        delimiter,
+        by,
+        by_name: None,
+        labels: Labels::default(),
+    })
+}
+
+fn histogram_menu(csv_data: &CSVData) -> Result<Statistic, Error> {
+    let column = select_column(csv_data, "Continuous column:", CONTINUOUS)?;
+    let (output, chart_out) = prompt_chart_output(&csv_data.headers[column], "histogram")?;
+    let bins = CustomType::<usize>::new("Number of bins:")
+        .with_default(default_bins(csv_data.column_count))
+        .with_validator(|bins: &usize| {
+            if *bins > 0 {
+                Ok(Validation::Valid)
+            } else {
+                Ok(Validation::Invalid("At least one bin is required".into()))
+            }
+        })
+        .prompt()?;
+    Ok(Statistic::Histogram {
+        column: Some(column),
+        column_name: None,
+        bins: Some(bins),
+        output,
+        chart_out,
+    })
+}
+
+fn boxplot_menu(csv_data: &CSVData) -> Result<Statistic, Error> {
+    let continuous_column = select_column(csv_data, "Continuous column:", CONTINUOUS)?;
+    let groups = columns_of_type(csv_data, CATEGORICAL);
+    let mut options = vec![NO_GROUPS.to_string()];
+    options.extend(groups.iter().map(|group| csv_data.headers[*group].clone()));
+    let categorical_column = Select::new("One box per level of a categorical column?", options)
+        .raw_prompt()?
+        .index
+        .checked_sub(1)
+        .map(|index| groups[index]);
+    let (output, chart_out) = prompt_chart_output(&csv_data.headers[continuous_column], "boxplot")?;
+    Ok(Statistic::Boxplot {
+        continuous_column: Some(continuous_column),
+        continuous_column_name: None,
+        categorical_column,
+        categorical_column_name: None,
+        output,
+        chart_out,
+    })
+}
+
+/// The name and description to print the result under, each left to the default when
+/// skipped.
+fn prompt_labels() -> Result<Labels, Error> {
+    let name = Text::new("Analysis name:")
+        .with_help_message("leave empty for one built from the column headers")
+        .prompt()?;
+    let description = Text::new("Description (hypothesis):")
+        .with_help_message("leave empty for one built from the column headers")
+        .prompt()?;
+    Ok(Labels {
+        name: Some(name).filter(|name| !name.trim().is_empty()),
+        description: Some(description).filter(|description| !description.trim().is_empty()),
+    })
+}
+
+/// Prompts for the chart's format, then the directory for HTML or the file for an image,
+/// as the chart statistics' `output` and `chart_out`.
+fn prompt_chart_output(column: &str, kind: &str) -> Result<(PathBuf, Option<PathBuf>), Error> {
+    let format = Select::new("Chart format:", CHART_FORMATS.to_vec()).prompt()?;
+    if format == CHART_FORMATS[0] {
+        let directory = Text::new("Directory to write the chart to:")
+            .with_default("graphics")
+            .prompt()?;
+        return Ok((PathBuf::from(expand_path(&directory)), None));
+    }
+    let default = format!("{}-{}.{}", column, kind, format.to_ascii_lowercase());
+    let file = Text::new("File to write the chart to:")
+        .with_default(&default)
+        .prompt()?;
+    Ok((
+        PathBuf::from("graphics"),
+        Some(PathBuf::from(expand_path(&file))),
+    ))
+}
+
+/// Offers recently used files first, falling back to a free-text path prompt
+/// whose suggestions are limited to `extensions`.
+fn prompt_file_path(message: &str, extensions: &[&'static str]) -> Result<String, Error> {
+    let mut options = recent_files();
+    if !options.is_empty() {
+        options.push(String::from(BROWSE_FOR_FILE));
+        let choice = Select::new(message, options).prompt()?;
+        if choice != BROWSE_FOR_FILE {
+            return Ok(choice);
+        }
+    }
+    let path = Text::new(message)
+        .with_autocomplete(FilePathCompleter::new(extensions))
+        .prompt()?;
+    Ok(expand_path(&path))
+}
+
+/// Expands a leading `~` to the home directory and `$VAR` / `${VAR}` to the value
+/// of the environment variable. Unset variables are left as written.
+pub fn expand_path(input: &str) -> String {
+    let mut expanded = match (input.strip_prefix('~'), dirs::home_dir()) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+            format!("{}{}", home.to_string_lossy(), rest)
+        }
+        _ => input.to_string(),
+    };
+
+    let mut search_from = 0;
+    while let Some(offset) = expanded[search_from..].find('$') {
+        let start = search_from + offset;
+        let (name, end) = if expanded[start + 1..].starts_with('{') {
+            match expanded[start + 2..].find('}') {
+                Some(close) => (
+                    expanded[start + 2..start + 2 + close].to_string(),
+                    start + 3 + close,
+                ),
+                None => break,
+            }
+        } else {
+            let length = expanded[start + 1..]
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(expanded.len() - start - 1);
+            (
+                expanded[start + 1..start + 1 + length].to_string(),
+                start + 1 + length,
+            )
+        };
+
+        match std::env::var(&name) {
+            Ok(value) if !name.is_empty() => {
+                expanded.replace_range(start..end, &value);
+                search_from = start + value.len();
+            }
+            _ => search_from = end.max(start + 1),
+        }
+    }
+
+    expanded
+}
+
+/// The 0-based columns whose inferred type is one of `types`.
+fn columns_of_type(csv_data: &CSVData, types: &[ColumnType]) -> Vec<usize> {
+    (0..csv_data.row_length)
+        .filter(|column| types.contains(&csv_data.column_type(*column)))
+        .collect()
+}
+
+/// Headers of `columns`, for a prompt; fails when there are none to offer.
+fn column_options(
+    csv_data: &CSVData,
+    columns: &[usize],
+    types: &[ColumnType],
+) -> Result<Vec<String>, Error> {
+    if columns.is_empty() {
+        return Err(anyhow!(
+            "This file has no {} columns",
+            types
+                .iter()
+                .map(ColumnType::label)
+                .collect::<Vec<&str>>()
+                .join(" or ")
+        ));
+    }
+    Ok(columns
+        .iter()
+        .map(|column| csv_data.headers[*column].clone())
+        .collect())
+}
+
+/// Prompts for a single header among the columns of the given types and returns its
+/// 0-based column index.
+fn select_column(csv_data: &CSVData, message: &str, types: &[ColumnType]) -> Result<usize, Error> {
+    let columns = columns_of_type(csv_data, types);
+    let options = column_options(csv_data, &columns, types)?;
+    Ok(columns[Select::new(message, options).raw_prompt()?.index])
+}
+
+/// Prompts for several headers at once among the columns of the given types and returns
+/// their 0-based column indices. Selections are returned in header order, not the order
+/// they were checked.
+fn select_columns(
+    csv_data: &CSVData,
+    message: &str,
+    types: &[ColumnType],
+    min: usize,
+    max: Option<usize>,
+) -> Result<Vec<usize>, Error> {
+    let columns = columns_of_type(csv_data, types);
+    let options = column_options(csv_data, &columns, types)?;
+    let validator = move |selected: &[ListOption<&String>]| {
+        let count = selected.len();
+        match max {
+            Some(max) if min == max && count != min => Ok(Validation::Invalid(
+                format!("Select exactly {} columns", min).into(),
+            )),
+            Some(max) if count > max => Ok(Validation::Invalid(
+                format!("Select at most {} columns", max).into(),
+            )),
+            _ if count < min => Ok(Validation::Invalid(
+                format!("Select at least {} columns", min).into(),
+            )),
+            _ => Ok(Validation::Valid),
+        }
+    };
+
+    Ok(MultiSelect::new(message, options)
+        .with_help_message(
+            "type to filter, space to select, enter to confirm (used in header order)",
+        )
+        .with_validator(validator)
+        .raw_prompt()?
+        .into_iter()
+        .map(|option| columns[option.index])
+        .collect())
+}
+
+/// Suggests filesystem entries under the directory of the current input,
+/// completing to the longest common prefix when nothing is highlighted.
+/// `~` and `$VAR`/`${VAR}` are expanded, files are limited to `extensions`
+/// (directories are always offered), and hidden entries are skipped unless
+/// the typed name itself starts with a `.`.
+#[derive(Clone, Default)]
+pub struct FilePathCompleter {
+    input: String,
+    paths: Vec<String>,
+    extensions: Vec<&'static str>,
+}
+
+impl FilePathCompleter {
+    /// Completer offering only files with one of the given extensions (case-insensitive).
+    /// An empty list offers every file.
+    pub fn new(extensions: &[&'static str]) -> FilePathCompleter {
+        FilePathCompleter {
+            extensions: extensions.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    fn update_input(&mut self, input: &str) -> Result<(), CustomUserError> {
+        let input = expand_path(input);
+        if input == self.input && !self.paths.is_empty() {
+            return Ok(());
+        }
+
+        self.input = input.clone();
+        self.paths.clear();
+
+        let input_path = Path::new(&input);
+        let fallback_parent = input_path
+            .parent()
+            .map(|p| {
+                if p.to_string_lossy().is_empty() {
+                    Path::new(".")
+                } else {
+                    p
+                }
+            })
+            .unwrap_or(Path::new("."));
+
+        let scan_dir = if input.ends_with('/') {
+            input_path
+        } else {
+            fallback_parent
+        };
+
+        let entries = match std::fs::read_dir(scan_dir) {
+            Ok(read_dir) => Ok(read_dir),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                std::fs::read_dir(fallback_parent)
+            }
+            Err(err) => Err(err),
+        }?;
+
+        let typed_name = if input.ends_with('/') {
+            ""
+        } else {
+            input_path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or("")
+        };
+        let show_hidden = typed_name.starts_with('.');
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if is_hidden && !show_hidden {
+                continue;
+            }
+            if !path.is_dir() && !self.has_allowed_extension(&path) {
+                continue;
+            }
+
+            let path_str = if path.is_dir() {
+                format!("{}/", path.to_string_lossy())
+            } else {
+                path.to_string_lossy().to_string()
+            };
+            // read_dir on "." yields "./name"; keep suggestions relative to what was typed
+            let path_str = if !input.starts_with("./") && scan_dir == Path::new(".") {
+                path_str.trim_start_matches("./").to_string()
+            } else {
+                path_str
+            };
+            if path_str.starts_with(&self.input) && path_str.len() != self.input.len() {
+                self.paths.push(path_str);
+            }
+        }
+        self.paths.sort();
+
+        Ok(())
+    }
+
+    fn has_allowed_extension(&self, path: &Path) -> bool {
+        self.extensions.is_empty()
+            || path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .map(|extension| {
+                    self.extensions
+                        .iter()
+                        .any(|allowed| allowed.eq_ignore_ascii_case(extension))
+                })
+                .unwrap_or(false)
+    }
+
+    fn longest_common_prefix(&self) -> String {
+        let mut prefix = match self.paths.first() {
+            Some(first) => first.clone(),
+            None => return self.input.clone(),
+        };
+        for path in self.paths.iter().skip(1) {
+            while !path.starts_with(&prefix) {
+                prefix.pop();
+            }
+        }
+        prefix
+    }
+}
+
+impl Autocomplete for FilePathCompleter {
+    fn get_suggestions(&mut self, input: &str) -> Result<Vec<String>, CustomUserError> {
+        self.update_input(input)?;
+        Ok(self.paths.clone())
+    }
+
+    fn get_completion(
+        &mut self,
+        input: &str,
+        highlighted_suggestion: Option<String>,
+    ) -> Result<Replacement, CustomUserError> {
+        self.update_input(input)?;
+
+        Ok(match highlighted_suggestion {
+            Some(suggestion) => Some(suggestion),
+            None => Some(self.longest_common_prefix()),
+        })
+    }
+}
@@ -0,0 +1,655 @@
+const FREQUENCY_LIMIT: usize = 20;
+const MISSINGNESS_BINS: usize = 40;
+
+use crate::functions::csv::{is_missing, CSVData, ColumnType};
+use crate::functions::stats_math::{kurtosis, median, pearson_r_method_1, quantile, skewness};
+use crate::logging;
+use crate::report::{escape, html_head, write_file};
+use crate::reporter::report;
+use anyhow::{anyhow, Error, Result};
+use chrono::Local;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+
+/// The file `--output` writes, chosen by the extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverviewFormat {
+    Html,
+    Json,
+}
+
+impl OverviewFormat {
+    pub fn from_path(path: &Path) -> Result<OverviewFormat, Error> {
+        match path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_lowercase())
+            .as_deref()
+        {
+            Some("html") | Some("htm") => Ok(OverviewFormat::Html),
+            Some("json") => Ok(OverviewFormat::Json),
+            _ => Err(anyhow!(
+                "Overview '{}' needs a .html or .json extension",
+                path.display()
+            )),
+        }
+    }
+}
+
+/// A summary of every column of a file: its type and missing cells, descriptives for the
+/// numeric columns, frequencies for the categorical ones, where the missing cells fall,
+/// and the correlations between the numeric columns.
+#[derive(Serialize, Debug, Clone)]
+pub struct Overview {
+    pub stisty_version: String,
+    pub timestamp: String, // RFC 3339, local time
+    pub input_file: String,
+    pub input_sha256: String,
+    pub rows: usize,
+    pub columns: Vec<ColumnOverview>,
+    pub missingness: Missingness,
+    pub correlations: CorrelationMatrix,
+}
+
+/// A column is numeric when every present cell parses as a number.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnKind {
+    Numeric,
+    Categorical,
+    Empty, // every cell missing
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ColumnOverview {
+    pub index: usize,
+    pub name: String,
+    pub kind: ColumnKind,
+    pub missing: usize,
+    pub distinct: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub descriptives: Option<NumericSummary>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub frequencies: Vec<Frequency>, // most frequent first, at most FREQUENCY_LIMIT
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct NumericSummary {
+    pub n: usize,
+    pub mean: f64,
+    pub standard_deviation: f64,
+    pub min: f64,
+    pub first_quartile: f64,
+    pub median: f64,
+    pub third_quartile: f64,
+    pub max: f64,
+    pub skewness: Option<f64>, // None below three values
+    pub kurtosis: Option<f64>, // None below four values
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Frequency {
+    pub level: String,
+    pub count: usize,
+}
+
+/// The share of each column's cells that are missing, over consecutive blocks of rows,
+/// for drawing as a heat map.
+#[derive(Serialize, Debug, Clone)]
+pub struct Missingness {
+    pub rows_per_bin: usize,
+    pub fractions: Vec<Vec<f64>>, // one per column, one entry per bin
+}
+
+/// Pearson r between each pair of numeric columns over the rows where both are present.
+#[derive(Serialize, Debug, Clone)]
+pub struct CorrelationMatrix {
+    pub columns: Vec<String>,
+    pub r: Vec<Vec<Option<f64>>>, // None when fewer than three pairs or a constant column
+    pub n: Vec<Vec<usize>>,
+}
+
+impl Overview {
+    pub fn new(
+        input_file: &Path,
+        input_sha256: &str,
+        csv_data: &CSVData,
+    ) -> Result<Overview, Error> {
+        report!("...Summarizing '{}'...", input_file.display());
+        let columns = (0..csv_data.row_length)
+            .map(|column| column_overview(csv_data, column))
+            .collect::<Result<Vec<ColumnOverview>, Error>>()?;
+        let correlations = correlation_matrix(csv_data, &columns)?;
+
+        Ok(Overview {
+            stisty_version: env!("CARGO_PKG_VERSION").to_string(),
+            timestamp: Local::now().to_rfc3339(),
+            input_file: input_file.to_string_lossy().to_string(),
+            input_sha256: input_sha256.to_string(),
+            rows: csv_data.column_count,
+            missingness: missingness(csv_data),
+            columns,
+            correlations,
+        })
+    }
+
+    pub fn print(&self) {
+        let missing = self
+            .columns
+            .iter()
+            .map(|column| column.missing)
+            .sum::<usize>();
+        let cells = self.rows * self.columns.len();
+        report!("{}", logging::format_title("Overview"));
+        report!("File..........................{}", self.input_file);
+        report!("Rows..........................{}", self.rows);
+        report!("Columns.......................{}", self.columns.len());
+        report!(
+            "Missing cells.................{} ({:.1}%)",
+            missing,
+            percent(missing, cells)
+        );
+
+        let name_width = self
+            .columns
+            .iter()
+            .map(|column| column.name.chars().count())
+            .chain(std::iter::once(6))
+            .max()
+            .unwrap_or_default();
+        report!("{}", logging::format_title("Columns"));
+        report!(
+            "{:<name_width$}  {:<11}  {:>7}  {:>8}",
+            "Column",
+            "Type",
+            "Missing",
+            "Distinct"
+        );
+        for column in self.columns.iter() {
+            report!(
+                "{:<name_width$}  {:<11}  {:>7}  {:>8}",
+                column.name,
+                format!("{:?}", column.kind).to_lowercase(),
+                column.missing,
+                column.distinct
+            );
+        }
+
+        let numeric = self
+            .columns
+            .iter()
+            .filter_map(|column| Some((column, column.descriptives.as_ref()?)))
+            .collect::<Vec<(&ColumnOverview, &NumericSummary)>>();
+        if !numeric.is_empty() {
+            report!("{}", logging::format_title("Descriptives"));
+            report!(
+                "{:<name_width$}  {:>5}  {:>9}  {:>9}  {:>9}  {:>9}  {:>9}",
+                "Column",
+                "n",
+                "M",
+                "SD",
+                "Min",
+                "Median",
+                "Max"
+            );
+            for (column, summary) in numeric {
+                report!(
+                    "{:<name_width$}  {:>5}  {:>9.3}  {:>9.3}  {:>9.3}  {:>9.3}  {:>9.3}",
+                    column.name,
+                    summary.n,
+                    summary.mean,
+                    summary.standard_deviation,
+                    summary.min,
+                    summary.median,
+                    summary.max
+                );
+            }
+        }
+
+        let categorical = self
+            .columns
+            .iter()
+            .filter(|column| column.kind == ColumnKind::Categorical)
+            .collect::<Vec<&ColumnOverview>>();
+        if !categorical.is_empty() {
+            report!("{}", logging::format_title("Frequencies"));
+            for column in categorical {
+                let present = self.rows - column.missing;
+                report!("{}", column.name);
+                for frequency in column.frequencies.iter() {
+                    report!(
+                        "..{}: {} ({:.1}%)",
+                        frequency.level,
+                        frequency.count,
+                        percent(frequency.count, present)
+                    );
+                }
+                if column.distinct > column.frequencies.len() {
+                    report!(
+                        "..({} more levels)",
+                        column.distinct - column.frequencies.len()
+                    );
+                }
+            }
+        }
+
+        let matrix = &self.correlations;
+        if matrix.columns.len() >= 2 {
+            let label_width = matrix
+                .columns
+                .iter()
+                .map(|name| name.chars().count())
+                .max()
+                .unwrap_or_default();
+            report!(
+                "{}",
+                logging::format_title("Correlations (Pearson r, pairwise complete)")
+            );
+            report!(
+                "{:<label_width$}{}",
+                "",
+                matrix
+                    .columns
+                    .iter()
+                    .map(|name| format!("  {:>w$}", name, w = name.chars().count().max(6)))
+                    .collect::<String>()
+            );
+            for (name, row) in matrix.columns.iter().zip(matrix.r.iter()) {
+                report!(
+                    "{:<label_width$}{}",
+                    name,
+                    row.iter()
+                        .zip(matrix.columns.iter())
+                        .map(|(r, column)| {
+                            let w = column.chars().count().max(6);
+                            match r {
+                                Some(r) => format!("  {:>w$.3}", r),
+                                None => format!("  {:>w$}", "n/a"),
+                            }
+                        })
+                        .collect::<String>()
+                );
+            }
+        }
+    }
+
+    /// Writes the overview to `path` as an HTML page or as JSON.
+    pub fn write(&self, path: &Path) -> Result<(), Error> {
+        let contents = match OverviewFormat::from_path(path)? {
+            OverviewFormat::Html => self.html(),
+            OverviewFormat::Json => serde_json::to_string_pretty(self)? + "\n",
+        };
+        write_file(path, contents)
+    }
+
+    fn html(&self) -> String {
+        let mut page = html_head("Stisty Overview");
+        let _ = writeln!(page, "<h1>Overview of {}</h1>", escape(&self.input_file));
+        let _ = writeln!(
+            page,
+            "<p>{} rows and {} columns, summarized on {} with stisty {}.</p>",
+            self.rows,
+            self.columns.len(),
+            escape(&self.timestamp),
+            escape(&self.stisty_version)
+        );
+
+        let _ = writeln!(
+            page,
+            "<h2>Columns</h2>\n<table>\n<thead><tr><th>Column</th><th>Type</th>\
+             <th>Missing</th><th>Distinct</th></tr></thead>"
+        );
+        for column in self.columns.iter() {
+            let _ = writeln!(
+                page,
+                "<tr><td>{}</td><td>{:?}</td><td class=\"number\">{}</td>\
+                 <td class=\"number\">{}</td></tr>",
+                escape(&column.name),
+                column.kind,
+                column.missing,
+                column.distinct
+            );
+        }
+        let _ = writeln!(page, "</table>");
+
+        let _ = writeln!(
+            page,
+            "<h2>Missing Values</h2>\n<p>Share of cells missing in each block of {} rows \
+             (darker is more).</p>\n<table>",
+            self.missingness.rows_per_bin
+        );
+        for (column, fractions) in self.columns.iter().zip(self.missingness.fractions.iter()) {
+            let _ = writeln!(
+                page,
+                "<tr><td>{}</td>{}</tr>",
+                escape(&column.name),
+                fractions
+                    .iter()
+                    .map(|fraction| format!(
+                        "<td title=\"{:.0}%\" style=\"padding: 0; width: 0.6em; \
+                         background: rgba(0, 0, 0, {:.2})\"></td>",
+                        100.0 * fraction,
+                        fraction
+                    ))
+                    .collect::<String>()
+            );
+        }
+        let _ = writeln!(page, "</table>");
+
+        let numeric = self
+            .columns
+            .iter()
+            .filter_map(|column| Some((column, column.descriptives.as_ref()?)))
+            .collect::<Vec<(&ColumnOverview, &NumericSummary)>>();
+        if !numeric.is_empty() {
+            let _ = writeln!(
+                page,
+                "<h2>Descriptive Statistics</h2>\n<table>\n<thead><tr><th>Variable</th>\
+                 <th><i>n</i></th><th><i>M</i></th><th><i>SD</i></th><th>Min</th>\
+                 <th><i>Mdn</i></th><th>Max</th></tr></thead>"
+            );
+            for (column, summary) in numeric {
+                let _ = writeln!(
+                    page,
+                    "<tr><td>{}</td><td class=\"number\">{}</td>{}</tr>",
+                    escape(&column.name),
+                    summary.n,
+                    [
+                        summary.mean,
+                        summary.standard_deviation,
+                        summary.min,
+                        summary.median,
+                        summary.max
+                    ]
+                    .iter()
+                    .map(|value| format!("<td class=\"number\">{:.2}</td>", value))
+                    .collect::<String>()
+                );
+            }
+            let _ = writeln!(page, "</table>");
+        }
+
+        let categorical = self
+            .columns
+            .iter()
+            .filter(|column| column.kind == ColumnKind::Categorical)
+            .collect::<Vec<&ColumnOverview>>();
+        if !categorical.is_empty() {
+            let _ = writeln!(page, "<h2>Frequencies</h2>");
+            for column in categorical {
+                let present = self.rows - column.missing;
+                let _ = writeln!(
+                    page,
+                    "<h3>{}</h3>\n<table>\n<thead><tr><th>Level</th><th><i>n</i></th>\
+                     <th>%</th></tr></thead>",
+                    escape(&column.name)
+                );
+                for frequency in column.frequencies.iter() {
+                    let _ = writeln!(
+                        page,
+                        "<tr><td>{}</td><td class=\"number\">{}</td>\
+                         <td class=\"number\">{:.1}</td></tr>",
+                        escape(&frequency.level),
+                        frequency.count,
+                        percent(frequency.count, present)
+                    );
+                }
+                let _ = writeln!(page, "</table>");
+                if column.distinct > column.frequencies.len() {
+                    let _ = writeln!(
+                        page,
+                        "<p>{} more levels not shown.</p>",
+                        column.distinct - column.frequencies.len()
+                    );
+                }
+            }
+        }
+
+        let matrix = &self.correlations;
+        if matrix.columns.len() >= 2 {
+            let _ = writeln!(
+                page,
+                "<h2>Correlations</h2>\n<p>Pearson <i>r</i> over the rows where both \
+                 columns are present.</p>\n<table>\n<thead><tr><th></th>{}</tr></thead>",
+                matrix
+                    .columns
+                    .iter()
+                    .map(|name| format!("<th>{}</th>", escape(name)))
+                    .collect::<String>()
+            );
+            for (name, row) in matrix.columns.iter().zip(matrix.r.iter()) {
+                let _ = writeln!(
+                    page,
+                    "<tr><td>{}</td>{}</tr>",
+                    escape(name),
+                    row.iter()
+                        .map(|r| match r {
+                            Some(r) => format!(
+                                "<td class=\"number\" style=\"background: rgba({}, {:.2})\">\
+                                 {:.2}</td>",
+                                if *r < 0.0 { "180, 0, 0" } else { "0, 0, 180" },
+                                0.4 * r.abs(),
+                                r
+                            ),
+                            None => String::from("<td class=\"number\">n/a</td>"),
+                        })
+                        .collect::<String>()
+                );
+            }
+            let _ = writeln!(page, "</table>");
+        }
+
+        let _ = writeln!(
+            page,
+            "<h2>Provenance</h2>\n<table>\n<tr><td>Input SHA-256</td><td><code>{}</code></td></tr>\
+             \n</table>\n</body>\n</html>",
+            escape(&self.input_sha256)
+        );
+        page
+    }
+}
+
+/// Prints one line per column: its index, header, inferred type, number of levels (for a
+/// categorical or boolean column), missing cells, and its first `samples` distinct values.
+pub fn print_columns(csv_data: &CSVData, samples: usize) -> Result<(), Error> {
+    let columns = (0..csv_data.row_length)
+        .map(|column| column_overview(csv_data, column))
+        .collect::<Result<Vec<ColumnOverview>, Error>>()?;
+    let name_width = columns
+        .iter()
+        .map(|column| column.name.chars().count())
+        .chain(std::iter::once(6))
+        .max()
+        .unwrap_or_default();
+
+    report!("{}", logging::format_title("Columns"));
+    report!(
+        "{:>5}  {:<name_width$}  {:<11}  {:>6}  {:>7}  Values",
+        "Index",
+        "Header",
+        "Type",
+        "Levels",
+        "Missing"
+    );
+    for column in columns.iter() {
+        let mut values: Vec<&str> = vec![];
+        for row in 0..csv_data.column_count {
+            let value = cell(csv_data, row, column.index);
+            if values.len() == samples {
+                break;
+            }
+            if !is_missing(value) && !values.contains(&value) {
+                values.push(value);
+            }
+        }
+        let column_type = csv_data.column_type(column.index);
+        let more = if column.distinct > values.len() {
+            ", ..."
+        } else {
+            ""
+        };
+        report!(
+            "{:>5}  {:<name_width$}  {:<11}  {:>6}  {:>7}  {}{}",
+            column.index,
+            column.name,
+            column_type.label(),
+            match column_type {
+                ColumnType::Categorical | ColumnType::Boolean => column.distinct.to_string(),
+                _ => String::from("-"),
+            },
+            column.missing,
+            values.join(", "),
+            more
+        );
+    }
+    report!("Rows: {}", csv_data.column_count);
+    Ok(())
+}
+
+fn percent(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        100.0 * count as f64 / total as f64
+    }
+}
+
+pub fn cell(csv_data: &CSVData, row: usize, column: usize) -> &str {
+    &csv_data.data[csv_data.row_length * row + column]
+}
+
+pub fn column_overview(csv_data: &CSVData, column: usize) -> Result<ColumnOverview, Error> {
+    let present = (0..csv_data.column_count)
+        .map(|row| cell(csv_data, row, column))
+        .filter(|value| !is_missing(value))
+        .collect::<Vec<&str>>();
+    let numbers = present
+        .iter()
+        .map(|value| value.parse::<f64>())
+        .collect::<Result<Vec<f64>, _>>()
+        .ok();
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for value in present.iter() {
+        *counts.entry(value).or_default() += 1;
+    }
+    let kind = match numbers {
+        _ if present.is_empty() => ColumnKind::Empty,
+        Some(_) => ColumnKind::Numeric,
+        None => ColumnKind::Categorical,
+    };
+
+    let mut frequencies = vec![];
+    if kind == ColumnKind::Categorical {
+        let mut levels = counts.iter().collect::<Vec<(&&str, &usize)>>();
+        levels.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        frequencies = levels
+            .into_iter()
+            .take(FREQUENCY_LIMIT)
+            .map(|(level, count)| Frequency {
+                level: level.to_string(),
+                count: *count,
+            })
+            .collect();
+    }
+
+    Ok(ColumnOverview {
+        index: column,
+        name: csv_data
+            .headers
+            .get(column)
+            .cloned()
+            .unwrap_or_else(|| format!("Column {}", column)),
+        kind,
+        missing: csv_data.column_count - present.len(),
+        distinct: counts.len(),
+        descriptives: match numbers {
+            Some(numbers) if kind == ColumnKind::Numeric => Some(numeric_summary(&numbers)?),
+            _ => None,
+        },
+        frequencies,
+    })
+}
+
+fn numeric_summary(values: &[f64]) -> Result<NumericSummary, Error> {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|x| f64::powi(x - mean, 2)).sum::<f64>() / (n - 1.0);
+    Ok(NumericSummary {
+        n: values.len(),
+        mean,
+        standard_deviation: variance.sqrt(),
+        min: values.iter().copied().fold(f64::INFINITY, f64::min),
+        first_quartile: quantile(values, 0.25)?,
+        median: median(values)?,
+        third_quartile: quantile(values, 0.75)?,
+        max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        skewness: skewness(values).ok(),
+        kurtosis: kurtosis(values).ok(),
+    })
+}
+
+/// Splits the rows into at most `MISSINGNESS_BINS` consecutive blocks.
+fn missingness(csv_data: &CSVData) -> Missingness {
+    let rows_per_bin = csv_data.column_count.div_ceil(MISSINGNESS_BINS).max(1);
+    let fractions = (0..csv_data.row_length)
+        .map(|column| {
+            (0..csv_data.column_count)
+                .step_by(rows_per_bin)
+                .map(|start| {
+                    let end = (start + rows_per_bin).min(csv_data.column_count);
+                    let missing = (start..end)
+                        .filter(|row| is_missing(cell(csv_data, *row, column)))
+                        .count();
+                    missing as f64 / (end - start) as f64
+                })
+                .collect()
+        })
+        .collect();
+    Missingness {
+        rows_per_bin,
+        fractions,
+    }
+}
+
+fn correlation_matrix(
+    csv_data: &CSVData,
+    columns: &[ColumnOverview],
+) -> Result<CorrelationMatrix, Error> {
+    let numeric = columns
+        .iter()
+        .filter(|column| column.kind == ColumnKind::Numeric)
+        .collect::<Vec<&ColumnOverview>>();
+    let mut r = vec![vec![None; numeric.len()]; numeric.len()];
+    let mut n = vec![vec![0; numeric.len()]; numeric.len()];
+    for (i, column_x) in numeric.iter().enumerate() {
+        for (j, column_y) in numeric.iter().enumerate().skip(i) {
+            let (x, y): (Vec<f64>, Vec<f64>) = (0..csv_data.column_count)
+                .map(|row| {
+                    (
+                        cell(csv_data, row, column_x.index),
+                        cell(csv_data, row, column_y.index),
+                    )
+                })
+                .filter(|(x, y)| !is_missing(x) && !is_missing(y))
+                .map(|(x, y)| Ok((x.parse::<f64>()?, y.parse::<f64>()?)))
+                .collect::<Result<Vec<(f64, f64)>, Error>>()?
+                .into_iter()
+                .unzip();
+            let pair_r = if x.len() >= 3 {
+                Some(pearson_r_method_1(&x, &y, Some(false))?).filter(|r| r.is_finite())
+            } else {
+                None
+            };
+            (r[i][j], r[j][i]) = (pair_r, pair_r);
+            (n[i][j], n[j][i]) = (x.len(), x.len());
+        }
+    }
+    Ok(CorrelationMatrix {
+        columns: numeric.iter().map(|column| column.name.clone()).collect(),
+        r,
+        n,
+    })
+}
@@ -0,0 +1,68 @@
+use crate::arg_handler::{categorical_columns, continuous_columns, DataOptions, Statistic};
+use crate::functions::csv::CSVData;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+use std::path::Path;
+
+/// Where a result came from, embedded in every result stisty writes so the
+/// file alone is enough to reproduce it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Provenance {
+    pub stisty_version: String,
+    pub timestamp: String, // RFC 3339, local time
+    pub input_file: String,
+    pub input_sha256: String,
+    pub columns: Vec<String>, // headers of the columns the statistics read
+    pub options: String,      // how the data were read and prepared
+    pub statistic: String,    // the statistics and their arguments
+    pub seed: Option<u64>,    // None unless rows were sampled (--sample)
+}
+
+impl Provenance {
+    pub fn new(
+        input_file: &Path,
+        input_sha256: &str,
+        options: &DataOptions,
+        csv_data: &CSVData,
+        statistics: &[Statistic],
+    ) -> Provenance {
+        let columns = categorical_columns(statistics, csv_data)
+            .into_iter()
+            .chain(continuous_columns(statistics, csv_data))
+            .map(|column| {
+                csv_data
+                    .headers
+                    .get(column)
+                    .cloned()
+                    .unwrap_or_else(|| format!("Column {}", column))
+            })
+            .collect();
+
+        Provenance {
+            stisty_version: env!("CARGO_PKG_VERSION").to_string(),
+            timestamp: Local::now().to_rfc3339(),
+            input_file: input_file.to_string_lossy().to_string(),
+            input_sha256: input_sha256.to_string(),
+            columns,
+            options: format!("{:?}", options),
+            statistic: statistics
+                .iter()
+                .map(|statistic| format!("{:?}", statistic))
+                .collect::<Vec<String>>()
+                .join(" + "),
+            seed: options.seed,
+        }
+    }
+}
+
+/// Lowercase hex SHA-256 digest of `bytes`.
+pub fn sha256_hex(bytes: impl AsRef<[u8]>) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{:02x}", byte);
+            hex
+        })
+}
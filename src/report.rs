@@ -0,0 +1,490 @@
+const CHART_WIDTH: f64 = 480.0;
+const CHART_HEIGHT: f64 = 240.0;
+const CHART_MARGIN: f64 = 36.0;
+
+use crate::functions::csv::CSVData;
+use crate::logging::title_text;
+use crate::provenance::Provenance;
+use crate::reporter::{ReportLevel, ReportedLine};
+use anyhow::{anyhow, Error, Result};
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The kind of report `--report` writes, chosen by the file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    pub fn from_path(path: &Path) -> Result<ReportFormat, Error> {
+        match path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_lowercase())
+            .as_deref()
+        {
+            Some("md") | Some("markdown") => Ok(ReportFormat::Markdown),
+            Some("html") | Some("htm") => Ok(ReportFormat::Html),
+            _ => Err(anyhow!(
+                "Report '{}' needs a .md or .html extension",
+                path.display()
+            )),
+        }
+    }
+}
+
+/// n, M, SD, and range of a continuous column, as reported in an APA descriptives table.
+#[derive(Debug, Clone)]
+struct Descriptives {
+    name: String,
+    values: Vec<f64>,
+    mean: f64,
+    standard_deviation: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Descriptives {
+    fn new(name: String, values: Vec<f64>) -> Descriptives {
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let variance = values.iter().map(|x| f64::powi(x - mean, 2)).sum::<f64>() / (n - 1.0);
+        Descriptives {
+            name,
+            mean,
+            standard_deviation: variance.sqrt(),
+            min: values.iter().copied().fold(f64::INFINITY, f64::min),
+            max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            values,
+        }
+    }
+}
+
+/// A piece of the printed output, recognized from how the statistics format their lines.
+#[derive(Debug, Clone)]
+enum Block {
+    Heading(String),
+    Rows(Vec<Row>),
+    Preformatted(Vec<String>),
+    Warning(String),
+    Paragraph(String),
+}
+
+/// A `Key........value`, `Key: value`, or `..key: value` (nested) line.
+#[derive(Debug, Clone)]
+struct Row {
+    key: String,
+    value: String,
+    nested: bool,
+}
+
+/// Writes the statistic's output to `path` as a Markdown or HTML report: a descriptives
+/// table and a histogram for each continuous column, the results, and the provenance.
+/// Markdown reports link their histograms as SVG files written next to the report.
+pub fn write_report(
+    path: &Path,
+    provenance: &Provenance,
+    csv_data: &CSVData,
+    continuous_columns: &[usize],
+    lines: &[ReportedLine],
+) -> Result<(), Error> {
+    let format = ReportFormat::from_path(path)?;
+    let descriptives = continuous_columns
+        .iter()
+        .map(|column| {
+            Ok(Descriptives::new(
+                csv_data
+                    .headers
+                    .get(*column)
+                    .cloned()
+                    .unwrap_or_else(|| format!("Column {}", column)),
+                csv_data.get_column_cached::<f64>(*column)?.to_vec(),
+            ))
+        })
+        .collect::<Result<Vec<Descriptives>, Error>>()?;
+    let blocks = blocks(lines);
+
+    let report = match format {
+        ReportFormat::Markdown => {
+            let mut charts = vec![];
+            for (index, column) in descriptives.iter().enumerate() {
+                let chart_path = chart_path(path, index + 1);
+                write_file(&chart_path, histogram_svg(column))?;
+                charts.push(chart_path);
+            }
+            markdown(provenance, &descriptives, &charts, &blocks)
+        }
+        ReportFormat::Html => html(provenance, &descriptives, &blocks),
+    };
+    write_file(path, report)
+}
+
+pub fn write_file(path: &Path, contents: String) -> Result<(), Error> {
+    fs::write(path, contents)
+        .map_err(|error| anyhow!("Could not write '{}': {}", path.display(), error))
+}
+
+/// `<report stem>-figure-<n>.svg`, next to the report.
+fn chart_path(report_path: &Path, figure: usize) -> PathBuf {
+    let stem = report_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| String::from("report"));
+    report_path.with_file_name(format!("{}-figure-{}.svg", stem, figure))
+}
+
+fn blocks(lines: &[ReportedLine]) -> Vec<Block> {
+    let mut blocks: Vec<Block> = vec![];
+    for line in lines.iter() {
+        let message = line.message.as_str();
+        if line.level == ReportLevel::Warning {
+            blocks.push(Block::Warning(message.to_string()));
+            continue;
+        }
+        if let Some(title) = title_text(message) {
+            if !title.is_empty() {
+                blocks.push(Block::Heading(title.to_string()));
+            }
+            continue;
+        }
+        // progress, not results
+        if message.starts_with("...Calculating") || message.starts_with("Retrieving column") {
+            continue;
+        }
+
+        if message.contains("  ") && !message.starts_with("..") {
+            match blocks.last_mut() {
+                Some(Block::Preformatted(table)) => table.push(message.to_string()),
+                _ => blocks.push(Block::Preformatted(vec![message.to_string()])),
+            }
+        } else if let Some(row) = row(message) {
+            match blocks.last_mut() {
+                Some(Block::Rows(rows)) => rows.push(row),
+                _ => blocks.push(Block::Rows(vec![row])),
+            }
+        } else {
+            blocks.push(Block::Paragraph(message.to_string()));
+        }
+    }
+    blocks
+}
+
+fn row(message: &str) -> Option<Row> {
+    if let Some(nested) = message.strip_prefix("..") {
+        let (key, value) = nested.split_once(": ")?;
+        return Some(Row {
+            key: key.to_string(),
+            value: value.to_string(),
+            nested: true,
+        });
+    }
+
+    let dots = message.find("..");
+    let colon = message.find(": ");
+    let (key, value) = match (dots, colon) {
+        (Some(dots), colon) if dots > 0 && colon.is_none_or(|colon| dots < colon) => {
+            (&message[..dots], message[dots..].trim_start_matches('.'))
+        }
+        (_, Some(colon)) => (&message[..colon], &message[colon + 2..]),
+        _ => return None,
+    };
+    (!key.is_empty() && !value.is_empty()).then(|| Row {
+        key: key.to_string(),
+        value: value.to_string(),
+        nested: false,
+    })
+}
+
+fn markdown(
+    provenance: &Provenance,
+    descriptives: &[Descriptives],
+    charts: &[PathBuf],
+    blocks: &[Block],
+) -> String {
+    let cell = |text: &str| text.replace('|', "\\|");
+    let mut report = String::new();
+    let _ = writeln!(report, "# Stisty Report\n");
+    let _ = writeln!(
+        report,
+        "{} was analyzed on {} with stisty {}.\n",
+        cell(&provenance.input_file),
+        provenance.timestamp,
+        provenance.stisty_version
+    );
+
+    if !descriptives.is_empty() {
+        let _ = writeln!(report, "## Descriptive Statistics\n");
+        let _ = writeln!(report, "| Variable | *n* | *M* | *SD* | Min | Max |");
+        let _ = writeln!(report, "|---|---:|---:|---:|---:|---:|");
+        for column in descriptives {
+            let _ = writeln!(
+                report,
+                "| {} | {} | {:.2} | {:.2} | {:.2} | {:.2} |",
+                cell(&column.name),
+                column.values.len(),
+                column.mean,
+                column.standard_deviation,
+                column.min,
+                column.max
+            );
+        }
+        let _ = writeln!(report);
+        for (index, (column, chart)) in descriptives.iter().zip(charts.iter()).enumerate() {
+            let _ = writeln!(
+                report,
+                "![Distribution of {}]({})\n\n*Figure {}.* Distribution of {}.\n",
+                column.name,
+                chart
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                index + 1,
+                column.name
+            );
+        }
+    }
+
+    let _ = writeln!(report, "## Results\n");
+    for block in blocks {
+        match block {
+            Block::Heading(title) => {
+                let _ = writeln!(report, "### {}\n", title);
+            }
+            Block::Rows(rows) => {
+                let _ = writeln!(report, "| | |\n|---|---|");
+                for row in rows {
+                    let indent = if row.nested { "&emsp;" } else { "" };
+                    let _ = writeln!(
+                        report,
+                        "| {}{} | {} |",
+                        indent,
+                        cell(&row.key),
+                        cell(&row.value)
+                    );
+                }
+                let _ = writeln!(report);
+            }
+            Block::Preformatted(table) => {
+                let _ = writeln!(report, "```\n{}\n```\n", table.join("\n"));
+            }
+            Block::Warning(message) => {
+                let _ = writeln!(report, "> **Warning:** {}\n", message);
+            }
+            Block::Paragraph(message) => {
+                let _ = writeln!(report, "{}\n", message);
+            }
+        }
+    }
+
+    let _ = writeln!(report, "## Provenance\n");
+    let _ = writeln!(report, "| | |\n|---|---|");
+    for (key, value) in provenance_rows(provenance) {
+        let _ = writeln!(report, "| {} | `{}` |", key, cell(&value));
+    }
+    report
+}
+
+/// The start of an HTML page, through `<body>`, in the reports' APA-like style.
+pub fn html_head(title: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>\n\
+         body {{ font-family: \"Times New Roman\", serif; max-width: 48em; margin: 2em auto; }}\n\
+         table {{ border-collapse: collapse; margin: 1em 0; }}\n\
+         table, thead {{ border-top: 1px solid; border-bottom: 1px solid; }}\n\
+         th, td {{ padding: 0.2em 1em; text-align: left; }}\n\
+         td.number {{ text-align: right; }}\n\
+         td.nested {{ padding-left: 2em; }}\n\
+         .warning {{ color: #8a4b00; }}\n\
+         figcaption {{ font-style: italic; }}\n\
+         </style>\n</head>\n<body>\n",
+        escape(title)
+    )
+}
+
+fn html(provenance: &Provenance, descriptives: &[Descriptives], blocks: &[Block]) -> String {
+    let mut report = html_head("Stisty Report");
+    let _ = writeln!(report, "<h1>Stisty Report</h1>");
+    let _ = writeln!(
+        report,
+        "<p>{} was analyzed on {} with stisty {}.</p>",
+        escape(&provenance.input_file),
+        escape(&provenance.timestamp),
+        escape(&provenance.stisty_version)
+    );
+
+    if !descriptives.is_empty() {
+        let _ = writeln!(report, "<h2>Descriptive Statistics</h2>");
+        let _ = writeln!(
+            report,
+            "<table>\n<thead><tr><th>Variable</th><th><i>n</i></th><th><i>M</i></th>\
+             <th><i>SD</i></th><th>Min</th><th>Max</th></tr></thead>"
+        );
+        for column in descriptives {
+            let _ = writeln!(
+                report,
+                "<tr><td>{}</td><td class=\"number\">{}</td><td class=\"number\">{:.2}</td>\
+                 <td class=\"number\">{:.2}</td><td class=\"number\">{:.2}</td>\
+                 <td class=\"number\">{:.2}</td></tr>",
+                escape(&column.name),
+                column.values.len(),
+                column.mean,
+                column.standard_deviation,
+                column.min,
+                column.max
+            );
+        }
+        let _ = writeln!(report, "</table>");
+        for (index, column) in descriptives.iter().enumerate() {
+            let _ = writeln!(
+                report,
+                "<figure>\n{}\n<figcaption>Figure {}. Distribution of {}.</figcaption>\n</figure>",
+                histogram_svg(column),
+                index + 1,
+                escape(&column.name)
+            );
+        }
+    }
+
+    let _ = writeln!(report, "<h2>Results</h2>");
+    for block in blocks {
+        match block {
+            Block::Heading(title) => {
+                let _ = writeln!(report, "<h3>{}</h3>", escape(title));
+            }
+            Block::Rows(rows) => {
+                let _ = writeln!(report, "<table>");
+                for row in rows {
+                    let _ = writeln!(
+                        report,
+                        "<tr><td{}>{}</td><td>{}</td></tr>",
+                        if row.nested { " class=\"nested\"" } else { "" },
+                        escape(&row.key),
+                        escape(&row.value)
+                    );
+                }
+                let _ = writeln!(report, "</table>");
+            }
+            Block::Preformatted(table) => {
+                let _ = writeln!(report, "<pre>{}</pre>", escape(&table.join("\n")));
+            }
+            Block::Warning(message) => {
+                let _ = writeln!(
+                    report,
+                    "<p class=\"warning\"><b>Warning:</b> {}</p>",
+                    escape(message)
+                );
+            }
+            Block::Paragraph(message) => {
+                let _ = writeln!(report, "<p>{}</p>", escape(message));
+            }
+        }
+    }
+
+    let _ = writeln!(report, "<h2>Provenance</h2>\n<table>");
+    for (key, value) in provenance_rows(provenance) {
+        let _ = writeln!(
+            report,
+            "<tr><td>{}</td><td><code>{}</code></td></tr>",
+            key,
+            escape(&value)
+        );
+    }
+    let _ = writeln!(report, "</table>\n</body>\n</html>");
+    report
+}
+
+fn provenance_rows(provenance: &Provenance) -> Vec<(&'static str, String)> {
+    vec![
+        ("stisty version", provenance.stisty_version.clone()),
+        ("Timestamp", provenance.timestamp.clone()),
+        ("Input file", provenance.input_file.clone()),
+        ("Input SHA-256", provenance.input_sha256.clone()),
+        ("Columns", provenance.columns.join(", ")),
+        ("Options", provenance.options.clone()),
+        ("Statistic", provenance.statistic.clone()),
+    ]
+    .into_iter()
+    .chain(provenance.seed.map(|seed| ("Seed", seed.to_string())))
+    .collect()
+}
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A histogram of the column as a standalone SVG, with Sturges' number of bins.
+fn histogram_svg(column: &Descriptives) -> String {
+    let n = column.values.len();
+    let bins = ((n as f64).log2().ceil() as usize + 1).max(1);
+    let range = column.max - column.min;
+    let mut counts = vec![0usize; bins];
+    for value in column.values.iter() {
+        let bin = if range > 0.0 {
+            (((value - column.min) / range) * bins as f64) as usize
+        } else {
+            0
+        };
+        counts[bin.min(bins - 1)] += 1;
+    }
+    let max_count = counts.iter().copied().max().unwrap_or_default().max(1);
+
+    let plot_width = CHART_WIDTH - 2.0 * CHART_MARGIN;
+    let plot_height = CHART_HEIGHT - 2.0 * CHART_MARGIN;
+    let bar_width = plot_width / bins as f64;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+         viewBox=\"0 0 {w} {h}\" font-family=\"serif\" font-size=\"12\">\n",
+        w = CHART_WIDTH,
+        h = CHART_HEIGHT
+    );
+    for (index, count) in counts.iter().enumerate() {
+        let bar_height = plot_height * *count as f64 / max_count as f64;
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" \
+             fill=\"#b0b0b0\" stroke=\"black\"/>",
+            CHART_MARGIN + index as f64 * bar_width,
+            CHART_MARGIN + plot_height - bar_height,
+            bar_width,
+            bar_height
+        );
+    }
+    let bottom = CHART_MARGIN + plot_height;
+    let _ = writeln!(
+        svg,
+        "<line x1=\"{m}\" y1=\"{b}\" x2=\"{r}\" y2=\"{b}\" stroke=\"black\"/>\n\
+         <line x1=\"{m}\" y1=\"{m}\" x2=\"{m}\" y2=\"{b}\" stroke=\"black\"/>",
+        m = CHART_MARGIN,
+        b = bottom,
+        r = CHART_MARGIN + plot_width
+    );
+    let _ = writeln!(
+        svg,
+        "<text x=\"{m}\" y=\"{y}\" text-anchor=\"middle\">{min}</text>\n\
+         <text x=\"{r}\" y=\"{y}\" text-anchor=\"middle\">{max}</text>\n\
+         <text x=\"{c}\" y=\"{label}\" text-anchor=\"middle\">{name}</text>\n\
+         <text x=\"{tick}\" y=\"{top}\" text-anchor=\"end\">{count}</text>\n\
+         <text x=\"{tick}\" y=\"{b}\" text-anchor=\"end\">0</text>",
+        m = CHART_MARGIN,
+        r = CHART_MARGIN + plot_width,
+        c = CHART_MARGIN + plot_width / 2.0,
+        y = bottom + 14.0,
+        label = bottom + 28.0,
+        tick = CHART_MARGIN - 4.0,
+        top = CHART_MARGIN + 4.0,
+        b = bottom,
+        min = column.min,
+        max = column.max,
+        name = escape(&column.name),
+        count = max_count
+    );
+    svg.push_str("</svg>");
+    svg
+}
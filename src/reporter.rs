@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, RwLock};
+
+/// Severity of a reported line. Kept separate from `log::Level` so embedders
+/// don't need the `log` crate to receive output.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportLevel {
+    Info,
+    Warning,
+}
+
+/// A single reported line, as kept by `capture_output`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReportedLine {
+    pub level: ReportLevel,
+    pub message: String,
+}
+
+/// Receives everything the statistics and data arrays print.
+pub trait Reporter: Send + Sync {
+    fn report(&self, level: ReportLevel, message: &str);
+}
+
+/// Forwards to the `log` crate, i.e. the CLI's stderr and log file appenders.
+pub struct LogReporter;
+
+impl Reporter for LogReporter {
+    fn report(&self, level: ReportLevel, message: &str) {
+        match level {
+            ReportLevel::Info => log::info!("{}", message),
+            ReportLevel::Warning => log::warn!("{}", message),
+        }
+    }
+}
+
+/// Discards all output.
+pub struct SilentReporter;
+
+impl Reporter for SilentReporter {
+    fn report(&self, _level: ReportLevel, _message: &str) {}
+}
+
+/// Writes one `{"level": ..., "message": ...}` object per line to stdout.
+pub struct JsonReporter;
+
+#[derive(Serialize)]
+struct JsonLine<'a> {
+    level: ReportLevel,
+    message: &'a str,
+}
+
+impl Reporter for JsonReporter {
+    fn report(&self, level: ReportLevel, message: &str) {
+        if let Ok(line) = serde_json::to_string(&JsonLine { level, message }) {
+            println!("{}", line);
+        }
+    }
+}
+
+/// `None` until `set_reporter` is called, which reports through `LogReporter`.
+static REPORTER: RwLock<Option<Box<dyn Reporter>>> = RwLock::new(None);
+
+/// Messages reported while a capture is active; `None` when nothing is capturing.
+static CAPTURED_LINES: Mutex<Option<Vec<ReportedLine>>> = Mutex::new(None);
+
+/// Replaces the process-wide reporter.
+pub fn set_reporter(reporter: Box<dyn Reporter>) {
+    *REPORTER.write().unwrap() = Some(reporter);
+}
+
+pub fn report_message(level: ReportLevel, message: &str) {
+    if let Some(lines) = CAPTURED_LINES.lock().unwrap().as_mut() {
+        lines.push(ReportedLine {
+            level,
+            message: message.to_string(),
+        });
+    }
+    match REPORTER.read().unwrap().as_ref() {
+        Some(reporter) => reporter.report(level, message),
+        None => LogReporter.report(level, message),
+    }
+}
+
+/// Runs `f` and returns its result along with every message it reported,
+/// so the output can be stored and replayed later. Reporting is unaffected.
+pub fn capture_output<T>(
+    f: impl FnOnce() -> anyhow::Result<T>,
+) -> anyhow::Result<(T, Vec<ReportedLine>)> {
+    *CAPTURED_LINES.lock().unwrap() = Some(vec![]);
+    let result = f();
+    let lines = CAPTURED_LINES.lock().unwrap().take().unwrap_or_default();
+    Ok((result?, lines))
+}
+
+/// `report!("...", args)` reports a formatted line at `ReportLevel::Info`.
+macro_rules! report {
+    ($($arg:tt)+) => {
+        $crate::reporter::report_message($crate::reporter::ReportLevel::Info, &format!($($arg)+))
+    };
+}
+
+/// `report_warning!("...", args)` reports a formatted line at `ReportLevel::Warning`.
+macro_rules! report_warning {
+    ($($arg:tt)+) => {
+        $crate::reporter::report_message($crate::reporter::ReportLevel::Warning, &format!($($arg)+))
+    };
+}
+
+pub(crate) use {report, report_warning};
@@ -0,0 +1,359 @@
+const MAX_LEVELS: usize = 20;
+const ALPHA: f64 = 0.05;
+
+use crate::functions::csv::{is_missing, CSVData};
+use crate::functions::distributions::{chi_square_p, f_p, two_tailed_p_from_t};
+use crate::functions::stats_math::{cramers_v, pearson_r_method_1, phi};
+use crate::logging;
+use crate::overview::{cell, column_overview, ColumnKind, ColumnOverview};
+use crate::reporter::report;
+use anyhow::{Error, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// How strongly two columns go together, with the measure chosen by their types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Measure {
+    PearsonR, // numeric with numeric
+    Eta,      // categorical with numeric
+    Phi,      // two-level categorical with two-level categorical
+    CramersV, // categorical with categorical
+}
+
+impl Measure {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Measure::PearsonR => "Pearson r",
+            Measure::Eta => "eta",
+            Measure::Phi => "phi",
+            Measure::CramersV => "Cramér's V",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Association {
+    pub column_x: String,
+    pub column_y: String,
+    pub measure: Measure,
+    pub value: f64, // r and phi keep their sign; eta and V are 0 to 1
+    pub n: usize,   // rows where both columns are present
+    pub p: f64,
+    pub holm_p: f64, // adjusted over every pair scanned
+}
+
+impl Association {
+    pub fn strength(&self) -> f64 {
+        self.value.abs()
+    }
+}
+
+/// Every pair of usable columns of a file, ranked by the strength of their association.
+/// An exploratory search: the more pairs scanned, the more strong-looking relationships
+/// turn up by chance.
+#[derive(Debug, Clone)]
+pub struct Scan {
+    pub input_file: String,
+    pub associations: Vec<Association>, // strongest first
+    pub skipped: Vec<(String, String)>, // column and why
+    pub not_computable: usize,          // pairs without enough rows or variation
+}
+
+impl Scan {
+    pub fn new(input_file: &Path, csv_data: &CSVData, exclude: &[usize]) -> Result<Scan, Error> {
+        report!("...Scanning column pairs of '{}'...", input_file.display());
+        let mut columns = vec![];
+        let mut skipped = vec![];
+        for column in (0..csv_data.row_length).filter(|column| !exclude.contains(column)) {
+            let overview = column_overview(csv_data, column)?;
+            let present = csv_data.column_count - overview.missing;
+            match overview.kind {
+                ColumnKind::Empty => {
+                    skipped.push((overview.name.clone(), String::from("every cell is missing")))
+                }
+                ColumnKind::Categorical if overview.distinct > MAX_LEVELS => skipped.push((
+                    overview.name.clone(),
+                    format!("{} levels (more than {})", overview.distinct, MAX_LEVELS),
+                )),
+                ColumnKind::Categorical if overview.distinct == present => skipped.push((
+                    overview.name.clone(),
+                    String::from("every value is distinct, like an identifier"),
+                )),
+                _ => columns.push(overview),
+            }
+        }
+
+        let mut associations = vec![];
+        let mut not_computable = 0;
+        for (i, column_x) in columns.iter().enumerate() {
+            for column_y in columns.iter().skip(i + 1) {
+                match association(csv_data, column_x, column_y)? {
+                    Some(association) => associations.push(association),
+                    None => not_computable += 1,
+                }
+            }
+        }
+        holm_adjust(&mut associations);
+        associations.sort_by(|a, b| {
+            b.strength()
+                .total_cmp(&a.strength())
+                .then(a.p.total_cmp(&b.p))
+        });
+
+        Ok(Scan {
+            input_file: input_file.to_string_lossy().to_string(),
+            associations,
+            skipped,
+            not_computable,
+        })
+    }
+
+    pub fn print(&self, top: usize) {
+        let pairs = self.associations.len();
+        report!("{}", logging::format_title("Scan"));
+        report!("File..........................{}", self.input_file);
+        report!("Pairs scanned.................{}", pairs);
+        if self.not_computable > 0 {
+            report!(
+                "Pairs not computable..........{} (too few rows or no variation)",
+                self.not_computable
+            );
+        }
+        for (column, reason) in self.skipped.iter() {
+            report!("Skipped '{}': {}", column, reason);
+        }
+        if pairs == 0 {
+            report!("No pair of columns could be compared");
+            return;
+        }
+
+        let shown = &self.associations[..top.min(pairs)];
+        let labels = shown
+            .iter()
+            .map(|association| format!("{} × {}", association.column_x, association.column_y))
+            .collect::<Vec<String>>();
+        let label_width = labels
+            .iter()
+            .map(|label| label.chars().count())
+            .chain(std::iter::once(4))
+            .max()
+            .unwrap_or_default();
+        report!(
+            "{}",
+            logging::format_title(&format!(
+                "Strongest Relationships ({} of {})",
+                shown.len(),
+                pairs
+            ))
+        );
+        report!(
+            "{:>4}  {:<label_width$}  {:<10}  {:>7}  {:>4}  {:>7}  {:>8}",
+            "Rank",
+            "Pair",
+            "Measure",
+            "Value",
+            "n",
+            "p",
+            "p (Holm)"
+        );
+        for (rank, (association, label)) in shown.iter().zip(labels.iter()).enumerate() {
+            report!(
+                "{:>4}  {:<label_width$}  {:<10}  {:>7.3}  {:>4}  {:>7.4}  {:>8.4}",
+                rank + 1,
+                label,
+                association.measure.name(),
+                association.value,
+                association.n,
+                association.p,
+                association.holm_p
+            );
+        }
+
+        report!("{}", logging::format_title("Caveats"));
+        report!(
+            "With {} pairs scanned, about {:.1} would reach p < {} by chance alone even with \
+             no real relationships; the Holm column adjusts for all {} of them.",
+            pairs,
+            pairs as f64 * ALPHA,
+            ALPHA,
+            pairs
+        );
+        report!(
+            "Pearson r, eta, phi, and Cramér's V are on different scales, so ranks across \
+             measures are only a rough guide. Each pair uses the rows where both columns are \
+             present."
+        );
+        report!(
+            "These are leads to test on new data, not findings: a relationship picked out \
+             because it was the strongest will look weaker when measured again."
+        );
+    }
+}
+
+/// The present values of two columns, row by row.
+fn paired_cells<'a>(
+    csv_data: &'a CSVData,
+    column_x: &ColumnOverview,
+    column_y: &ColumnOverview,
+) -> Vec<(&'a str, &'a str)> {
+    (0..csv_data.column_count)
+        .map(|row| {
+            (
+                cell(csv_data, row, column_x.index),
+                cell(csv_data, row, column_y.index),
+            )
+        })
+        .filter(|(x, y)| !is_missing(x) && !is_missing(y))
+        .collect()
+}
+
+fn association(
+    csv_data: &CSVData,
+    column_x: &ColumnOverview,
+    column_y: &ColumnOverview,
+) -> Result<Option<Association>, Error> {
+    let pairs = paired_cells(csv_data, column_x, column_y);
+    let measured = match (column_x.kind, column_y.kind) {
+        (ColumnKind::Numeric, ColumnKind::Numeric) => {
+            let (x, y): (Vec<f64>, Vec<f64>) = pairs
+                .iter()
+                .map(|(x, y)| Ok((x.parse::<f64>()?, y.parse::<f64>()?)))
+                .collect::<Result<Vec<(f64, f64)>, Error>>()?
+                .into_iter()
+                .unzip();
+            pearson(&x, &y)?.map(|(r, p)| (Measure::PearsonR, r, p))
+        }
+        (ColumnKind::Categorical, ColumnKind::Numeric) => {
+            eta(&pairs)?.map(|(eta, p)| (Measure::Eta, eta, p))
+        }
+        (ColumnKind::Numeric, ColumnKind::Categorical) => {
+            let swapped = pairs.iter().map(|(x, y)| (*y, *x)).collect::<Vec<_>>();
+            eta(&swapped)?.map(|(eta, p)| (Measure::Eta, eta, p))
+        }
+        _ => categorical_association(&pairs)?,
+    };
+    Ok(measured.map(|(measure, value, p)| Association {
+        column_x: column_x.name.clone(),
+        column_y: column_y.name.clone(),
+        measure,
+        value,
+        n: pairs.len(),
+        p,
+        holm_p: p,
+    }))
+}
+
+/// r and its two-tailed p from t with n - 2 degrees of freedom.
+fn pearson(x: &[f64], y: &[f64]) -> Result<Option<(f64, f64)>, Error> {
+    if x.len() < 3 {
+        return Ok(None);
+    }
+    let r = pearson_r_method_1(&x.to_vec(), &y.to_vec(), Some(false))?;
+    if !r.is_finite() {
+        return Ok(None);
+    }
+    let df = (x.len() - 2) as f64;
+    let p = if r.abs() >= 1.0 {
+        0.0
+    } else {
+        two_tailed_p_from_t(r * (df / (1.0 - r * r)).sqrt(), df)?
+    };
+    Ok(Some((r, p)))
+}
+
+/// The correlation ratio of a numeric column on the levels of a categorical one (the
+/// square root of the one-way ANOVA's eta squared), and the ANOVA's p. `pairs` are
+/// (level, value).
+fn eta(pairs: &[(&str, &str)]) -> Result<Option<(f64, f64)>, Error> {
+    let mut groups: HashMap<&str, Vec<f64>> = HashMap::new();
+    for (level, value) in pairs.iter() {
+        groups.entry(level).or_default().push(value.parse::<f64>()?);
+    }
+    let n = pairs.len();
+    let k = groups.len();
+    if k < 2 || n <= k {
+        return Ok(None);
+    }
+
+    let grand_mean = groups.values().flatten().sum::<f64>() / n as f64;
+    let mut ss_between = 0.0;
+    let mut ss_within = 0.0;
+    for values in groups.values() {
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        ss_between += values.len() as f64 * f64::powi(mean - grand_mean, 2);
+        ss_within += values.iter().map(|x| f64::powi(x - mean, 2)).sum::<f64>();
+    }
+    let ss_total = ss_between + ss_within;
+    if ss_total == 0.0 {
+        return Ok(None);
+    }
+
+    let (df_between, df_within) = ((k - 1) as f64, (n - k) as f64);
+    let p = if ss_within == 0.0 {
+        0.0
+    } else {
+        f_p(
+            (ss_between / df_between) / (ss_within / df_within),
+            df_between,
+            df_within,
+        )?
+    };
+    Ok(Some(((ss_between / ss_total).sqrt(), p)))
+}
+
+/// Phi for a 2 x 2 table and Cramér's V for a larger one, from the contingency table of
+/// two categorical columns, with the Pearson chi-square test's p. Phi is positive when
+/// the first levels of each column (in sorted order) go together.
+fn categorical_association(pairs: &[(&str, &str)]) -> Result<Option<(Measure, f64, f64)>, Error> {
+    let mut counts: HashMap<(&str, &str), f64> = HashMap::new();
+    let mut row_totals: BTreeMap<&str, f64> = BTreeMap::new();
+    let mut column_totals: BTreeMap<&str, f64> = BTreeMap::new();
+    for (x, y) in pairs.iter() {
+        *counts.entry((x, y)).or_default() += 1.0;
+        *row_totals.entry(x).or_default() += 1.0;
+        *column_totals.entry(y).or_default() += 1.0;
+    }
+    let (r, c) = (row_totals.len(), column_totals.len());
+    if r < 2 || c < 2 {
+        return Ok(None);
+    }
+
+    let n = pairs.len() as f64;
+    let count = |x: &str, y: &str| counts.get(&(x, y)).copied().unwrap_or_default();
+    let mut chi_square = 0.0;
+    for (x, row_total) in row_totals.iter() {
+        for (y, column_total) in column_totals.iter() {
+            let expected = row_total * column_total / n;
+            chi_square += f64::powi(count(x, y) - expected, 2) / expected;
+        }
+    }
+    let p = chi_square_p(chi_square, ((r - 1) * (c - 1)) as f64)?;
+    if let ([x_1, x_2], [y_1, y_2]) = (
+        &row_totals.keys().collect::<Vec<_>>()[..],
+        &column_totals.keys().collect::<Vec<_>>()[..],
+    ) {
+        let table = [
+            [count(x_1, y_1), count(x_1, y_2)],
+            [count(x_2, y_1), count(x_2, y_2)],
+        ];
+        return Ok(Some((Measure::Phi, phi(table)?, p)));
+    }
+    Ok(Some((
+        Measure::CramersV,
+        cramers_v(chi_square, n, r, c)?,
+        p,
+    )))
+}
+
+/// Holm's step-down adjustment of every p over the whole family of pairs.
+fn holm_adjust(associations: &mut [Association]) {
+    let m = associations.len();
+    let mut order = (0..m).collect::<Vec<usize>>();
+    order.sort_by(|a, b| associations[*a].p.total_cmp(&associations[*b].p));
+    let mut running_max: f64 = 0.0;
+    for (step, index) in order.into_iter().enumerate() {
+        let adjusted = f64::min(1.0, (m - step) as f64 * associations[index].p);
+        running_max = running_max.max(adjusted);
+        associations[index].holm_p = running_max;
+    }
+}
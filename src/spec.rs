@@ -0,0 +1,211 @@
+const STATISTICS_KEY: &str = "statistic";
+const TEST_KEY: &str = "test";
+
+use crate::arg_handler::{handle_args, Cli};
+use crate::logging;
+use crate::reporter::report;
+use anyhow::{anyhow, Error, Result};
+use clap::{Arg, Command, CommandFactory, Parser, ValueHint};
+use std::fs;
+use std::path::Path;
+use toml::{Table, Value};
+
+/// Runs every statistic listed in an analysis spec, a TOML file that writes down the
+/// `stisty configure` invocations of an analysis:
+///
+/// ```toml
+/// csv = "survey.csv"
+/// missing = "listwise"
+///
+/// [[statistic]]
+/// test = "anova"
+/// categorical-column = 1
+/// continuous-column = 4
+///
+/// [[statistic]]
+/// test = "single-sample-t"
+/// column = 4
+/// mu = 3.0
+/// report = "gpa.html"
+/// ```
+///
+/// Top-level keys are `configure` options shared by every statistic; each `[[statistic]]`
+/// names its `test` (the subcommand) and takes that test's arguments, plus any
+/// `configure` option to override for it alone. Keys are the long flags, with `-` or `_`.
+/// A flag is set with `true`, a repeatable option takes an array, and relative paths are
+/// read from the spec's directory. The statistics run in order, stopping at the first
+/// that fails.
+pub fn run_spec(path: &Path) -> Result<(), Error> {
+    let contents = fs::read_to_string(path)
+        .map_err(|error| anyhow!("Could not read '{}': {}", path.display(), error))?;
+    let mut spec = contents
+        .parse::<Table>()
+        .map_err(|error| anyhow!("'{}' is not a valid spec: {}", path.display(), error))?;
+    let base_dir = path.parent().unwrap_or(Path::new(""));
+
+    let statistics = match spec.remove(STATISTICS_KEY) {
+        Some(Value::Array(statistics)) if !statistics.is_empty() => statistics,
+        _ => {
+            return Err(anyhow!(
+                "'{}' lists no statistics; add one or more [[{}]] tables",
+                path.display(),
+                STATISTICS_KEY
+            ))
+        }
+    };
+
+    for (index, statistic) in statistics.iter().enumerate() {
+        let Value::Table(statistic) = statistic else {
+            return Err(anyhow!(
+                "Statistic {} in '{}' is not a table",
+                index + 1,
+                path.display()
+            ));
+        };
+        let test = statistic
+            .get(TEST_KEY)
+            .and_then(Value::as_str)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Statistic {} in '{}' needs a `{}` naming the statistic",
+                    index + 1,
+                    path.display(),
+                    TEST_KEY
+                )
+            })?;
+        report!(
+            "{}",
+            logging::format_title(&format!("{} ({} of {})", test, index + 1, statistics.len()))
+        );
+
+        let arguments = configure_arguments(&spec, statistic, test, base_dir)
+            .and_then(|arguments| {
+                Cli::try_parse_from(arguments).map_err(|error| anyhow!(clap_message(&error)))
+            })
+            .map_err(|error| {
+                anyhow!(
+                    "Statistic {} ('{}') in '{}': {}",
+                    index + 1,
+                    test,
+                    path.display(),
+                    error
+                )
+            })?;
+        if let Some(command) = arguments.command {
+            handle_args(command)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The `stisty configure ... <test> ...` command line a spec entry stands for.
+fn configure_arguments(
+    shared: &Table,
+    statistic: &Table,
+    test: &str,
+    base_dir: &Path,
+) -> Result<Vec<String>, Error> {
+    let cli = Cli::command();
+    let configure = cli
+        .find_subcommand("configure")
+        .ok_or_else(|| anyhow!("stisty has no configure command"))?;
+    let subcommand = configure
+        .get_subcommands()
+        .find(|subcommand| {
+            subcommand.get_name() == test || subcommand.get_long_flag() == Some(test)
+        })
+        .ok_or_else(|| anyhow!("'{}' is not a statistic", test))?;
+
+    // options given with the statistic replace the shared ones
+    let mut configure_options = shared.clone();
+    let mut statistic_arguments = vec![];
+    for (key, value) in statistic.iter().filter(|(key, _)| *key != TEST_KEY) {
+        if let Some(arg) = find_arg(subcommand, key) {
+            statistic_arguments.extend(arg_values(arg, value, base_dir)?);
+        } else if find_arg(configure, key).is_some() {
+            configure_options.insert(key.clone(), value.clone());
+        } else {
+            return Err(anyhow!("'{}' is not an option of '{}'", key, test));
+        }
+    }
+
+    let mut arguments = vec![String::from("stisty"), String::from("configure")];
+    for (key, value) in configure_options.iter() {
+        let arg = find_arg(configure, key)
+            .ok_or_else(|| anyhow!("'{}' is not an option of configure", key))?;
+        arguments.extend(arg_values(arg, value, base_dir)?);
+    }
+    arguments.push(subcommand.get_name().to_string());
+    arguments.extend(statistic_arguments);
+    Ok(arguments)
+}
+
+/// The argument a spec key names, by its long flag or, for a positional, its name.
+fn find_arg<'a>(command: &'a Command, key: &str) -> Option<&'a Arg> {
+    let flag = key.replace('_', "-");
+    let id = key.replace('-', "_");
+    command.get_arguments().find(|arg| {
+        arg.get_long() == Some(flag.as_str())
+            || (arg.is_positional() && arg.get_id().as_str() == id)
+    })
+}
+
+/// Command-line words for one key: `--flag` for a `true` switch, `--option=value` for
+/// each value (so negative numbers aren't read as flags), or the bare value of a
+/// positional.
+fn arg_values(arg: &Arg, value: &Value, base_dir: &Path) -> Result<Vec<String>, Error> {
+    let flag = arg.get_long().map(|long| format!("--{}", long));
+    let name = arg.get_long().unwrap_or(arg.get_id().as_str());
+    if !arg.get_action().takes_values() {
+        return match value {
+            Value::Boolean(true) => Ok(flag.into_iter().collect()),
+            Value::Boolean(false) => Ok(vec![]),
+            _ => Err(anyhow!("'{}' is a switch; set it to true or false", name)),
+        };
+    }
+
+    let values = match value {
+        Value::Array(values) => values.iter().collect(),
+        value => vec![value],
+    };
+    values
+        .into_iter()
+        .map(|value| {
+            let mut word = match value {
+                Value::String(text) => text.clone(),
+                Value::Integer(number) => number.to_string(),
+                Value::Float(number) => number.to_string(),
+                Value::Boolean(switch) => switch.to_string(),
+                _ => return Err(anyhow!("'{}' takes a string or a number", name)),
+            };
+            let is_path = matches!(
+                arg.get_value_hint(),
+                ValueHint::AnyPath | ValueHint::FilePath | ValueHint::DirPath
+            );
+            if is_path && Path::new(&word).is_relative() {
+                word = base_dir.join(&word).to_string_lossy().to_string();
+            }
+            Ok(match &flag {
+                Some(flag) => format!("{}={}", flag, word),
+                None => word,
+            })
+        })
+        .collect()
+}
+
+/// A clap error without its "error: " prefix and usage hints, on one line.
+fn clap_message(error: &clap::Error) -> String {
+    let message = error.to_string();
+    let message = message
+        .lines()
+        .take_while(|line| !line.starts_with("Usage:"))
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("For more information"))
+        .collect::<Vec<&str>>()
+        .join(" ");
+    message
+        .strip_prefix("error: ")
+        .unwrap_or(&message)
+        .to_string()
+}
@@ -0,0 +1,226 @@
+/// The quantiles estimated for every streamed column.
+const QUARTILES: [f64; 3] = [0.25, 0.5, 0.75];
+
+use crate::functions::apa::{format_p, format_statistic};
+use crate::functions::csv::{is_missing, CSVStream};
+use crate::functions::distributions::{two_tailed_p_from_t, two_tailed_p_from_z};
+use crate::functions::stats_math::{P2Quantile, RunningStats};
+use crate::logging;
+use crate::reporter::report;
+use anyhow::{anyhow, Error, Result};
+use std::path::Path;
+
+#[derive(Debug, Clone)]
+pub struct StreamedColumn {
+    pub name: String,
+    pub index: usize,
+    pub stats: RunningStats,
+    pub quartiles: Vec<P2Quantile>, // estimates of QUARTILES
+    pub missing: usize,
+}
+
+/// Descriptives of continuous columns accumulated in one pass over a file, holding a
+/// single row at a time, so files larger than memory can be summarized and tested
+/// against a population mean.
+#[derive(Debug, Clone)]
+pub struct Stream {
+    pub input_file: String,
+    pub rows: usize,
+    pub columns: Vec<StreamedColumn>,
+}
+
+impl Stream {
+    pub fn new(input_file: &Path, stream: &mut CSVStream, columns: &[usize]) -> Result<Stream> {
+        report!("...Streaming '{}'...", input_file.display());
+        let mut streamed = columns
+            .iter()
+            .map(|&index| {
+                let name = stream.headers.get(index).ok_or_else(|| {
+                    anyhow!(
+                        "Column {} is out of range ({} columns)",
+                        index,
+                        stream.headers.len()
+                    )
+                })?;
+                Ok(StreamedColumn {
+                    name: name.clone(),
+                    index,
+                    stats: RunningStats::default(),
+                    quartiles: QUARTILES
+                        .iter()
+                        .map(|&p| P2Quantile::new(p))
+                        .collect::<Result<Vec<P2Quantile>, Error>>()?,
+                    missing: 0,
+                })
+            })
+            .collect::<Result<Vec<StreamedColumn>, Error>>()?;
+
+        let mut row_number = 0;
+        while let Some(row) = stream.next_row()? {
+            row_number += 1;
+            for column in streamed.iter_mut() {
+                let value = row.get(column.index).unwrap_or_default().trim();
+                if is_missing(value) {
+                    column.missing += 1;
+                    continue;
+                }
+                let x = value.parse::<f64>().map_err(|_| {
+                    anyhow!(
+                        "'{}' in row {} of '{}' is not a number",
+                        value,
+                        row_number,
+                        column.name
+                    )
+                })?;
+                column.stats.push(x);
+                column
+                    .quartiles
+                    .iter_mut()
+                    .for_each(|quartile| quartile.push(x));
+            }
+        }
+
+        Ok(Stream {
+            input_file: input_file.display().to_string(),
+            rows: stream.rows_read,
+            columns: streamed,
+        })
+    }
+
+    pub fn print(&self) -> Result<(), Error> {
+        report!("{}", logging::format_title("Streamed Descriptives"));
+        report!("File..........................{}", self.input_file);
+        report!("Rows..........................{}", self.rows);
+
+        let name_width = self
+            .columns
+            .iter()
+            .map(|column| column.name.chars().count())
+            .chain(std::iter::once(6))
+            .max()
+            .unwrap_or_default();
+        report!(
+            "{:<name_width$}  {:>7}  {:>7}  {}",
+            "Column",
+            "n",
+            "Missing",
+            ["Mean", "SD", "Min", "Q1", "Median", "Q3", "Max"]
+                .map(|label| format!("{:>9}", label))
+                .join("  ")
+        );
+        for column in self.columns.iter() {
+            let stats = &column.stats;
+            let range = stats.range().ok();
+            let mut values = vec![
+                stats.mean().ok(),
+                stats.standard_deviation().ok(),
+                range.map(|(min, _)| min),
+            ];
+            values.extend(
+                column
+                    .quartiles
+                    .iter()
+                    .map(|quartile| quartile.estimate().ok()),
+            );
+            values.push(range.map(|(_, max)| max));
+            report!(
+                "{:<name_width$}  {:>7}  {:>7}  {}",
+                column.name,
+                stats.n,
+                column.missing,
+                values
+                    .iter()
+                    .map(|value| match value {
+                        Some(value) => format!("{:>9.3}", value),
+                        None => format!("{:>9}", "n/a"),
+                    })
+                    .collect::<Vec<String>>()
+                    .join("  ")
+            );
+        }
+        report!("Quartiles are P² estimates from the single pass, exact for up to five values");
+        Ok(())
+    }
+
+    /// Tests each column's mean against `mu`: a z test when the population `sigma` is
+    /// known, else a single sample t test. Normality is not checked, since Shapiro-Wilk
+    /// needs every value at once.
+    pub fn print_tests(&self, mu: f64, sigma: Option<f64>) -> Result<(), Error> {
+        if sigma.is_some_and(|sigma| sigma <= 0.0) {
+            return Err(anyhow!(
+                "A z test requires a positive population standard deviation"
+            ));
+        }
+        for column in self.columns.iter() {
+            let stats = &column.stats;
+            let n = stats.n as f64;
+            let mean = stats.mean()?;
+            match sigma {
+                Some(sigma) => {
+                    // z = (x_bar - mu) / (sigma / sqrt(n)), d = (x_bar - mu) / sigma
+                    let standard_error = sigma / f64::sqrt(n);
+                    let z = (mean - mu) / standard_error;
+                    let p = two_tailed_p_from_z(z)?;
+                    report!(
+                        "{}",
+                        logging::format_title(&format!("{} Z Test", column.name))
+                    );
+                    report!(
+                        "Description: 'The mean of '{}' differs from {}.'",
+                        column.name,
+                        mu
+                    );
+                    report!("n: {}", stats.n);
+                    report!("Sample Mean: {}", mean);
+                    report!("mu: {}", mu);
+                    report!("sigma: {}", sigma);
+                    report!("Standard Error: {}", standard_error);
+                    report!("z: {}", z);
+                    report!("p (two-tailed): {}", p);
+                    report!(
+                        "APA: z = {}, {}, d = {}",
+                        format_statistic(z),
+                        format_p(p),
+                        format_statistic((mean - mu) / sigma)
+                    );
+                }
+                None => {
+                    // t = (x_bar - mu) / (s / sqrt(n)), d = (x_bar - mu) / s
+                    let standard_deviation = stats.standard_deviation()?;
+                    let standard_error = standard_deviation / f64::sqrt(n);
+                    let df = stats.n - 1;
+                    let t = (mean - mu) / standard_error;
+                    let p = two_tailed_p_from_t(t, df as f64)?;
+                    report!(
+                        "{}",
+                        logging::format_title(&format!("{} Single Sample t", column.name))
+                    );
+                    report!(
+                        "Description: 'The mean of '{}' differs from {}.'",
+                        column.name,
+                        mu
+                    );
+                    report!("n: {}", stats.n);
+                    report!("Sample Mean: {}", mean);
+                    report!("mu: {}", mu);
+                    report!("Standard Error: {}", standard_error);
+                    report!("Single Sample t = {}", t);
+                    report!("p (two-tailed): {}", p);
+                    report!(
+                        "APA: t({}) = {}, {}, d = {}",
+                        df,
+                        format_statistic(t),
+                        format_p(p),
+                        format_statistic((mean - mu) / standard_deviation)
+                    );
+                }
+            }
+        }
+        if sigma.is_none() {
+            report!(
+                "Normality is not checked when streaming; Shapiro-Wilk needs every value at once"
+            );
+        }
+        Ok(())
+    }
+}
@@ -720,7 +720,7 @@ pub fn run_glasses_occupation_likes_test() -> Result<(), Error> {
         &sleep_data_array,
     )?;
 
-    employment_sleep_independent_t.print();
+    employment_sleep_independent_t.print()?;
 
     Ok(())
 }
@@ -745,7 +745,7 @@ pub fn run_anova_sample_test() -> Result<(), Error> {
         &gpa_data_array,
     )?;
 
-    school_vs_gpa_anova.print();
+    school_vs_gpa_anova.print()?;
 
     Ok(())
 }
@@ -785,8 +785,8 @@ pub fn run_exam_3_review_test() -> Result<(), Error> {
         &november_sleep_data_array,
     )?;
 
-    drinks_vs_headphones_anova.print();
-    drinks_vs_nov_sleep.print();
+    drinks_vs_headphones_anova.print()?;
+    drinks_vs_nov_sleep.print()?;
 
     Ok(())
 }
@@ -0,0 +1,99 @@
+use crate::logging;
+use crate::reporter::report;
+use anyhow::{Error, Result};
+use std::fs;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The phase that files written during other phases are counted under.
+pub const EXPORT_PHASE: &str = "export";
+
+/// Time spent in `time_export` so far, moved out of the phase that ran it.
+static EXPORT_TIME: Mutex<Duration> = Mutex::new(Duration::ZERO);
+
+/// Runs `f`, which writes results to a file, counting its duration as export time in
+/// whichever phase calls it.
+pub fn time_export<T>(f: impl FnOnce() -> Result<T, Error>) -> Result<T, Error> {
+    let start = Instant::now();
+    let result = f();
+    *EXPORT_TIME.lock().unwrap() += start.elapsed();
+    result
+}
+
+fn export_time() -> Duration {
+    *EXPORT_TIME.lock().unwrap()
+}
+
+/// Wall-clock durations of named phases of a run, reported with peak memory
+/// when enabled (`--timing`). A disabled timer still runs each phase, it just
+/// doesn't report.
+pub struct PhaseTimer {
+    enabled: bool,
+    phases: Vec<(&'static str, Duration)>,
+}
+
+impl PhaseTimer {
+    pub fn new(enabled: bool) -> PhaseTimer {
+        PhaseTimer {
+            enabled,
+            phases: vec![],
+        }
+    }
+
+    pub fn time<T>(
+        &mut self,
+        phase: &'static str,
+        f: impl FnOnce() -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        let start = Instant::now();
+        let exported_before = export_time();
+        let result = f();
+        let exported = export_time() - exported_before;
+        self.add(phase, start.elapsed() - exported);
+        if !exported.is_zero() {
+            self.add(EXPORT_PHASE, exported);
+        }
+        result
+    }
+
+    /// Adds `duration` to `phase`, appending it the first time it's seen.
+    fn add(&mut self, phase: &'static str, duration: Duration) {
+        match self.phases.iter_mut().find(|(name, _)| *name == phase) {
+            Some((_, total)) => *total += duration,
+            None => self.phases.push((phase, duration)),
+        }
+    }
+
+    pub fn report(&self) {
+        if !self.enabled {
+            return;
+        }
+        report!("{}", logging::format_title("Timing"));
+        for (phase, duration) in &self.phases {
+            report!("..{}: {:.3} ms", phase, duration.as_secs_f64() * 1000.0);
+        }
+        let total = self
+            .phases
+            .iter()
+            .map(|(_, duration)| *duration)
+            .sum::<Duration>();
+        report!("Total: {:.3} ms", total.as_secs_f64() * 1000.0);
+        match peak_memory_bytes() {
+            Some(bytes) => report!("Peak memory: {:.1} MiB", bytes as f64 / (1024.0 * 1024.0)),
+            None => report!("Peak memory: unavailable on this platform"),
+        }
+    }
+}
+
+/// Peak resident set size of this process, from `VmHWM` in `/proc/self/status` (Linux only).
+fn peak_memory_bytes() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let kilobytes = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))?
+        .split_whitespace()
+        .next()?
+        .parse::<u64>()
+        .ok()?;
+    Some(kilobytes * 1024)
+}
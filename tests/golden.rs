@@ -0,0 +1,1150 @@
+//! Runs each statistic on the fixtures in `tests/fixtures` and compares everything it
+//! reports to the results recorded in `tests/golden`. Numbers are compared to a relative
+//! tolerance, everything else exactly. After an intended change to the output, re-record
+//! the golden files with `UPDATE_GOLDEN=1 cargo test --test golden`.
+//!
+//! The goldens only pin down what stisty printed when they were recorded, so the
+//! `*_matches_r` tests also check each statistic, its df, and its p-value against the R
+//! call given in the test's comment (with `d <- read.csv(<fixture>, check.names = FALSE)`).
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Numbers within this relative difference of the recorded value match.
+const RELATIVE_TOLERANCE: f64 = 1e-9;
+
+/// Reference values match within this relative difference; the normal-approximation
+/// p-values go through an erfc accurate to 1.2e-7.
+const R_TOLERANCE: f64 = 1e-6;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Line {
+    level: String,
+    message: String,
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Text(String),
+    Number(f64),
+}
+
+fn fixture(name: &str) -> String {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+        .to_string_lossy()
+        .to_string()
+}
+
+fn stisty_output(args: &[&str]) -> std::process::Output {
+    let scratch = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    Command::new(env!("CARGO_BIN_EXE_Stisty"))
+        .args(["--reporter", "json", "--title-width", "70"])
+        .args(args)
+        .current_dir(&scratch)
+        .env("HOME", &scratch)
+        .env("XDG_CONFIG_HOME", scratch.join(".config"))
+        .output()
+        .expect("failed to run stisty")
+}
+
+/// Runs stisty with the JSON reporter and a fixed banner width, in a scratch directory so
+/// no user config or log file is involved. Paths to the fixtures are printed as
+/// `tests/fixtures/...` so the goldens don't depend on where the repository is.
+fn run_stisty(args: &[&str]) -> Vec<Line> {
+    let output = stisty_output(args);
+    assert!(
+        output.status.success(),
+        "stisty {:?} failed:\n{}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let fixtures = fixture("");
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| {
+            let line: Line =
+                serde_json::from_str(line).expect("stisty printed a line that isn't JSON");
+            Line {
+                message: line.message.replace(&fixtures, "tests/fixtures/"),
+                ..line
+            }
+        })
+        .collect()
+}
+
+/// Runs `stisty configure` on `csv` without the cache.
+fn run_configure(csv: &str, args: &[&str]) -> Vec<Line> {
+    let csv = fixture(csv);
+    let configure = ["configure", "--csv", &csv, "--no-cache"];
+    run_stisty(&[&configure[..], args].concat())
+}
+
+/// Runs `stisty configure` on `csv`, which must fail, and returns the error it logged.
+fn configure_error(csv: &str, args: &[&str]) -> String {
+    let csv = fixture(csv);
+    let configure = ["configure", "--csv", &csv, "--no-cache"];
+    let output = stisty_output(&[&configure[..], args].concat());
+    assert!(!output.status.success(), "stisty {:?} succeeded", args);
+    String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .rev()
+        .find_map(|line| {
+            line.split_once("ERROR: ")
+                .map(|(_, error)| error.to_string())
+        })
+        .unwrap_or_else(|| panic!("stisty {:?} failed without logging an error", args))
+}
+
+/// Splits a message into runs of text and the numbers between them.
+fn tokenize(message: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut text = String::new();
+    let chars = message.chars().collect::<Vec<char>>();
+    let mut index = 0;
+    while index < chars.len() {
+        let starts_number = chars[index].is_ascii_digit()
+            || (chars[index] == '-' && chars.get(index + 1).is_some_and(char::is_ascii_digit));
+        if !starts_number {
+            text.push(chars[index]);
+            index += 1;
+            continue;
+        }
+
+        let start = index;
+        index += 1;
+        while index < chars.len()
+            && (chars[index].is_ascii_digit()
+                || (chars[index] == '.' && chars.get(index + 1).is_some_and(char::is_ascii_digit))
+                || ((chars[index] == 'e' || chars[index] == 'E')
+                    && chars
+                        .get(index + 1)
+                        .is_some_and(|next| next.is_ascii_digit() || *next == '-')))
+        {
+            index += if chars[index] == 'e' || chars[index] == 'E' {
+                2
+            } else {
+                1
+            };
+        }
+        let number = chars[start..index].iter().collect::<String>();
+        match number.parse::<f64>() {
+            Ok(value) => {
+                if !text.is_empty() {
+                    tokens.push(Token::Text(std::mem::take(&mut text)));
+                }
+                tokens.push(Token::Number(value));
+            }
+            Err(_) => text.push_str(&number),
+        }
+    }
+    if !text.is_empty() {
+        tokens.push(Token::Text(text));
+    }
+    tokens
+}
+
+fn messages_match(expected: &str, actual: &str) -> bool {
+    let expected = tokenize(expected);
+    let actual = tokenize(actual);
+    expected.len() == actual.len()
+        && expected.iter().zip(actual.iter()).all(|pair| match pair {
+            (Token::Number(x), Token::Number(y)) => {
+                x == y || (x - y).abs() <= RELATIVE_TOLERANCE * x.abs().max(y.abs())
+            }
+            (x, y) => x == y,
+        })
+}
+
+/// Compares the output of `stisty configure` to `tests/golden/<name>.json`, or rewrites
+/// the golden file when `UPDATE_GOLDEN` is set.
+fn check_golden(name: &str, csv: &str, args: &[&str]) {
+    compare_to_golden(name, run_configure(csv, args));
+}
+
+fn compare_to_golden(name: &str, lines: Vec<Line>) {
+    let golden_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{}.json", name));
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        let json = serde_json::to_string_pretty(&lines).unwrap();
+        fs::write(&golden_path, json + "\n").expect("failed to write the golden file");
+        return;
+    }
+
+    let golden: Vec<Line> = serde_json::from_str(
+        &fs::read_to_string(&golden_path)
+            .unwrap_or_else(|_| panic!("missing {}", golden_path.display())),
+    )
+    .expect("the golden file isn't valid JSON");
+    for (index, (expected, actual)) in golden.iter().zip(lines.iter()).enumerate() {
+        assert!(
+            expected.level == actual.level && messages_match(&expected.message, &actual.message),
+            "{}: line {} differs\n  expected: {:?}\n    actual: {:?}",
+            name,
+            index + 1,
+            expected,
+            actual
+        );
+    }
+    assert_eq!(
+        golden.len(),
+        lines.len(),
+        "{}: expected {} lines, got {}",
+        name,
+        golden.len(),
+        lines.len()
+    );
+}
+
+/// Runs `stisty configure` on `csv` and checks the first number after each prefix against
+/// the value R reports. Prefixes are found in order, so a repeated label like `..p: `
+/// refers to the next occurrence after the previous match. Where stisty prints a p-value
+/// only rounded in the APA line, the df is checked there instead.
+fn check_against_r(csv: &str, args: &[&str], expected: &[(&str, f64)]) {
+    let lines = run_configure(csv, args);
+    let mut from = 0;
+    for (prefix, value) in expected {
+        let index = lines[from..]
+            .iter()
+            .position(|line| line.message.starts_with(prefix))
+            .unwrap_or_else(|| {
+                panic!(
+                    "{:?}: no line after {} starts with {:?}",
+                    args, from, prefix
+                )
+            })
+            + from;
+        let actual = tokenize(&lines[index].message[prefix.len()..])
+            .into_iter()
+            .find_map(|token| match token {
+                Token::Number(number) => Some(number),
+                Token::Text(_) => None,
+            })
+            .unwrap_or_else(|| panic!("no number in {:?}", lines[index].message));
+        assert!(
+            (actual - value).abs() <= R_TOLERANCE * value.abs(),
+            "{:?}: {:?} is {}, R gives {}",
+            args,
+            prefix,
+            actual,
+            value
+        );
+        from = index + 1;
+    }
+}
+
+#[test]
+fn tokenize_separates_numbers_from_text() {
+    assert_eq!(
+        tokenize("..F(2, 21): 8.9e-1 at -3"),
+        vec![
+            Token::Text(String::from("..F(")),
+            Token::Number(2.0),
+            Token::Text(String::from(", ")),
+            Token::Number(21.0),
+            Token::Text(String::from("): ")),
+            Token::Number(0.89),
+            Token::Text(String::from(" at ")),
+            Token::Number(-3.0),
+        ]
+    );
+    assert!(messages_match("p: 0.1", "p: 0.10000000000000001"));
+    assert!(!messages_match("p: 0.1", "p: 0.2"));
+}
+
+#[test]
+fn z_test() {
+    check_golden(
+        "z_test",
+        "anova_sample.csv",
+        &["z-test", "--column", "4", "--mu", "3", "--sigma", "0.5"],
+    );
+}
+
+#[test]
+fn single_sample_t() {
+    check_golden(
+        "single_sample_t",
+        "anova_sample.csv",
+        &["single-sample-t", "--column", "4", "--mu", "3"],
+    );
+}
+
+#[test]
+fn paired_samples_t() {
+    check_golden(
+        "paired_samples_t",
+        "glasses_occupation_likes.csv",
+        &["paired-samples-t", "--column-x", "6", "--column-y", "7"],
+    );
+}
+
+#[test]
+fn t_tests_reject_values_without_spread() {
+    assert_eq!(
+        configure_error(
+            "constant_scores.csv",
+            &["single-sample-t", "--column", "1", "--mu", "3"]
+        ),
+        "All values are equal--cannot run 'Single Sample t'"
+    );
+    assert_eq!(
+        configure_error(
+            "constant_scores.csv",
+            &["paired-samples-t", "--column-x", "1", "--column-y", "2"]
+        ),
+        "The paired differences have no spread--cannot run 'Paired Sample t'"
+    );
+    assert_eq!(
+        configure_error(
+            "constant_scores.csv",
+            &[
+                "independent-groups-t",
+                "--categorical-column",
+                "0",
+                "--continuous-column",
+                "1"
+            ]
+        ),
+        "The values within each level have no spread--cannot run 'Independent Groups t'"
+    );
+}
+
+#[test]
+fn t_tests_reject_too_few_values() {
+    let one_row = ["--filter", "Participant == 1"];
+    assert_eq!(
+        configure_error(
+            "anova_sample.csv",
+            &[
+                &one_row[..],
+                &["single-sample-t", "--column", "4", "--mu", "3"]
+            ]
+            .concat()
+        ),
+        "At least two values are required--cannot run 'Single Sample t'"
+    );
+    assert_eq!(
+        configure_error(
+            "anova_sample.csv",
+            &[
+                &one_row[..],
+                &["paired-samples-t", "--column-x", "5", "--column-y", "6"]
+            ]
+            .concat()
+        ),
+        "At least two pairs are required--cannot run 'Paired Sample t'"
+    );
+}
+
+#[test]
+fn independent_groups_t() {
+    check_golden(
+        "independent_groups_t",
+        "anova_sample.csv",
+        &[
+            "independent-groups-t",
+            "--categorical-column",
+            "2",
+            "--continuous-column",
+            "4",
+        ],
+    );
+}
+
+#[test]
+fn anova() {
+    check_golden(
+        "anova",
+        "anova_sample.csv",
+        &[
+            "anova",
+            "--categorical-column",
+            "1",
+            "--continuous-column",
+            "4",
+        ],
+    );
+}
+
+#[test]
+fn anova_by_column_name() {
+    check_golden(
+        "anova",
+        "anova_sample.csv",
+        &[
+            "anova",
+            "--categorical-column-name",
+            "school",
+            "--continuous-column-name",
+            "GPA",
+        ],
+    );
+}
+
+#[test]
+fn anova_with_labels() {
+    check_golden(
+        "anova_with_labels",
+        "anova_sample.csv",
+        &[
+            "anova",
+            "--categorical-column",
+            "1",
+            "--continuous-column",
+            "4",
+            "--name",
+            "GPA by School",
+            "--description",
+            "Mean GPA differs between schools.",
+        ],
+    );
+}
+
+#[test]
+fn joined() {
+    check_golden(
+        "joined",
+        "anova_sample.csv",
+        &[
+            "--join",
+            &fixture("anova_sample_sleep.csv"),
+            "--join-on",
+            "participant",
+            "correlation",
+            "--column-x",
+            "4",
+            "--column-y-name",
+            "sleep",
+        ],
+    );
+}
+
+#[test]
+fn filtered() {
+    check_golden(
+        "filtered",
+        "anova_sample.csv",
+        &[
+            "--filter",
+            "School != 'CSU' && !(Netflix > 20)",
+            "independent-groups-t",
+            "--categorical-column",
+            "1",
+            "--continuous-column",
+            "4",
+        ],
+    );
+}
+
+#[test]
+fn flagged_outliers() {
+    check_golden(
+        "flagged_outliers",
+        "anova_sample.csv",
+        &[
+            "--flag-outliers",
+            "iqr",
+            "single-sample-t",
+            "--column",
+            "5",
+            "--mu",
+            "3",
+        ],
+    );
+}
+
+#[test]
+fn byte_order_mark() {
+    check_golden(
+        "byte_order_mark",
+        "school_gpa_bom.csv",
+        &[
+            "independent-groups-t",
+            "--categorical-column-name",
+            "School",
+            "--continuous-column-name",
+            "GPA",
+        ],
+    );
+}
+
+#[test]
+fn latin1() {
+    check_golden(
+        "latin1",
+        "city_gpa_latin1.csv",
+        &[
+            "independent-groups-t",
+            "--categorical-column-name",
+            "Ciudad",
+            "--continuous-column-name",
+            "GPA",
+        ],
+    );
+}
+
+#[test]
+fn clustered() {
+    check_golden(
+        "clustered",
+        "sleep_mood_nights.csv",
+        &[
+            "correlation",
+            "--column-x-name",
+            "Sleep",
+            "--column-y-name",
+            "Mood",
+            "--cluster-name",
+            "Subject",
+            "+",
+            "multi-regression",
+            "--dependent-column",
+            "3",
+            "--predictor-columns",
+            "2",
+            "--cluster",
+            "0",
+        ],
+    );
+}
+
+#[test]
+fn polynomial_regression() {
+    check_golden(
+        "polynomial_regression",
+        "anova_sample.csv",
+        &[
+            "multi-regression",
+            "--formula",
+            "GPA ~ Netflix + Longboards + Netflix:Longboards + Netflix^2",
+        ],
+    );
+}
+
+#[test]
+fn count_regression() {
+    check_golden(
+        "count_regression",
+        "soda_bathroom.csv",
+        &[
+            "count-regression",
+            "`Trips to Bathroom` ~ `Ounces of Soda`",
+            "+",
+            "count-regression",
+            "`Trips to Bathroom` ~ `Ounces of Soda`",
+            "--model",
+            "poisson",
+        ],
+    );
+}
+
+#[test]
+fn charts() {
+    check_golden(
+        "charts",
+        "anova_sample.csv",
+        &[
+            "histogram",
+            "--column-name",
+            "GPA",
+            "+",
+            "histogram",
+            "--column-name",
+            "GPA",
+            "--bins",
+            "5",
+            "+",
+            "boxplot",
+            "--continuous-column-name",
+            "GPA",
+            "--categorical-column-name",
+            "School",
+            "+",
+            "boxplot",
+            "--continuous-column-name",
+            "Netflix",
+        ],
+    );
+}
+
+#[test]
+fn exported_charts() {
+    check_golden(
+        "exported_charts",
+        "anova_sample.csv",
+        &[
+            "histogram",
+            "--column-name",
+            "GPA",
+            "--chart-out",
+            "charts/gpa.svg",
+            "+",
+            "boxplot",
+            "--continuous-column-name",
+            "GPA",
+            "--categorical-column-name",
+            "School",
+            "--chart-out",
+            "charts/gpa-by-school.png",
+        ],
+    );
+}
+
+#[test]
+fn terminal_charts() {
+    check_golden(
+        "terminal_charts",
+        "anova_sample.csv",
+        &[
+            "--plot-backend",
+            "terminal",
+            "histogram",
+            "--column-name",
+            "GPA",
+            "--bins",
+            "5",
+            "+",
+            "boxplot",
+            "--continuous-column-name",
+            "GPA",
+            "--categorical-column-name",
+            "School",
+        ],
+    );
+}
+
+#[test]
+fn ordinal_regression() {
+    check_golden(
+        "ordinal_regression",
+        "satisfaction_likert.csv",
+        &[
+            "ordinal-regression",
+            "Satisfaction ~ Tenure + Department",
+            "--levels",
+            "Disagree,Neutral,Agree",
+        ],
+    );
+}
+
+#[test]
+fn recoded() {
+    check_golden(
+        "recoded",
+        "anova_sample.csv",
+        &[
+            "--recode",
+            "CU Boulder,CU Denver=>CU",
+            "--reference",
+            "CU",
+            "formula",
+            "GPA ~ School",
+        ],
+    );
+}
+
+#[test]
+fn pivoted() {
+    check_golden(
+        "pivoted",
+        "reaction_times_long.csv",
+        &[
+            "--pivot",
+            "subject",
+            "--pivot-key",
+            "session",
+            "--pivot-value",
+            "rt",
+            "paired-samples-t",
+            "--column-x-name",
+            "morning",
+            "--column-y-name",
+            "evening",
+        ],
+    );
+}
+
+#[test]
+fn melted() {
+    check_golden(
+        "melted",
+        "glasses_occupation_likes.csv",
+        &["--melt", "6..7", "inspect"],
+    );
+}
+
+#[test]
+fn anova_from_json_lines() {
+    check_golden(
+        "anova",
+        "anova_sample.jsonl",
+        &[
+            "anova",
+            "--categorical-column",
+            "1",
+            "--continuous-column",
+            "4",
+        ],
+    );
+}
+
+#[test]
+fn chained() {
+    check_golden(
+        "chained",
+        "anova_sample.csv",
+        &[
+            "anova",
+            "--categorical-column",
+            "1",
+            "--continuous-column",
+            "4",
+            "+",
+            "kruskal-wallis",
+            "--categorical-column",
+            "1",
+            "--continuous-column",
+            "4",
+        ],
+    );
+}
+
+#[test]
+fn missing_by_analysis() {
+    check_golden(
+        "missing_by_analysis",
+        "partial_scores.csv",
+        &[
+            "--missing",
+            "analysis",
+            "single-sample-t",
+            "--column",
+            "1",
+            "--mu",
+            "3",
+            "+",
+            "correlation",
+            "--column-x",
+            "1",
+            "--column-y",
+            "2",
+        ],
+    );
+}
+
+#[test]
+fn mann_whitney_u() {
+    check_golden(
+        "mann_whitney_u",
+        "anova_sample.csv",
+        &[
+            "mann-whitney-u",
+            "--categorical-column",
+            "2",
+            "--continuous-column",
+            "4",
+        ],
+    );
+}
+
+#[test]
+fn kruskal_wallis() {
+    check_golden(
+        "kruskal_wallis",
+        "glasses_occupation_likes.csv",
+        &[
+            "kruskal-wallis",
+            "--categorical-column",
+            "1",
+            "--continuous-column",
+            "4",
+        ],
+    );
+}
+
+#[test]
+fn correlation() {
+    check_golden(
+        "correlation",
+        "glasses_occupation_likes.csv",
+        &["correlation", "--column-x", "4", "--column-y", "5"],
+    );
+}
+
+#[test]
+fn wilcoxon_signed_rank() {
+    check_golden(
+        "wilcoxon_signed_rank",
+        "glasses_occupation_likes.csv",
+        &["wilcoxon-signed-rank", "--column-x", "6", "--column-y", "7"],
+    );
+}
+
+#[test]
+fn wilcoxon_signed_rank_without_negative_differences() {
+    check_golden(
+        "wilcoxon_signed_rank_without_negative_differences",
+        "increasing_pairs.csv",
+        &["wilcoxon-signed-rank", "--column-x", "0", "--column-y", "1"],
+    );
+}
+
+#[test]
+fn multi_regression() {
+    check_golden(
+        "multi_regression",
+        "anova_sample.csv",
+        &[
+            "multi-regression",
+            "--dependent-column",
+            "4",
+            "--predictor-columns",
+            "5",
+            "--predictor-columns",
+            "6",
+        ],
+    );
+}
+
+#[test]
+fn multiple_response() {
+    check_golden(
+        "multiple_response",
+        "streaming_survey.csv",
+        &["multiple-response", "--column", "1", "--by", "2"],
+    );
+}
+
+#[test]
+fn formula() {
+    check_golden(
+        "formula",
+        "anova_sample.csv",
+        &["formula", "GPA ~ School * Netflix"],
+    );
+}
+
+#[test]
+fn run_spec() {
+    compare_to_golden("run_spec", run_stisty(&["run", &fixture("analysis.toml")]));
+}
+
+#[test]
+fn inspect() {
+    check_golden("inspect", "glasses_occupation_likes.csv", &["inspect"]);
+}
+
+#[test]
+fn overview() {
+    compare_to_golden(
+        "overview",
+        run_stisty(&[
+            "overview",
+            "--csv",
+            &fixture("glasses_occupation_likes.csv"),
+        ]),
+    );
+}
+
+#[test]
+fn scan() {
+    compare_to_golden(
+        "scan",
+        run_stisty(&[
+            "scan",
+            "--csv",
+            &fixture("glasses_occupation_likes.csv"),
+            "--exclude-column",
+            "0",
+        ]),
+    );
+}
+
+#[test]
+fn stream() {
+    compare_to_golden(
+        "stream",
+        run_stisty(&[
+            "stream",
+            "--csv",
+            &fixture("anova_sample.csv"),
+            "--column",
+            "4",
+            "--column-name",
+            "netflix",
+            "--mu",
+            "3",
+        ]),
+    );
+}
+
+#[test]
+fn detected_delimiter() {
+    let mut lines = run_configure(
+        "streaming_survey_semicolons.csv",
+        &["multiple-response", "--column", "1", "--by", "2"],
+    );
+    let detected = lines.remove(0);
+    assert_eq!(
+        detected.message,
+        "Reading 'tests/fixtures/streaming_survey_semicolons.csv' as semicolon-delimited"
+    );
+    compare_to_golden("multiple_response", lines);
+}
+
+// t.test(d$GPA, mu = 3)
+#[test]
+fn single_sample_t_matches_r() {
+    check_against_r(
+        "anova_sample.csv",
+        &["single-sample-t", "--column", "4", "--mu", "3"],
+        &[
+            ("Single Sample t = ", 0.6181859317016574),
+            ("APA: t(", 23.0),
+        ],
+    );
+}
+
+// z <- (mean(d$GPA) - 3) / (0.5 / sqrt(24)); 2 * pnorm(-abs(z))
+#[test]
+fn z_test_matches_r() {
+    check_against_r(
+        "anova_sample.csv",
+        &["z-test", "--column", "4", "--mu", "3", "--sigma", "0.5"],
+        &[
+            ("z: ", 0.7348469228349535),
+            ("p (two-tailed): ", 0.4624327264504763),
+        ],
+    );
+}
+
+// stisty takes y - x, so this is x and y swapped:
+// t.test(d$`Liking for Friends`, d$`Liking for Stranger Things`, paired = TRUE)
+#[test]
+fn paired_samples_t_matches_r() {
+    check_against_r(
+        "glasses_occupation_likes.csv",
+        &["paired-samples-t", "--column-x", "6", "--column-y", "7"],
+        &[("Paired Sample t = ", -3.82266686423068), ("APA: t(", 23.0)],
+    );
+}
+
+// t.test(GPA ~ Major, data = d, var.equal = TRUE)
+// car::leveneTest(GPA ~ Major, data = d, center = mean)
+// car::leveneTest(GPA ~ Major, data = d, center = median)
+#[test]
+fn independent_groups_t_matches_r() {
+    check_against_r(
+        "anova_sample.csv",
+        &[
+            "independent-groups-t",
+            "--categorical-column",
+            "2",
+            "--continuous-column",
+            "4",
+        ],
+        &[
+            ("Independent Groups t: ", -0.8888505209644558),
+            ("APA: t(", 22.0),
+            ("..F(1, 22): ", 1.041496645687807),
+            ("..p: ", 0.318565728700376),
+            ("..F(1, 22): ", 0.4708904109589041),
+            ("..p: ", 0.49974673701601413),
+        ],
+    );
+}
+
+// summary(aov(GPA ~ School, data = d))
+// car::leveneTest(GPA ~ School, data = d, center = mean)
+// car::leveneTest(GPA ~ School, data = d, center = median)
+#[test]
+fn anova_matches_r() {
+    check_against_r(
+        "anova_sample.csv",
+        &[
+            "anova",
+            "--categorical-column",
+            "1",
+            "--continuous-column",
+            "4",
+        ],
+        &[
+            ("dfB: ", 2.0),
+            ("dfW: ", 21.0),
+            ("F: ", 8.911262798634812),
+            ("..F(2, 21): ", 0.12146118721461187),
+            ("..p: ", 0.8862430501070605),
+            ("..F(2, 21): ", 0.04208416833667335),
+            ("..p: ", 0.9588697268756494),
+        ],
+    );
+}
+
+// wilcox.test(GPA ~ Major, data = d, exact = FALSE, correct = TRUE)
+#[test]
+fn mann_whitney_u_matches_r() {
+    check_against_r(
+        "anova_sample.csv",
+        &[
+            "mann-whitney-u",
+            "--categorical-column",
+            "2",
+            "--continuous-column",
+            "4",
+        ],
+        &[
+            ("z: ", -0.8406372884358352),
+            ("Mann-Whitney U: ", 57.0),
+            (
+                "p (two-tailed, normal approximation): ",
+                0.40055116243120975,
+            ),
+        ],
+    );
+}
+
+// kruskal.test(Sleep ~ Eyewear, data = d)
+#[test]
+fn kruskal_wallis_matches_r() {
+    check_against_r(
+        "glasses_occupation_likes.csv",
+        &[
+            "kruskal-wallis",
+            "--categorical-column",
+            "1",
+            "--continuous-column",
+            "4",
+        ],
+        &[
+            ("df: ", 2.0),
+            ("Kruskal-Wallis H: ", 10.761368963900612),
+            ("p (chi-square approximation): ", 0.004604669038223301),
+        ],
+    );
+}
+
+// cor.test(d$Sleep, d$Screentime, method = "pearson")
+// cor.test(d$Sleep, d$Screentime, method = "spearman", exact = FALSE)
+// cor.test(d$Sleep, d$Screentime, method = "kendall", exact = FALSE, continuity = FALSE)
+#[test]
+fn correlation_matches_r() {
+    check_against_r(
+        "glasses_occupation_likes.csv",
+        &["correlation", "--column-x", "4", "--column-y", "5"],
+        &[
+            ("df: ", 22.0),
+            ("Pearson r: ", 0.1708930114891278),
+            ("..t: ", 0.8135265828962707),
+            ("..p (two-tailed): ", 0.42463391701605724),
+            ("Spearman rho: ", 0.197003811019819),
+            ("..p (two-tailed): ", 0.35617222383246394),
+            ("Kendall tau-b: ", 0.17089490666612614),
+            ("..z: ", 1.0288129496050702),
+            (
+                "..p (two-tailed, normal approximation): ",
+                0.30356758037131165,
+            ),
+        ],
+    );
+}
+
+// stisty's W+ is R's V with x and y swapped (stisty takes y - x):
+// wilcox.test(d$`Liking for Friends`, d$`Liking for Stranger Things`, paired = TRUE,
+//             exact = FALSE, correct = TRUE)
+#[test]
+fn wilcoxon_signed_rank_matches_r() {
+    check_against_r(
+        "glasses_occupation_likes.csv",
+        &["wilcoxon-signed-rank", "--column-x", "6", "--column-y", "7"],
+        &[
+            ("n (non-zero differences): ", 19.0),
+            ("z: ", -2.9254035564018346),
+            ("Wilcoxon W: ", 22.5),
+            (
+                "p (two-tailed, normal approximation): ",
+                0.0034400978879431237,
+            ),
+        ],
+    );
+}
+
+// summary(lm(GPA ~ Netflix + Longboards, data = d))
+#[test]
+fn multi_regression_matches_r() {
+    check_against_r(
+        "anova_sample.csv",
+        &[
+            "multi-regression",
+            "--dependent-column",
+            "4",
+            "--predictor-columns",
+            "5",
+            "--predictor-columns",
+            "6",
+        ],
+        &[
+            ("..b: ", 2.789189973956588),
+            ("..t: ", 11.342085555918372),
+            ("..p (two-tailed): ", 2.044919473396337e-10),
+            ("..b: ", 0.01973760034307616),
+            ("..t: ", 1.2157364039409764),
+            ("..p (two-tailed): ", 0.23757598629722634),
+            ("..b: ", 0.0680854295376066),
+            ("..t: ", 0.7367680295988571),
+            ("..p (two-tailed): ", 0.4694151949047064),
+            ("df (regression)", 2.0),
+            ("df (error)", 21.0),
+            ("F.", 0.9637596877075457),
+            ("p.", 0.3976979651193872),
+            ("R^2.", 0.08407012306276569),
+        ],
+    );
+}
+
+// anova(lm(GPA ~ School * Netflix, data = d))
+#[test]
+fn formula_matches_r() {
+    check_against_r(
+        "anova_sample.csv",
+        &["formula", "GPA ~ School * Netflix"],
+        &[
+            ("F.", 4.8218081766846215),
+            ("p.", 0.005681311527008465),
+            ("..F: ", 9.665644461241119),
+            ("..p: ", 0.0014086259404741295),
+            ("..F: ", 0.21144201950004032),
+            ("..p: ", 0.6511450464487808),
+            ("..F: ", 2.2831549707204135),
+            ("..p: ", 0.13070963658454304),
+        ],
+    );
+}
+
+// s <- sapply(c("Netflix", "Hulu", "Disney+"), grepl, x = d$Services, fixed = TRUE)
+// DescTools::CochranQTest(s)
+// chisq.test(table(s[, "Netflix"], d$`Age Group`), correct = FALSE), and so on
+#[test]
+fn multiple_response_matches_r() {
+    check_against_r(
+        "streaming_survey.csv",
+        &["multiple-response", "--column", "1", "--by", "2"],
+        &[
+            ("Cochran's Q", 1.2727272727272727),
+            ("df.", 2.0),
+            ("p.", 0.5292133415000503),
+            ("..Chi-square(1): ", 1.5),
+            ("..p: ", 0.2206713619198468),
+            ("..Chi-square(1): ", 0.34285714285714286),
+            ("..p: ", 0.5581846494226573),
+            ("..Chi-square(1): ", 3.085714285714286),
+            ("..p: ", 0.07898257926378298),
+        ],
+    );
+}